@@ -1,7 +1,8 @@
-use std::{collections::HashSet, sync::Arc};
+use std::sync::Arc;
 
 use rmcp::{
     ErrorData, RoleServer, ServerHandler,
+    handler::server::subscription::SubscriptionManager,
     model::*,
     service::RequestContext,
     transport::{
@@ -28,14 +29,14 @@ fn json_object(v: Value) -> JsonObject {
 
 #[derive(Clone)]
 struct ConformanceServer {
-    subscriptions: Arc<Mutex<HashSet<String>>>,
+    subscriptions: SubscriptionManager,
     log_level: Arc<Mutex<LoggingLevel>>,
 }
 
 impl ConformanceServer {
     fn new() -> Self {
         Self {
-            subscriptions: Arc::new(Mutex::new(HashSet::new())),
+            subscriptions: SubscriptionManager::new(),
             log_level: Arc::new(Mutex::new(LoggingLevel::Debug)),
         }
     }
@@ -646,20 +647,18 @@ impl ServerHandler for ConformanceServer {
     async fn subscribe(
         &self,
         request: SubscribeRequestParams,
-        _cx: RequestContext<RoleServer>,
+        cx: RequestContext<RoleServer>,
     ) -> Result<(), ErrorData> {
-        let mut subs = self.subscriptions.lock().await;
-        subs.insert(request.uri.to_string());
+        self.subscriptions.subscribe(request.uri, &cx.peer);
         Ok(())
     }
 
     async fn unsubscribe(
         &self,
         request: UnsubscribeRequestParams,
-        _cx: RequestContext<RoleServer>,
+        cx: RequestContext<RoleServer>,
     ) -> Result<(), ErrorData> {
-        let mut subs = self.subscriptions.lock().await;
-        subs.remove(request.uri.as_str());
+        self.subscriptions.unsubscribe(&request.uri, &cx.peer);
         Ok(())
     }
 
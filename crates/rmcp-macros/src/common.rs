@@ -1,7 +1,9 @@
 //! Common utilities shared between different macro implementations
 
+use darling::{FromMeta, ast::NestedMeta};
+use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Attribute, Expr, FnArg, ImplItem, ImplItemFn, ItemImpl, Signature, Type};
+use syn::{Attribute, Expr, FnArg, ImplItem, ImplItemFn, ItemImpl, LitStr, Signature, Type};
 
 /// Parse a None expression
 pub fn none_expr() -> syn::Result<Expr> {
@@ -96,3 +98,42 @@ pub fn has_sibling_handler(item_impl: &ItemImpl, handler_name: &str) -> bool {
             .is_some_and(|seg| seg.ident == handler_name)
     })
 }
+
+/// Parse the arguments of a `#[name]` or `#[name(...)]` attribute found on a method.
+pub fn parse_attribute_args<T: FromMeta + Default>(attr: &Attribute) -> syn::Result<T> {
+    match &attr.meta {
+        syn::Meta::Path(_) => Ok(T::default()),
+        syn::Meta::List(list) => {
+            let attr_args = NestedMeta::parse_meta_list(list.tokens.clone())?;
+            Ok(T::from_list(&attr_args)?)
+        }
+        syn::Meta::NameValue(meta) => {
+            let name = meta.path.segments.last().map(|seg| seg.ident.to_string());
+            let name = name.unwrap_or_default();
+            Err(syn::Error::new_spanned(
+                attr,
+                format!("expected `#[{name}]` or `#[{name}(...)]`"),
+            ))
+        }
+    }
+}
+
+/// The `.with_scopes([...])` call for a `scopes = [...]` argument, or nothing
+/// when no scopes are given.
+pub fn scopes_call(scopes: &[LitStr]) -> syn::Result<TokenStream> {
+    for scope in scopes {
+        let value = scope.value();
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            return Err(syn::Error::new(
+                scope.span(),
+                format!(
+                    "Invalid scope '{value}'. Scopes must be non-empty and contain no whitespace"
+                ),
+            ));
+        }
+    }
+    if scopes.is_empty() {
+        return Ok(TokenStream::new());
+    }
+    Ok(quote! { .with_scopes([#(#scopes),*]) })
+}
@@ -0,0 +1,131 @@
+use darling::{FromMeta, ast::NestedMeta};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ImplItemFn;
+
+use crate::resource::{box_async_fn, template_variable_names};
+
+#[derive(FromMeta, Default, Debug)]
+#[darling(default)]
+pub struct CompletionAttribute {
+    /// The name of the prompt whose argument is completed
+    pub prompt: Option<String>,
+    /// The URI template of the resource template whose variable is completed
+    pub resource: Option<String>,
+    /// The prompt argument or template variable to complete
+    pub argument: Option<String>,
+    /// When true, the generated future will not require `Send`. Useful for `!Send` handlers
+    /// (e.g. single-threaded database connections). Also enabled globally by the `local` crate feature.
+    pub local: bool,
+}
+
+pub fn completion(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = NestedMeta::parse_meta_list(attr)?;
+        CompletionAttribute::from_list(&attr_args)?
+    };
+    let mut fn_item = syn::parse2::<ImplItemFn>(input)?;
+    let fn_ident = fn_item.sig.ident.clone();
+
+    let Some(argument) = attribute.argument else {
+        return Err(syn::Error::new_spanned(
+            &fn_ident,
+            "#[completion] requires an `argument`, e.g. #[completion(prompt = \"code_review\", argument = \"language\")]",
+        ));
+    };
+    let target = match (attribute.prompt, attribute.resource) {
+        (Some(prompt), None) => quote! {
+            rmcp::handler::server::router::completion::CompletionTarget::prompt(#prompt, #argument)
+        },
+        (None, Some(uri_template)) => {
+            if !template_variable_names(&uri_template).contains(&argument) {
+                return Err(syn::Error::new_spanned(
+                    &fn_ident,
+                    format!("`{argument}` is not a variable of the URI template `{uri_template}`"),
+                ));
+            }
+            quote! {
+                rmcp::handler::server::router::completion::CompletionTarget::resource(#uri_template, #argument)
+            }
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &fn_ident,
+                "#[completion] requires exactly one of `prompt` or `resource`",
+            ));
+        }
+    };
+
+    let target_fn_ident = format_ident!("{}_completion_target", fn_ident);
+    let doc_comment = format!("Generated completion target function for {fn_ident}");
+    let target_fn = quote! {
+        #[doc = #doc_comment]
+        pub fn #target_fn_ident() -> rmcp::handler::server::router::completion::CompletionTarget {
+            #target
+        }
+    };
+
+    box_async_fn(&mut fn_item, cfg!(feature = "local") || attribute.local)?;
+
+    Ok(quote! {
+        #target_fn
+        #fn_item
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_completion_macro() -> syn::Result<()> {
+        let attr = quote! {
+            prompt = "code_review",
+            argument = "language"
+        };
+        let input = quote! {
+            async fn complete_language(&self, value: CompletionValue) -> Vec<String> {
+                Vec::new()
+            }
+        };
+        let result = completion(attr, input)?.to_string();
+        assert!(result.contains("complete_language_completion_target"));
+        assert!(result.contains("CompletionTarget :: prompt (\"code_review\" , \"language\")"));
+        assert!(result.contains("Box :: pin"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_completion_macro_rejects_unknown_template_variable() {
+        let attr = quote! {
+            resource = "repo://{owner}/{repo}",
+            argument = "branch"
+        };
+        let input = quote! {
+            fn complete_branch(&self) -> Vec<String> {
+                Vec::new()
+            }
+        };
+        assert!(completion(attr, input).is_err());
+    }
+
+    #[test]
+    fn test_completion_macro_requires_single_target() {
+        let input = quote! {
+            fn complete(&self) -> Vec<String> {
+                Vec::new()
+            }
+        };
+        assert!(completion(quote! { argument = "language" }, input.clone()).is_err());
+        assert!(
+            completion(
+                quote! { prompt = "p", resource = "r://{x}", argument = "x" },
+                input.clone()
+            )
+            .is_err()
+        );
+        assert!(completion(quote! { prompt = "p" }, input).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, ImplItem, ItemImpl, parse_quote};
+
+use crate::{
+    common::{has_method, has_sibling_handler},
+    tool_handler::{CallerCapability, build_get_info},
+};
+
+#[derive(FromMeta, Debug, Default)]
+#[darling(default)]
+pub struct CompletionHandlerAttribute {
+    pub router: Option<Expr>,
+}
+
+pub fn completion_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = darling::ast::NestedMeta::parse_meta_list(attr)?;
+        CompletionHandlerAttribute::from_list(&attr_args)?
+    };
+
+    let mut impl_block = syn::parse2::<ItemImpl>(input)?;
+
+    let router_expr = attribute
+        .router
+        .unwrap_or_else(|| syn::parse2(quote! { Self::completion_router() }).unwrap());
+
+    if !has_method("complete", &impl_block) {
+        let complete_impl: ImplItem = parse_quote! {
+            async fn complete(
+                &self,
+                request: rmcp::model::CompleteRequestParams,
+                context: rmcp::service::RequestContext<rmcp::RoleServer>,
+            ) -> Result<rmcp::model::CompleteResult, rmcp::ErrorData> {
+                let complete_context = rmcp::handler::server::completion::CompleteContext::new(
+                    self,
+                    request,
+                    context,
+                );
+                #router_expr.complete(complete_context).await
+            }
+        };
+        impl_block.items.push(complete_impl);
+    }
+
+    // Auto-generate get_info() if not already provided and no sibling handler
+    // macro is going to generate it.
+    if !has_method("get_info", &impl_block)
+        && !has_sibling_handler(&impl_block, "tool_handler")
+        && !has_sibling_handler(&impl_block, "prompt_handler")
+        && !has_sibling_handler(&impl_block, "resource_handler")
+    {
+        let get_info_fn =
+            build_get_info(&impl_block, None, None, None, CallerCapability::Completions)?;
+        impl_block.items.push(get_info_fn);
+    }
+
+    Ok(quote! {
+        #impl_block
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_completion_handler_macro() -> syn::Result<()> {
+        let input = quote! {
+            impl ServerHandler for MyCompletionHandler {}
+        };
+
+        let result = completion_handler(TokenStream::new(), input)?;
+        let result_str = result.to_string();
+
+        assert!(result_str.contains("async fn complete"));
+        assert!(result_str.contains("Self :: completion_router () . complete"));
+        assert!(result_str.contains("enable_completions"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_completion_handler_defers_get_info_to_sibling() -> syn::Result<()> {
+        let input = quote! {
+            #[prompt_handler]
+            impl ServerHandler for MyCompletionHandler {}
+        };
+
+        let result = completion_handler(TokenStream::new(), input)?;
+        let result_str = result.to_string();
+
+        assert!(!result_str.contains("fn get_info"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ImplItem, ItemImpl, Visibility, parse_quote};
+
+#[derive(FromMeta, Debug, Default)]
+#[darling(default)]
+pub struct CompletionRouterAttribute {
+    pub router: Option<String>,
+    pub vis: Option<Visibility>,
+}
+
+pub fn completion_router(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = darling::ast::NestedMeta::parse_meta_list(attr)?;
+        CompletionRouterAttribute::from_list(&attr_args)?
+    };
+
+    let mut impl_block = syn::parse2::<ItemImpl>(input)?;
+    let self_ty = &impl_block.self_ty;
+
+    let router_fn_ident = attribute
+        .router
+        .map(|s| format_ident!("{}", s))
+        .unwrap_or_else(|| format_ident!("completion_router"));
+    let vis = attribute.vis.unwrap_or(Visibility::Inherited);
+
+    let mut route_calls = Vec::new();
+    for item in &impl_block.items {
+        if let ImplItem::Fn(fn_item) = item {
+            let has_completion_attr = fn_item.attrs.iter().any(|attr| {
+                attr.path()
+                    .segments
+                    .last()
+                    .is_some_and(|seg| seg.ident == "completion")
+            });
+            if has_completion_attr {
+                let fn_ident = &fn_item.sig.ident;
+                let target_fn_ident = format_ident!("{}_completion_target", fn_ident);
+                route_calls.push(quote! {
+                    .with_route((Self::#target_fn_ident(), Self::#fn_ident))
+                });
+            }
+        }
+    }
+
+    let router_fn: ImplItem = parse_quote! {
+        #vis fn #router_fn_ident() -> rmcp::handler::server::router::completion::CompletionRouter<#self_ty> {
+            rmcp::handler::server::router::completion::CompletionRouter::new()
+                #(#route_calls)*
+        }
+    };
+
+    impl_block.items.push(router_fn);
+
+    Ok(quote! {
+        #impl_block
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_completion_router_macro() -> syn::Result<()> {
+        let input = quote! {
+            impl MyCompletionHandler {
+                #[completion(prompt = "code_review", argument = "language")]
+                async fn complete_language(&self) -> Vec<String> {
+                    Vec::new()
+                }
+
+                fn helper(&self) {}
+            }
+        };
+
+        let result = completion_router(quote! { vis = "pub" }, input)?;
+        let result_str = result.to_string();
+
+        assert!(result_str.contains("pub fn completion_router"));
+        assert!(result_str.contains("CompletionRouter :: new"));
+        assert!(result_str.contains("complete_language_completion_target"));
+        assert!(!result_str.contains("helper_completion_target"));
+
+        Ok(())
+    }
+}
@@ -4,13 +4,22 @@
 use proc_macro::TokenStream;
 
 mod common;
+mod completion;
+mod completion_handler;
+mod completion_router;
+mod mcp_error;
 mod prompt;
 mod prompt_handler;
 mod prompt_router;
+mod resource;
+mod resource_handler;
+mod resource_router;
 mod task_handler;
 mod tool;
+mod tool_args;
 mod tool_handler;
 mod tool_router;
+mod validation;
 /// # tool
 ///
 /// This macro is used to mark a function as a tool handler.
@@ -21,10 +30,16 @@ mod tool_router;
 ///
 /// | field             | type                       | usage |
 /// | :-                | :-                         | :-    |
-/// | `name`            | `String`                   | The name of the tool. If not provided, it defaults to the function name. |
-/// | `description`     | `String`                   | A description of the tool. The document of this function will be used. |
+/// | `name`            | `String`                   | The name of the tool. If not provided, it defaults to the function name. 1 to 128 characters of `A-Z`, `a-z`, `0-9`, `_`, `-` and `.`. |
+/// | `description`     | `String`                   | A description of the tool. The document of this function will be used. Required with the `strict` feature. |
 /// | `input_schema`    | `Expr`                     | A JSON Schema object defining the expected parameters for the tool. If not provide, if will use the json schema of its argument with type `Parameters<T>` |
+/// | `output_schema`   | `Expr`                     | A JSON Schema object for the structured output. Generated for a `Json<T>` return type; when given, the tool must return `Json<T>` or `CallToolResult`. |
+/// | `execution`       | `ToolExecutionAttribute`   | `execution(task_support = "...")` with `"forbidden"`, `"optional"` or `"required"`. |
 /// | `annotations`     | `ToolAnnotationsAttribute` | Additional tool information. Defaults to `None`. |
+/// | `timeout`         | `String`                   | Abort the handler and return a tool error after this long, e.g. `"30s"`. Units: `ms`, `s`, `m`, `h`. |
+/// | `max_concurrency` | `usize`                    | Maximum number of calls of this tool running at once, shared by every instance of the server type. |
+/// | `on_busy`         | `String`                   | `"queue"` (default) waits for a free slot, `"reject"` returns a tool error. Requires `max_concurrency`. |
+/// | `scopes`          | `[String]`                 | OAuth scopes the caller must hold, e.g. `["files:write"]`. Applied by `#[tool_router]`. |
 ///
 /// ## Example
 ///
@@ -34,6 +49,42 @@ mod tool_router;
 ///     // handling tool request
 /// }
 /// ```
+///
+/// ## Plain arguments
+///
+/// Instead of a `Parameters<T>` struct, a tool can take its arguments directly. An argument is
+/// a tool argument when it is marked `#[arg]`, or when its type is a primitive, `String`,
+/// `Value`, `Option`, `Vec` or a map or set. Other arguments are extractors, such as `Peer`,
+/// `RequestContext` or `Extension<T>`. The input schema is generated from the tool arguments.
+///
+/// | `#[arg]` field | type     | usage |
+/// | :-             | :-       | :-    |
+/// | `description`  | `String` | The description of the argument in the input schema. |
+/// | `default`      | `flag`   | Use `Default::default()` when the argument is missing. |
+///
+/// ```rust,ignore
+/// #[tool(description = "Add two numbers")]
+/// async fn add(
+///     &self,
+///     #[arg(description = "The first number")] a: i64,
+///     b: Option<i64>,
+///     peer: Peer<RoleServer>,
+/// ) -> String {
+///     (a + b.unwrap_or_default()).to_string()
+/// }
+/// ```
+///
+/// The tool keeps its signature and can still be called directly. `#[tool_router]` routes to a
+/// generated, hidden `__add_tool_handler` instead, which takes a `JsonObject` in place of the
+/// arguments and returns `Result<R, ErrorData>`, so arguments that fail to deserialize are
+/// answered with an invalid params error.
+///
+/// ## Validation
+///
+/// Invalid names, unknown `task_support` values and an `output_schema` on a tool that cannot
+/// return structured content are compile errors, as are two tools of the same name in one
+/// `#[tool_router]`. With the `strict` feature (`macros-strict` in `rmcp`), so is a tool
+/// without a description.
 #[proc_macro_attribute]
 pub fn tool(attr: TokenStream, input: TokenStream) -> TokenStream {
     tool::tool(attr.into(), input.into())
@@ -43,7 +94,8 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> TokenStream {
 
 /// # tool_router
 ///
-/// This macro is used to generate a tool router based on functions marked with `#[rmcp::tool]` in an implementation block.
+/// This macro is used to generate a tool router based on functions marked with `#[rmcp::tool]` in an implementation block,
+/// a trait or an inline module.
 ///
 /// It creates a function that returns a `ToolRouter` instance.
 ///
@@ -55,7 +107,7 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// | field            | type          | usage |
 /// | :-               | :-            | :-    |
 /// | `router`         | `Ident`       | The name of the router function to be generated. Defaults to `tool_router`. |
-/// | `vis`            | `Visibility`  | The visibility of the generated router function. Defaults to empty. |
+/// | `vis`            | `Visibility`  | The visibility of the generated router function. Defaults to empty, or `pub` on a module. Not allowed on a trait. |
 /// | `server_handler` | `flag`        | When set, also emits `#[::rmcp::tool_handler]` on `impl ServerHandler for Self` so you can omit a separate `#[tool_handler]` block. |
 ///
 /// ## Example
@@ -123,6 +175,50 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
+///
+/// ### Tool sets
+///
+/// On an inline module, `#[tool_router]` collects and expands the free functions marked with
+/// `#[tool]`, so `tool` need not be imported into the module. The generated `pub fn tool_router<S>() -> ToolRouter<S>` fits any server:
+///
+/// ```rust,ignore
+/// #[tool_router]
+/// mod math {
+///     #[tool(description = "Add two numbers")]
+///     pub async fn add(a: i64, b: i64) -> String {
+///         (a + b).to_string()
+///     }
+/// }
+///
+/// #[tool_handler(router = Self::tool_router() + math::tool_router())]
+/// impl ServerHandler for MyToolHandler {}
+/// ```
+///
+/// On a trait, the tools are default methods, so every implementor shares them and may override
+/// them. The router is a provided method of the trait, `Self::tool_router()` by default:
+///
+/// ```rust,ignore
+/// #[tool_router(router = greeter_router)]
+/// trait Greeter {
+///     fn greeting(&self) -> &str;
+///
+///     #[tool(description = "Greet someone")]
+///     async fn greet(&self, name: String) -> String {
+///         format!("{}, {name}", self.greeting())
+///     }
+/// }
+///
+/// impl Greeter for MyToolHandler {
+///     fn greeting(&self) -> &str {
+///         "Hello"
+///     }
+/// }
+///
+/// #[tool_handler(router = Self::greeter_router())]
+/// impl ServerHandler for MyToolHandler {}
+/// ```
+///
+/// `server_handler` is only available on impl blocks.
 #[proc_macro_attribute]
 pub fn tool_router(attr: TokenStream, input: TokenStream) -> TokenStream {
     tool_router::tool_router(attr.into(), input.into())
@@ -208,9 +304,11 @@ pub fn tool_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// | field             | type     | usage |
 /// | :-                | :-       | :-    |
-/// | `name`            | `String` | The name of the prompt. If not provided, it defaults to the function name. |
-/// | `description`     | `String` | A description of the prompt. The document of this function will be used if not provided. |
+/// | `name`            | `String` | The name of the prompt. If not provided, it defaults to the function name. Checked like tool names, and unique within a `#[prompt_router]`. |
+/// | `description`     | `String` | A description of the prompt. The document of this function will be used if not provided. Required with the `strict` feature. |
 /// | `arguments`       | `Expr`   | An expression that evaluates to `Option<Vec<PromptArgument>>` defining the prompt's arguments. If not provided, it will automatically generate arguments from the `Parameters<T>` type found in the function signature. |
+/// | `scopes`          | `[String]` | OAuth scopes the caller must hold. Applied by `#[prompt_router]`. |
+/// | `template`        | `String` | A template file to render the prompt from, relative to the crate root. See below. |
 ///
 /// ## Example
 ///
@@ -220,6 +318,22 @@ pub fn tool_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     // Generate prompt messages based on arguments
 /// }
 /// ```
+///
+/// ## Templates
+///
+/// With `template`, the file (relative to the crate root) is included with `include_str!` and the
+/// prompt is rendered from it (see `rmcp::handler::server::prompt::template` for the syntax). The
+/// template is parsed at compile time, so a missing file or a syntax error is reported on the
+/// `template` attribute instead of at runtime. Its arguments are derived from
+/// the template's placeholders, and requests missing a required one are rejected with invalid
+/// params. The function is only a declaration: it takes `&self` alone and has an empty body.
+/// `{{@resource}}` and `{{@image}}` references are read through the server's `read_resource`.
+///
+/// ```rust,ignore
+/// /// Reviews code for best practices
+/// #[prompt(template = "prompts/review.md")]
+/// fn review(&self) {}
+/// ```
 #[proc_macro_attribute]
 pub fn prompt(attr: TokenStream, input: TokenStream) -> TokenStream {
     prompt::prompt(attr.into(), input.into())
@@ -300,6 +414,238 @@ pub fn prompt_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// # resource
+///
+/// This macro is used to mark a function as a handler for a static resource.
+///
+/// This will generate a function that returns the attribute of this resource, with type `rmcp::model::Resource`.
+///
+/// ## Usage
+///
+/// | field         | type     | usage |
+/// | :-            | :-       | :-    |
+/// | `uri`         | `String` | The URI of the resource. Required. |
+/// | `name`        | `String` | The name of the resource. If not provided, it defaults to the function name. |
+/// | `title`       | `String` | A human readable title of the resource. |
+/// | `description` | `String` | A description of the resource. The document of this function will be used if not provided. |
+/// | `mime_type`   | `String` | The MIME type of the resource content. |
+/// | `size`        | `u32`    | The size of the resource content in bytes. |
+/// | `icons`       | `Expr`   | Optional icons for the resource. |
+/// | `meta`        | `Expr`   | Optional metadata for the resource. |
+/// | `scopes`      | `[String]` | OAuth scopes the caller must hold. Applied by `#[resource_router]`. |
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[resource(uri = "file:///README.md", mime_type = "text/markdown")]
+/// async fn readme(&self) -> Result<String, ErrorData> {
+///     Ok(include_str!("../README.md").to_string())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn resource(attr: TokenStream, input: TokenStream) -> TokenStream {
+    resource::resource(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// # resource_template
+///
+/// This macro is used to mark a function as a handler for a resource template.
+///
+/// This will generate a function that returns the attribute of this template, with type
+/// `rmcp::model::ResourceTemplate`. Function arguments named after a template variable are
+/// parsed from the matched URI; other arguments are regular resource extractors.
+///
+/// ## Usage
+///
+/// | field          | type     | usage |
+/// | :-             | :-       | :-    |
+/// | `uri_template` | `String` | The RFC 6570 URI template. Required. |
+/// | `name`         | `String` | The name of the template. If not provided, it defaults to the function name. |
+/// | `title`        | `String` | A human readable title of the template. |
+/// | `description`  | `String` | A description of the template. The document of this function will be used if not provided. |
+/// | `mime_type`    | `String` | The MIME type of the resources matched by this template. |
+/// | `icons`        | `Expr`   | Optional icons for the template. |
+/// | `scopes`       | `[String]` | OAuth scopes the caller must hold. Applied by `#[resource_router]`. |
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[resource_template(uri_template = "users://{id}/profile")]
+/// async fn user_profile(&self, id: u64) -> Result<String, ErrorData> {
+///     // `id` is parsed from the requested URI
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn resource_template(attr: TokenStream, input: TokenStream) -> TokenStream {
+    resource::resource_template(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// # resource_router
+///
+/// This macro generates a resource router based on functions marked with `#[rmcp::resource]`
+/// or `#[rmcp::resource_template]` in an implementation block.
+///
+/// It creates a function that returns a `ResourceRouter` instance.
+///
+/// ## Usage
+///
+/// | field     | type          | usage |
+/// | :-        | :-            | :-    |
+/// | `router`  | `Ident`       | The name of the router function to be generated. Defaults to `resource_router`. |
+/// | `vis`     | `Visibility`  | The visibility of the generated router function. Defaults to empty. |
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[resource_router]
+/// impl MyResourceHandler {
+///     #[resource(uri = "file:///README.md")]
+///     async fn readme(&self) -> String {
+///         // ...
+///     }
+///
+///     #[resource_template(uri_template = "file:///{path}")]
+///     async fn file(&self, path: String) -> Result<String, ErrorData> {
+///         // ...
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn resource_router(attr: TokenStream, input: TokenStream) -> TokenStream {
+    resource_router::resource_router(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// # resource_handler
+///
+/// This macro generates handler methods for `read_resource`, `list_resources` and
+/// `list_resource_templates` in the implementation block, using a `ResourceRouter`. It also
+/// auto-generates `get_info()` with resources capability enabled if not already provided.
+///
+/// ## Usage
+///
+/// | field     | type   | usage |
+/// | :-        | :-     | :-    |
+/// | `router`  | `Expr` | The expression to access the `ResourceRouter` instance. Defaults to `Self::resource_router()`. |
+/// | `meta`    | `Expr` | Optional metadata for `ListResourcesResult` and `ListResourceTemplatesResult`. |
+///
+/// ## Example
+/// ```rust,ignore
+/// #[resource_handler]
+/// impl ServerHandler for MyResourceHandler {
+///     // ...implement other handler methods
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn resource_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
+    resource_handler::resource_handler(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// # completion
+///
+/// This macro is used to mark a function as a completion handler for a prompt argument or a
+/// resource template variable.
+///
+/// This will generate a function that returns the target of this handler, with type
+/// `rmcp::handler::server::router::completion::CompletionTarget`. Handler arguments are
+/// completion extractors such as `CompletionValue`, `ArgumentInfo`, `Reference` and
+/// `CompletionContext`; the latter carries the arguments the client has already resolved.
+///
+/// ## Usage
+///
+/// | field      | type     | usage |
+/// | :-         | :-       | :-    |
+/// | `prompt`   | `String` | The name of the prompt whose argument is completed. |
+/// | `resource` | `String` | The URI template whose variable is completed. |
+/// | `argument` | `String` | The argument or template variable to complete. Required. |
+///
+/// Exactly one of `prompt` and `resource` must be given.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[completion(prompt = "code_review", argument = "language")]
+/// async fn complete_language(&self, CompletionValue(value): CompletionValue) -> Vec<String> {
+///     ["rust", "python", "go"]
+///         .into_iter()
+///         .filter(|lang| lang.starts_with(&value))
+///         .map(String::from)
+///         .collect()
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn completion(attr: TokenStream, input: TokenStream) -> TokenStream {
+    completion::completion(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// # completion_router
+///
+/// This macro generates a completion router based on functions marked with
+/// `#[rmcp::completion]` in an implementation block.
+///
+/// It creates a function that returns a `CompletionRouter` instance.
+///
+/// ## Usage
+///
+/// | field     | type          | usage |
+/// | :-        | :-            | :-    |
+/// | `router`  | `Ident`       | The name of the router function to be generated. Defaults to `completion_router`. |
+/// | `vis`     | `Visibility`  | The visibility of the generated router function. Defaults to empty. |
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[completion_router]
+/// impl MyCompletionHandler {
+///     #[completion(resource = "repo://{owner}/{repo}", argument = "repo")]
+///     async fn complete_repo(&self, context: CompletionContext) -> Vec<String> {
+///         // `context.get_argument("owner")` holds the already selected owner
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn completion_router(attr: TokenStream, input: TokenStream) -> TokenStream {
+    completion_router::completion_router(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// # completion_handler
+///
+/// This macro generates the `complete` handler method in the implementation block, using a
+/// `CompletionRouter`. Requests for targets without a registered handler get an empty
+/// completion. It also auto-generates `get_info()` with completions capability enabled if not
+/// already provided.
+///
+/// ## Usage
+///
+/// | field     | type   | usage |
+/// | :-        | :-     | :-    |
+/// | `router`  | `Expr` | The expression to access the `CompletionRouter` instance. Defaults to `Self::completion_router()`. |
+///
+/// ## Example
+/// ```rust,ignore
+/// #[completion_handler]
+/// impl ServerHandler for MyCompletionHandler {
+///     // ...implement other handler methods
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn completion_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
+    completion_handler::completion_handler(attr.into(), input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// # task_handler
 ///
 /// Generates basic task-handling methods (`enqueue_task` and `list_tasks`) for a server handler
@@ -313,3 +659,55 @@ pub fn task_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// # McpError
+///
+/// Derive the conversion of an error enum to `rmcp::ErrorData`, and
+/// `IntoCallToolResult`, so tools can return `Result<T, MyError>`.
+///
+/// ## Usage
+///
+/// On variants:
+///
+/// | field        | type     | usage |
+/// | :-           | :-       | :-    |
+/// | `code`       | `i32`    | The JSON-RPC error code. Defaults to `-32603`, internal error. |
+/// | `tool_error` | `flag`   | Tools return this variant as a `CallToolResult` with `is_error` set, instead of a JSON-RPC error. Elsewhere it still converts to `ErrorData` with `code`. |
+/// | `message`    | `String` | The error message, with `{field}` or `{0}` placeholders for the variant's fields. Defaults to the `Display` of the error. |
+///
+/// On fields:
+///
+/// | field  | type   | usage |
+/// | :-     | :-     | :-    |
+/// | `data` | `flag` | Serialize this field with serde into the error's `data`. A `tool_error` result carries it as a second, JSON text content block after the message; `structured_content` is left unset, as it must match the tool's `output_schema`. At most one per variant. |
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[derive(Debug, thiserror::Error, McpError)]
+/// enum UserError {
+///     #[error("no user {0}")]
+///     #[mcp(code = -32602)]
+///     UnknownUser(String),
+///     #[error("rate limited")]
+///     #[mcp(tool_error, message = "rate limited, retry in {retry_after}s")]
+///     RateLimited {
+///         #[mcp(data)]
+///         retry_after: u64,
+///     },
+///     #[error("database error: {0}")]
+///     #[mcp(message = "database unavailable")]
+///     Database(String),
+/// }
+///
+/// #[tool(description = "Look up a user")]
+/// async fn user(&self, Parameters(id): Parameters<String>) -> Result<String, UserError> {
+///     // ...
+/// }
+/// ```
+#[proc_macro_derive(McpError, attributes(mcp))]
+pub fn mcp_error(input: TokenStream) -> TokenStream {
+    mcp_error::derive_mcp_error(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
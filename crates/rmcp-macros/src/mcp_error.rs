@@ -0,0 +1,247 @@
+use darling::{FromDeriveInput, FromField, FromVariant, ast::Style};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Expr, Ident, LitStr};
+
+#[derive(FromDeriveInput, Debug)]
+#[darling(attributes(mcp), supports(enum_any))]
+struct McpErrorInput {
+    ident: Ident,
+    generics: syn::Generics,
+    data: darling::ast::Data<McpErrorVariant, ()>,
+}
+
+#[derive(FromVariant, Debug)]
+#[darling(attributes(mcp))]
+struct McpErrorVariant {
+    ident: Ident,
+    fields: darling::ast::Fields<McpErrorField>,
+    /// The JSON-RPC error code. Defaults to `-32603`, internal error.
+    #[darling(default)]
+    code: Option<Expr>,
+    /// Return the error from tools as a `CallToolResult` with `is_error`,
+    /// instead of a JSON-RPC error
+    #[darling(default)]
+    tool_error: bool,
+    /// The message, with `{field}` or `{0}` placeholders for the variant's
+    /// fields. Defaults to the `Display` of the error.
+    #[darling(default)]
+    message: Option<String>,
+}
+
+#[derive(FromField, Debug)]
+#[darling(attributes(mcp))]
+struct McpErrorField {
+    ident: Option<Ident>,
+    /// Serialize this field into the error's `data`
+    #[darling(default)]
+    data: bool,
+}
+
+/// Turn the `{0}` placeholders of a message into `{_0}`, the name the
+/// variant's tuple fields are bound to.
+fn rewrite_template(template: &str) -> String {
+    let mut rewritten = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        rewritten.push(c);
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            rewritten.push(chars.next().unwrap());
+            continue;
+        }
+        if chars.peek().is_some_and(char::is_ascii_digit) {
+            rewritten.push('_');
+        }
+    }
+    rewritten
+}
+
+struct VariantExpansion {
+    pattern: TokenStream,
+    message: TokenStream,
+    data: TokenStream,
+}
+
+fn expand_variant(
+    enum_ident: &Ident,
+    variant: &McpErrorVariant,
+    this: &TokenStream,
+) -> syn::Result<VariantExpansion> {
+    let variant_ident = &variant.ident;
+    let bindings: Vec<Ident> = variant
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            field
+                .ident
+                .clone()
+                .unwrap_or_else(|| format_ident!("_{}", index))
+        })
+        .collect();
+    let pattern = match variant.fields.style {
+        Style::Unit => quote! { #enum_ident::#variant_ident },
+        Style::Tuple => quote! { #enum_ident::#variant_ident(#(#bindings),*) },
+        Style::Struct => quote! { #enum_ident::#variant_ident { #(#bindings),* } },
+    };
+
+    let message = match &variant.message {
+        Some(template) => {
+            let template = LitStr::new(&rewrite_template(template), variant_ident.span());
+            quote! { format!(#template) }
+        }
+        None => quote! { ::std::string::ToString::to_string(#this) },
+    };
+
+    let mut data_fields = variant
+        .fields
+        .iter()
+        .zip(&bindings)
+        .filter(|(field, _)| field.data)
+        .map(|(_, binding)| binding);
+    let data = match (data_fields.next(), data_fields.next()) {
+        (None, _) => quote! { None },
+        (Some(binding), None) => quote! { rmcp::serde_json::to_value(#binding).ok() },
+        (Some(_), Some(second)) => {
+            return Err(syn::Error::new_spanned(
+                second,
+                "only one field of a variant can be marked #[mcp(data)]",
+            ));
+        }
+    };
+
+    Ok(VariantExpansion {
+        pattern,
+        message,
+        data,
+    })
+}
+
+pub fn derive_mcp_error(input: TokenStream) -> syn::Result<TokenStream> {
+    let input = syn::parse2::<DeriveInput>(input)?;
+    let input = McpErrorInput::from_derive_input(&input)?;
+    let enum_ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let variants = input
+        .data
+        .take_enum()
+        .expect("darling only accepts enums here");
+
+    let error = quote! { error };
+    let mut to_error_data = Vec::new();
+    let mut to_tool_result = Vec::new();
+    for variant in &variants {
+        let VariantExpansion {
+            pattern,
+            message,
+            data,
+        } = expand_variant(enum_ident, variant, &error)?;
+        let code = match &variant.code {
+            Some(code) => quote! { rmcp::model::ErrorCode(#code) },
+            None => quote! { rmcp::model::ErrorCode::INTERNAL_ERROR },
+        };
+        to_error_data.push(quote! {
+            #pattern => rmcp::ErrorData::new(#code, #message, #data),
+        });
+        if variant.tool_error {
+            to_tool_result.push(quote! {
+                #pattern => {
+                    // the data is a second content block, as `structured_content`
+                    // would have to match the tool's `output_schema`
+                    let mut content = vec![rmcp::model::Content::text(#message)];
+                    if let Some(data) = #data {
+                        content.push(rmcp::model::Content::json(data)?);
+                    }
+                    Ok(rmcp::model::CallToolResult::error(content))
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#enum_ident #ty_generics> for rmcp::ErrorData #where_clause {
+            #[allow(unused_variables)]
+            fn from(error: #enum_ident #ty_generics) -> Self {
+                let error = &error;
+                match error {
+                    #(#to_error_data)*
+                }
+            }
+        }
+
+        impl #impl_generics rmcp::handler::server::tool::IntoCallToolResult for #enum_ident #ty_generics #where_clause {
+            #[allow(unused_variables, unreachable_patterns)]
+            fn into_call_tool_result(self) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+                let error = &self;
+                match error {
+                    #(#to_tool_result)*
+                    _ => Err(rmcp::ErrorData::from(self)),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_template() {
+        assert_eq!(rewrite_template("no {0} and {1:?}"), "no {_0} and {_1:?}");
+        assert_eq!(rewrite_template("{name} {{0}}"), "{name} {{0}}");
+    }
+
+    #[test]
+    fn test_mcp_error_derive() -> syn::Result<()> {
+        let input = quote! {
+            enum MyError {
+                #[mcp(code = -32602, message = "unknown user {0}")]
+                UnknownUser(String),
+                #[mcp(tool_error, message = "retry in {seconds}s")]
+                RateLimited {
+                    #[mcp(data)]
+                    seconds: u64,
+                },
+                Internal,
+            }
+        };
+        let result = derive_mcp_error(input)?.to_string();
+        assert!(result.contains("ErrorCode (- 32602)"));
+        assert!(result.contains("format ! (\"unknown user {_0}\")"));
+        assert!(result.contains("ErrorCode :: INTERNAL_ERROR"));
+        assert!(result.contains("to_value (seconds)"));
+        assert!(result.contains("CallToolResult :: error"));
+        assert!(result.contains("Content :: json (data)"));
+        assert!(!result.contains("structured_content"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_mcp_error_rejects_two_data_fields() {
+        let input = quote! {
+            enum MyError {
+                Both {
+                    #[mcp(data)]
+                    a: u64,
+                    #[mcp(data)]
+                    b: u64,
+                },
+            }
+        };
+        assert!(derive_mcp_error(input).is_err());
+    }
+
+    #[test]
+    fn test_mcp_error_rejects_structs() {
+        let input = quote! {
+            struct MyError {
+                reason: String,
+            }
+        };
+        assert!(derive_mcp_error(input).is_err());
+    }
+}
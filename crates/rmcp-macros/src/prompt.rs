@@ -1,15 +1,18 @@
 use darling::{FromMeta, ast::NestedMeta};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
-use syn::{Expr, Ident, ImplItemFn, ReturnType};
+use syn::{Expr, Ident, ImplItemFn, LitStr, ReturnType};
 
-use crate::common::{extract_doc_line, none_expr};
+use crate::{
+    common::{extract_doc_line, none_expr, scopes_call},
+    validation::{check_description, check_name, check_prompt_template, route_name},
+};
 
 #[derive(FromMeta, Default, Debug)]
 #[darling(default)]
 pub struct PromptAttribute {
     /// The name of the prompt
-    pub name: Option<String>,
+    pub name: Option<LitStr>,
     /// Human readable title of prompt
     pub title: Option<String>,
     /// Optional description of what the prompt does
@@ -20,9 +23,73 @@ pub struct PromptAttribute {
     pub icons: Option<Expr>,
     /// Optional metadata for the prompt
     pub meta: Option<Expr>,
+    /// OAuth scopes the caller must hold, e.g. `["prompts:read"]`.
+    pub scopes: Vec<LitStr>,
     /// When true, the generated future will not require `Send`. Useful for `!Send` handlers
     /// (e.g. single-threaded database connections). Also enabled globally by the `local` crate feature.
     pub local: bool,
+    /// A template file, relative to the crate root, to render the prompt from.
+    pub template: Option<LitStr>,
+}
+
+/// Turn the empty `fn_item` of a template prompt into a handler rendering the
+/// template at `path`. Returns the function giving the parsed template.
+fn template_prompt(fn_item: &mut ImplItemFn, path: &LitStr) -> syn::Result<ImplItemFn> {
+    if fn_item.sig.receiver().is_none()
+        || fn_item.sig.inputs.len() > 1
+        || !fn_item.block.stmts.is_empty()
+        || !matches!(fn_item.sig.output, ReturnType::Default)
+    {
+        return Err(syn::Error::new_spanned(
+            &fn_item.sig,
+            "a template prompt is rendered from its template; declare it as `fn name(&self) {}`",
+        ));
+    }
+    // checked here so a bad template fails the build; `include_str!` then
+    // rebuilds the crate when the file changes. Should `rmcp` still reject
+    // the template, the prompt answers with the error instead of panicking.
+    let resolved = check_prompt_template(path)?;
+    let template_fn_ident = format_ident!("{}_prompt_template", fn_item.sig.ident);
+    let doc_comment = format!("Generated prompt template from {}", path.value());
+    let template_fn = syn::parse2::<ImplItemFn>(quote! {
+        #[doc = #doc_comment]
+        pub fn #template_fn_ident() -> Result<
+            &'static rmcp::handler::server::prompt::template::PromptTemplate,
+            rmcp::ErrorData,
+        > {
+            static TEMPLATE: ::std::sync::LazyLock<
+                Result<
+                    rmcp::handler::server::prompt::template::PromptTemplate,
+                    rmcp::handler::server::prompt::template::PromptTemplateError,
+                >,
+            > = ::std::sync::LazyLock::new(|| {
+                rmcp::handler::server::prompt::template::PromptTemplate::parse(include_str!(#resolved))
+            });
+            TEMPLATE.as_ref().map_err(|error| {
+                rmcp::ErrorData::internal_error(
+                    format!("invalid prompt template {}: {}", #path, error),
+                    None,
+                )
+            })
+        }
+    })?;
+
+    fn_item.sig.asyncness = Some(Default::default());
+    fn_item.sig.inputs.push(syn::parse_quote! {
+        __rmcp_context: rmcp::service::RequestContext<rmcp::RoleServer>
+    });
+    fn_item.sig.inputs.push(syn::parse_quote! {
+        rmcp::handler::server::wrapper::Parameters(__rmcp_arguments): rmcp::handler::server::wrapper::Parameters<rmcp::model::JsonObject>
+    });
+    fn_item.sig.output = syn::parse_quote! {
+        -> Result<Vec<rmcp::model::PromptMessage>, rmcp::ErrorData>
+    };
+    fn_item.block = syn::parse_quote! {{
+        Self::#template_fn_ident()?
+            .render(self, Some(&__rmcp_arguments), &__rmcp_context)
+            .await
+    }};
+    Ok(template_fn)
 }
 
 pub struct ResolvedPromptAttribute {
@@ -79,15 +146,30 @@ pub fn prompt(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream>
         let attr_args = NestedMeta::parse_meta_list(attr)?;
         PromptAttribute::from_list(&attr_args)?
     };
+    // `#[prompt_router]` applies the scopes; reject bad ones here as well.
+    scopes_call(&attribute.scopes)?;
     let mut fn_item = syn::parse2::<ImplItemFn>(input.clone())?;
-    let fn_ident = &fn_item.sig.ident;
+    let fn_ident = fn_item.sig.ident.clone();
     let omit_send = cfg!(feature = "local") || attribute.local;
 
     let prompt_attr_fn_ident = format_ident!("{}_prompt_attr", fn_ident);
+    let template_fn = attribute
+        .template
+        .as_ref()
+        .map(|path| template_prompt(&mut fn_item, path))
+        .transpose()?;
 
     // Try to find prompt parameters from function parameters
     let arguments_expr = if let Some(arguments) = attribute.arguments {
         arguments
+    } else if let Some(template_fn) = &template_fn {
+        let template_fn_ident = &template_fn.sig.ident;
+        syn::parse2::<Expr>(quote! {{
+            let arguments = Self::#template_fn_ident()
+                .map(|template| template.arguments())
+                .unwrap_or_default();
+            (!arguments.is_empty()).then(|| arguments.to_vec())
+        }})?
     } else {
         // Look for a type named Parameters in the function signature
         let params_ty = crate::common::find_parameters_type_impl(&fn_item);
@@ -103,7 +185,8 @@ pub fn prompt(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream>
         }
     };
 
-    let name = attribute.name.unwrap_or_else(|| fn_ident.to_string());
+    let (name, name_span) = route_name(attribute.name.as_ref(), &fn_ident);
+    check_name("prompt", &name, name_span)?;
     let description = if let Some(s) = attribute.description {
         Some(Expr::Lit(syn::ExprLit {
             attrs: Vec::new(),
@@ -112,6 +195,12 @@ pub fn prompt(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream>
     } else {
         fn_item.attrs.iter().try_fold(None, extract_doc_line)?
     };
+    check_description(
+        "prompt",
+        description.is_some(),
+        &fn_ident,
+        cfg!(feature = "strict"),
+    )?;
     let arguments = arguments_expr;
 
     let resolved_prompt_attr = ResolvedPromptAttribute {
@@ -169,6 +258,7 @@ pub fn prompt(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream>
 
     Ok(quote! {
         #prompt_attr_fn
+        #template_fn
         #fn_item
     })
 }
@@ -273,4 +363,52 @@ mod test {
         assert!(!result_str.contains("+ Send +"));
         Ok(())
     }
+
+    /// Write `text` to a template file, returning its absolute path.
+    fn template_file(name: &str, text: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, text).unwrap();
+        path.display().to_string()
+    }
+
+    #[test]
+    fn test_template_prompt() -> syn::Result<()> {
+        let path = template_file("rmcp_macros_review.md", "Review {{code}}");
+        let attr = quote! { template = #path };
+        let input = quote! {
+            fn review(&self) {}
+        };
+        let result = prompt(attr, input)?.to_string();
+        assert!(result.contains("fn review_prompt_template ()"));
+        assert!(result.contains(&format!("include_str ! ({path:?})")));
+        assert!(result.contains("Self :: review_prompt_template () . map (| template | template . arguments ())"));
+        assert!(result.contains("review_prompt_template () ? . render (self , Some (& __rmcp_arguments) , & __rmcp_context)"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_prompt_needs_empty_fn() {
+        for input in [
+            quote! { fn review(&self) { todo!() } },
+            quote! { fn review(&self, Parameters(args): Parameters<Args>) {} },
+            quote! { fn review(&self) -> Vec<PromptMessage> {} },
+            quote! { fn review() {} },
+        ] {
+            assert!(prompt(quote! { template = "prompts/review.md" }, input).is_err());
+        }
+    }
+
+    #[test]
+    fn test_template_prompt_is_parsed_at_compile_time() {
+        let input = quote! { fn review(&self) {} };
+        let missing = prompt(quote! { template = "prompts/missing.md" }, input.clone());
+        let error = missing.unwrap_err().to_string();
+        assert!(error.contains("failed to read prompt template"), "{error}");
+
+        let path = template_file("rmcp_macros_invalid.md", "Review {{code");
+        let error = prompt(quote! { template = #path }, input)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("line 1: unclosed placeholder"), "{error}");
+    }
 }
@@ -56,15 +56,13 @@ pub fn prompt_handler(attr: TokenStream, input: TokenStream) -> syn::Result<Toke
     let list_prompts_impl: ImplItem = parse_quote! {
         async fn list_prompts(
             &self,
-            _request: Option<PaginatedRequestParams>,
+            request: Option<PaginatedRequestParams>,
             _context: RequestContext<RoleServer>,
         ) -> Result<ListPromptsResult, rmcp::ErrorData> {
-            let prompts = #router_expr.list_all();
-            Ok(ListPromptsResult {
-                prompts,
-                meta: #meta,
-                next_cursor: None,
-            })
+            let cursor = request.and_then(|request| request.cursor);
+            let mut result = #router_expr.list_page(cursor.as_deref())?;
+            result.meta = #meta;
+            Ok(result)
         }
     };
 
@@ -157,7 +155,7 @@ mod test {
         assert!(
             result_str.contains("self")
                 && result_str.contains("get_prompt_router")
-                && result_str.contains("list_all")
+                && result_str.contains("list_page")
         );
 
         Ok(())
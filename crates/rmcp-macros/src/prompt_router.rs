@@ -3,6 +3,12 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{ImplItem, ItemImpl, Visibility, parse_quote};
 
+use crate::{
+    common::{parse_attribute_args, scopes_call},
+    prompt::PromptAttribute,
+    validation::{RouteNames, route_name},
+};
+
 #[derive(FromMeta, Debug, Default)]
 #[darling(default)]
 pub struct PromptRouterAttribute {
@@ -28,37 +34,41 @@ pub fn prompt_router(attr: TokenStream, input: TokenStream) -> syn::Result<Token
     let vis = attribute.vis.unwrap_or(Visibility::Inherited);
 
     let mut prompt_route_fn_calls = Vec::new();
+    let mut names = RouteNames::new("prompt");
 
-    for item in &mut impl_block.items {
+    for item in &impl_block.items {
         if let ImplItem::Fn(fn_item) = item {
-            let has_prompt_attr = fn_item.attrs.iter().any(|attr| {
+            let prompt_attr = fn_item.attrs.iter().find(|attr| {
                 attr.path()
                     .segments
                     .last()
-                    .map(|seg| seg.ident == "prompt")
-                    .unwrap_or(false)
+                    .is_some_and(|seg| seg.ident == "prompt")
             });
 
-            if has_prompt_attr {
+            if let Some(prompt_attr) = prompt_attr {
                 let fn_ident = &fn_item.sig.ident;
                 let attr_fn_ident = format_ident!("{}_prompt_attr", fn_ident);
-
-                // Check what parameters the function takes
-                let mut param_names = Vec::new();
-                let mut param_types = Vec::new();
-
-                for input in &fn_item.sig.inputs {
-                    if let syn::FnArg::Typed(pat_type) = input {
-                        // Extract parameter pattern and type
-                        param_types.push(&*pat_type.ty);
-                        param_names.push(&*pat_type.pat);
-                    }
-                }
+                let attribute: PromptAttribute = parse_attribute_args(prompt_attr)?;
+                let (name, span) = route_name(attribute.name.as_ref(), fn_ident);
+                names.insert(name, span)?;
+                let scopes = scopes_call(&attribute.scopes)?;
 
                 // Use the exact same pattern as tool_router
-                prompt_route_fn_calls.push(quote! {
-                    .with_route((Self::#attr_fn_ident(), Self::#fn_ident))
-                });
+                if scopes.is_empty() {
+                    prompt_route_fn_calls.push(quote! {
+                        .with_route((Self::#attr_fn_ident(), Self::#fn_ident))
+                    });
+                } else {
+                    prompt_route_fn_calls.push(quote! {
+                        .with_route(
+                            rmcp::handler::server::router::prompt::PromptRoute::new(
+                                Self::#attr_fn_ident(),
+                                Self::#fn_ident,
+                            )
+                            #scopes
+                        )
+                    });
+                }
             }
         }
     }
@@ -108,4 +118,22 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_prompt_router_rejects_duplicate_names() {
+        let input = quote! {
+            impl MyPromptHandler {
+                #[prompt(name = "review")]
+                async fn review_code(&self) -> Vec<PromptMessage> {
+                    vec![]
+                }
+
+                #[prompt(name = "review")]
+                async fn review_docs(&self) -> Vec<PromptMessage> {
+                    vec![]
+                }
+            }
+        };
+        assert!(prompt_router(TokenStream::new(), input).is_err());
+    }
 }
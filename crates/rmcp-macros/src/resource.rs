@@ -0,0 +1,423 @@
+use darling::{FromMeta, ast::NestedMeta};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Expr, FnArg, Ident, ImplItemFn, LitStr, Pat, ReturnType, Type};
+
+use crate::common::{extract_doc_line, scopes_call};
+
+#[derive(FromMeta, Default, Debug)]
+#[darling(default)]
+pub struct ResourceAttribute {
+    /// The URI of the resource
+    pub uri: Option<String>,
+    /// The name of the resource
+    pub name: Option<String>,
+    /// Human readable title of resource
+    pub title: Option<String>,
+    /// Optional description of the resource
+    pub description: Option<String>,
+    /// Optional MIME type of the resource content
+    pub mime_type: Option<String>,
+    /// Optional size of the resource content in bytes
+    pub size: Option<u32>,
+    /// Optional icons for the resource
+    pub icons: Option<Expr>,
+    /// Optional metadata for the resource
+    pub meta: Option<Expr>,
+    /// OAuth scopes the caller must hold, e.g. `["files:read"]`.
+    pub scopes: Vec<LitStr>,
+    /// When true, the generated future will not require `Send`. Useful for `!Send` handlers
+    /// (e.g. single-threaded database connections). Also enabled globally by the `local` crate feature.
+    pub local: bool,
+}
+
+#[derive(FromMeta, Default, Debug)]
+#[darling(default)]
+pub struct ResourceTemplateAttribute {
+    /// The RFC 6570 URI template
+    pub uri_template: Option<String>,
+    /// The name of the resource template
+    pub name: Option<String>,
+    /// Human readable title of resource template
+    pub title: Option<String>,
+    /// Optional description of the resource template
+    pub description: Option<String>,
+    /// Optional MIME type of the resources matched by this template
+    pub mime_type: Option<String>,
+    /// Optional icons for the resource template
+    pub icons: Option<Expr>,
+    /// OAuth scopes the caller must hold, e.g. `["files:read"]`.
+    pub scopes: Vec<LitStr>,
+    /// When true, the generated future will not require `Send`. Useful for `!Send` handlers
+    /// (e.g. single-threaded database connections). Also enabled globally by the `local` crate feature.
+    pub local: bool,
+}
+
+/// Collect the variable names declared in a URI template, in order of appearance.
+///
+/// Operators (`+`, `#`, `.`, `/`, `;`, `?`, `&`) and value modifiers (`*`, `:N`) are
+/// stripped so that only the bare variable names remain.
+pub fn template_variable_names(uri_template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = uri_template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let expression = &rest[start + 1..start + end];
+        let expression = expression.trim_start_matches(['+', '#', '.', '/', ';', '?', '&']);
+        for var in expression.split(',') {
+            let var = var.trim_end_matches('*');
+            let var = var.split(':').next().unwrap_or_default();
+            if !var.is_empty() && !names.iter().any(|n| n == var) {
+                names.push(var.to_string());
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    names
+}
+
+fn description_expr(
+    description: Option<String>,
+    fn_item: &ImplItemFn,
+) -> syn::Result<Option<Expr>> {
+    if let Some(s) = description {
+        Ok(Some(Expr::Lit(syn::ExprLit {
+            attrs: Vec::new(),
+            lit: syn::Lit::Str(LitStr::new(&s, Span::call_site())),
+        })))
+    } else {
+        fn_item.attrs.iter().try_fold(None, extract_doc_line)
+    }
+}
+
+/// Rewrite an `async fn` into a function returning a boxed future, like `#[tool]` does.
+pub(crate) fn box_async_fn(fn_item: &mut ImplItemFn, omit_send: bool) -> syn::Result<()> {
+    if fn_item.sig.asyncness.is_none() {
+        return Ok(());
+    }
+    let output = match &fn_item.sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+    let new_output = boxed_future_return(fn_item, output, omit_send)?;
+    let prev_block = &fn_item.block;
+    let new_block = syn::parse2::<syn::Block>(quote! {
+       { Box::pin(async move #prev_block ) }
+    })?;
+    fn_item.sig.asyncness = None;
+    fn_item.sig.output = new_output;
+    fn_item.block = new_block;
+    Ok(())
+}
+
+fn boxed_future_return(
+    fn_item: &ImplItemFn,
+    output: TokenStream,
+    omit_send: bool,
+) -> syn::Result<ReturnType> {
+    let mut lt = quote! { 'static };
+    if let Some(receiver) = fn_item.sig.receiver() {
+        if let Some((_, receiver_lt)) = receiver.reference.as_ref() {
+            if let Some(receiver_lt) = receiver_lt {
+                lt = quote! { #receiver_lt };
+            } else {
+                lt = quote! { '_ };
+            }
+        }
+    }
+    if omit_send {
+        syn::parse2::<ReturnType>(
+            quote! { -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = #output> + #lt>> },
+        )
+    } else {
+        syn::parse2::<ReturnType>(
+            quote! { -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = #output> + Send + #lt>> },
+        )
+    }
+}
+
+pub fn resource(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = NestedMeta::parse_meta_list(attr)?;
+        ResourceAttribute::from_list(&attr_args)?
+    };
+    // `#[resource_router]` applies the scopes; reject bad ones here as well.
+    scopes_call(&attribute.scopes)?;
+    let mut fn_item = syn::parse2::<ImplItemFn>(input)?;
+    let fn_ident = fn_item.sig.ident.clone();
+    let Some(uri) = attribute.uri else {
+        return Err(syn::Error::new_spanned(
+            &fn_item.sig.ident,
+            "#[resource] requires a `uri`, e.g. #[resource(uri = \"file:///README.md\")]",
+        ));
+    };
+
+    let name = attribute.name.unwrap_or_else(|| fn_ident.to_string());
+    let description_call = description_expr(attribute.description, &fn_item)?
+        .map(|d| quote! { .with_description(#d) })
+        .unwrap_or_default();
+    let title_call = attribute
+        .title
+        .map(|t| quote! { .with_title(#t) })
+        .unwrap_or_default();
+    let mime_type_call = attribute
+        .mime_type
+        .map(|m| quote! { .with_mime_type(#m) })
+        .unwrap_or_default();
+    let size_call = attribute
+        .size
+        .map(|s| quote! { .with_size(#s) })
+        .unwrap_or_default();
+    let icons_call = attribute
+        .icons
+        .map(|i| quote! { .with_icons(#i) })
+        .unwrap_or_default();
+    let meta_call = attribute
+        .meta
+        .map(|m| quote! { .with_meta(#m) })
+        .unwrap_or_default();
+
+    let resource_attr_fn_ident = format_ident!("{}_resource_attr", fn_ident);
+    let doc_comment = format!("Generated resource metadata function for {name}");
+    let resource_attr_fn = quote! {
+        #[doc = #doc_comment]
+        pub fn #resource_attr_fn_ident() -> rmcp::model::Resource {
+            use rmcp::model::AnnotateAble;
+            rmcp::model::RawResource::new(#uri, #name)
+                #title_call
+                #description_call
+                #mime_type_call
+                #size_call
+                #icons_call
+                #meta_call
+                .no_annotation()
+        }
+    };
+
+    box_async_fn(&mut fn_item, cfg!(feature = "local") || attribute.local)?;
+
+    Ok(quote! {
+        #resource_attr_fn
+        #fn_item
+    })
+}
+
+pub fn resource_template(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = NestedMeta::parse_meta_list(attr)?;
+        ResourceTemplateAttribute::from_list(&attr_args)?
+    };
+    scopes_call(&attribute.scopes)?;
+    let mut fn_item = syn::parse2::<ImplItemFn>(input)?;
+    let fn_ident = fn_item.sig.ident.clone();
+    let Some(uri_template) = attribute.uri_template else {
+        return Err(syn::Error::new_spanned(
+            &fn_item.sig.ident,
+            "#[resource_template] requires a `uri_template`, e.g. #[resource_template(uri_template = \"file:///{path}\")]",
+        ));
+    };
+    let omit_send = cfg!(feature = "local") || attribute.local;
+
+    let name = attribute.name.unwrap_or_else(|| fn_ident.to_string());
+    let description_call = description_expr(attribute.description, &fn_item)?
+        .map(|d| quote! { .with_description(#d) })
+        .unwrap_or_default();
+    let title_call = attribute
+        .title
+        .map(|t| quote! { .with_title(#t) })
+        .unwrap_or_default();
+    let mime_type_call = attribute
+        .mime_type
+        .map(|m| quote! { .with_mime_type(#m) })
+        .unwrap_or_default();
+    let icons_call = attribute
+        .icons
+        .map(|i| quote! { .with_icons(#i) })
+        .unwrap_or_default();
+
+    let template_attr_fn_ident = format_ident!("{}_resource_template_attr", fn_ident);
+    let doc_comment = format!("Generated resource template metadata function for {name}");
+    let template_attr_fn = quote! {
+        #[doc = #doc_comment]
+        pub fn #template_attr_fn_ident() -> rmcp::model::ResourceTemplate {
+            use rmcp::model::AnnotateAble;
+            rmcp::model::RawResourceTemplate::new(#uri_template, #name)
+                #title_call
+                #description_call
+                #mime_type_call
+                #icons_call
+                .no_annotation()
+        }
+    };
+
+    let handler_fn = template_handler_fn(&fn_item, &uri_template, omit_send)?;
+    box_async_fn(&mut fn_item, omit_send)?;
+
+    Ok(quote! {
+        #template_attr_fn
+        #handler_fn
+        #fn_item
+    })
+}
+
+/// Build `<fn>_resource_template_handler`, which takes the matched
+/// `TemplateVariables` in place of every argument named after a template variable,
+/// parses them into the declared types and forwards to the original function.
+fn template_handler_fn(
+    fn_item: &ImplItemFn,
+    uri_template: &str,
+    omit_send: bool,
+) -> syn::Result<TokenStream> {
+    let fn_ident = &fn_item.sig.ident;
+    let handler_ident = format_ident!("{}_resource_template_handler", fn_ident);
+    let variable_names = template_variable_names(uri_template);
+    let vis = &fn_item.vis;
+    let generics = &fn_item.sig.generics;
+    let where_clause = &generics.where_clause;
+
+    let mut params = Vec::new();
+    let mut parse_stmts = Vec::new();
+    let mut call_args = Vec::new();
+    let mut receiver = None;
+    for (index, input) in fn_item.sig.inputs.iter().enumerate() {
+        match input {
+            FnArg::Receiver(r) => receiver = Some(r.clone()),
+            FnArg::Typed(pat_type) => {
+                let ty: &Type = &pat_type.ty;
+                match &*pat_type.pat {
+                    Pat::Ident(pat_ident)
+                        if variable_names.iter().any(|v| pat_ident.ident == v) =>
+                    {
+                        let ident = &pat_ident.ident;
+                        let name = ident.to_string();
+                        parse_stmts.push(quote! {
+                            let #ident: #ty = __rmcp_variables.parse(#name)?;
+                        });
+                        call_args.push(quote! { #ident });
+                    }
+                    _ => {
+                        let ident = format_ident!("__rmcp_arg{}", index);
+                        params.push(quote! { #ident: #ty });
+                        call_args.push(quote! { #ident });
+                    }
+                }
+            }
+        }
+    }
+
+    let callee: Ident = fn_ident.clone();
+    let call = if receiver.is_some() {
+        quote! { self.#callee(#(#call_args),*) }
+    } else {
+        quote! { Self::#callee(#(#call_args),*) }
+    };
+    let receiver = receiver.map(|r| quote! { #r, });
+    let output = match &fn_item.sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+
+    let tokens = if fn_item.sig.asyncness.is_some() {
+        let ret = boxed_future_return(
+            fn_item,
+            quote! { ::std::result::Result<#output, rmcp::ErrorData> },
+            omit_send,
+        )?;
+        quote! {
+            #[doc(hidden)]
+            #vis fn #handler_ident #generics (
+                #receiver
+                __rmcp_variables: rmcp::handler::server::resource::TemplateVariables,
+                #(#params),*
+            ) #ret #where_clause {
+                Box::pin(async move {
+                    #(#parse_stmts)*
+                    Ok::<_, rmcp::ErrorData>(#call.await)
+                })
+            }
+        }
+    } else {
+        quote! {
+            #[doc(hidden)]
+            #vis fn #handler_ident #generics (
+                #receiver
+                __rmcp_variables: rmcp::handler::server::resource::TemplateVariables,
+                #(#params),*
+            ) -> ::std::result::Result<#output, rmcp::ErrorData> #where_clause {
+                #(#parse_stmts)*
+                Ok(#call)
+            }
+        }
+    };
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_template_variable_names() {
+        assert_eq!(template_variable_names("file:///{path}"), vec!["path"]);
+        assert_eq!(
+            template_variable_names("http://example.com{/owner,repo}{?q,page:3}{#frag*}"),
+            vec!["owner", "repo", "q", "page", "frag"]
+        );
+        assert!(template_variable_names("file:///README.md").is_empty());
+    }
+
+    #[test]
+    fn test_resource_macro() -> syn::Result<()> {
+        let attr = quote! {
+            uri = "file:///README.md",
+            mime_type = "text/markdown"
+        };
+        let input = quote! {
+            /// The project readme
+            async fn readme(&self) -> String {
+                String::new()
+            }
+        };
+        let result = resource(attr, input)?.to_string();
+        assert!(result.contains("readme_resource_attr"));
+        assert!(result.contains("RawResource :: new"));
+        assert!(result.contains("The project readme"));
+        assert!(result.contains("text/markdown"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resource_macro_requires_uri() {
+        let input = quote! {
+            fn readme(&self) -> String {
+                String::new()
+            }
+        };
+        assert!(resource(quote! {}, input).is_err());
+    }
+
+    #[test]
+    fn test_resource_template_macro_generates_handler() -> syn::Result<()> {
+        let attr = quote! {
+            uri_template = "users://{user_id}/posts/{post}"
+        };
+        let input = quote! {
+            async fn user_post(&self, user_id: u64, post: String, peer: Peer<RoleServer>) -> String {
+                String::new()
+            }
+        };
+        let result = resource_template(attr, input)?.to_string();
+        assert!(result.contains("user_post_resource_template_attr"));
+        assert!(result.contains("user_post_resource_template_handler"));
+        assert!(result.contains("__rmcp_variables . parse (\"user_id\")"));
+        assert!(result.contains("__rmcp_variables . parse (\"post\")"));
+        assert!(result.contains("__rmcp_arg3 : Peer < RoleServer >"));
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, ImplItem, ItemImpl, parse_quote};
+
+use crate::{
+    common::{has_method, has_sibling_handler},
+    tool_handler::{CallerCapability, build_get_info},
+};
+
+#[derive(FromMeta, Debug, Default)]
+#[darling(default)]
+pub struct ResourceHandlerAttribute {
+    pub router: Option<Expr>,
+    pub meta: Option<Expr>,
+}
+
+pub fn resource_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = darling::ast::NestedMeta::parse_meta_list(attr)?;
+        ResourceHandlerAttribute::from_list(&attr_args)?
+    };
+
+    let mut impl_block = syn::parse2::<ItemImpl>(input)?;
+
+    let router_expr = attribute
+        .router
+        .unwrap_or_else(|| syn::parse2(quote! { Self::resource_router() }).unwrap());
+
+    let meta = if let Some(meta) = attribute.meta {
+        quote! { Some(#meta) }
+    } else {
+        quote! { None }
+    };
+
+    let read_resource_impl: ImplItem = parse_quote! {
+        async fn read_resource(
+            &self,
+            request: rmcp::model::ReadResourceRequestParams,
+            context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> Result<rmcp::model::ReadResourceResult, rmcp::ErrorData> {
+            let resource_context = rmcp::handler::server::resource::ResourceContext::new(
+                self,
+                request.uri,
+                context,
+            );
+            #router_expr.read_resource(resource_context).await
+        }
+    };
+
+    let list_resources_impl: ImplItem = parse_quote! {
+        async fn list_resources(
+            &self,
+            request: Option<rmcp::model::PaginatedRequestParams>,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> Result<rmcp::model::ListResourcesResult, rmcp::ErrorData> {
+            let cursor = request.and_then(|request| request.cursor);
+            let mut result = #router_expr.list_page(cursor.as_deref())?;
+            result.meta = #meta;
+            Ok(result)
+        }
+    };
+
+    let list_resource_templates_impl: ImplItem = parse_quote! {
+        async fn list_resource_templates(
+            &self,
+            request: Option<rmcp::model::PaginatedRequestParams>,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> Result<rmcp::model::ListResourceTemplatesResult, rmcp::ErrorData> {
+            let cursor = request.and_then(|request| request.cursor);
+            let mut result = #router_expr.list_templates_page(cursor.as_deref())?;
+            result.meta = #meta;
+            Ok(result)
+        }
+    };
+
+    for (name, item) in [
+        ("read_resource", read_resource_impl),
+        ("list_resources", list_resources_impl),
+        ("list_resource_templates", list_resource_templates_impl),
+    ] {
+        if !has_method(name, &impl_block) {
+            impl_block.items.push(item);
+        }
+    }
+
+    // Auto-generate get_info() if not already provided and no sibling handler
+    // macro is going to generate it.
+    if !has_method("get_info", &impl_block)
+        && !has_sibling_handler(&impl_block, "tool_handler")
+        && !has_sibling_handler(&impl_block, "prompt_handler")
+    {
+        let get_info_fn =
+            build_get_info(&impl_block, None, None, None, CallerCapability::Resources)?;
+        impl_block.items.push(get_info_fn);
+    }
+
+    Ok(quote! {
+        #impl_block
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resource_handler_macro() -> syn::Result<()> {
+        let input = quote! {
+            impl ServerHandler for MyResourceHandler {}
+        };
+
+        let result = resource_handler(TokenStream::new(), input)?;
+        let result_str = result.to_string();
+
+        assert!(result_str.contains("async fn read_resource"));
+        assert!(result_str.contains("async fn list_resources"));
+        assert!(result_str.contains("async fn list_resource_templates"));
+        assert!(result_str.contains("enable_resources"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resource_handler_keeps_existing_methods() -> syn::Result<()> {
+        let attr = quote! { router = self.resource_router };
+        let input = quote! {
+            impl ServerHandler for MyResourceHandler {
+                async fn list_resources(
+                    &self,
+                    _request: Option<PaginatedRequestParams>,
+                    _context: RequestContext<RoleServer>,
+                ) -> Result<ListResourcesResult, ErrorData> {
+                    Ok(ListResourcesResult::default())
+                }
+            }
+        };
+
+        let result = resource_handler(attr, input)?;
+        let result_str = result.to_string();
+
+        assert_eq!(result_str.matches("fn list_resources").count(), 1);
+        assert!(result_str.contains("self . resource_router . read_resource"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ImplItem, ItemImpl, Visibility, parse_quote};
+
+use crate::{
+    common::{parse_attribute_args, scopes_call},
+    resource::{ResourceAttribute, ResourceTemplateAttribute},
+};
+
+#[derive(FromMeta, Debug, Default)]
+#[darling(default)]
+pub struct ResourceRouterAttribute {
+    pub router: Option<String>,
+    pub vis: Option<Visibility>,
+}
+
+fn find_attr<'a>(fn_item: &'a syn::ImplItemFn, name: &str) -> Option<&'a syn::Attribute> {
+    fn_item.attrs.iter().find(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == name)
+    })
+}
+
+pub fn resource_router(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    let attribute = if attr.is_empty() {
+        Default::default()
+    } else {
+        let attr_args = darling::ast::NestedMeta::parse_meta_list(attr)?;
+        ResourceRouterAttribute::from_list(&attr_args)?
+    };
+
+    let mut impl_block = syn::parse2::<ItemImpl>(input)?;
+    let self_ty = &impl_block.self_ty;
+
+    let router_fn_ident = attribute
+        .router
+        .map(|s| format_ident!("{}", s))
+        .unwrap_or_else(|| format_ident!("resource_router"));
+    let vis = attribute.vis.unwrap_or(Visibility::Inherited);
+
+    let mut route_calls = Vec::new();
+    for item in &impl_block.items {
+        if let ImplItem::Fn(fn_item) = item {
+            let fn_ident = &fn_item.sig.ident;
+            if let Some(attr) = find_attr(fn_item, "resource") {
+                let attr_fn_ident = format_ident!("{}_resource_attr", fn_ident);
+                let attribute: ResourceAttribute = parse_attribute_args(attr)?;
+                let scopes = scopes_call(&attribute.scopes)?;
+                if scopes.is_empty() {
+                    route_calls.push(quote! {
+                        .with_route((Self::#attr_fn_ident(), Self::#fn_ident))
+                    });
+                } else {
+                    route_calls.push(quote! {
+                        .with_route(
+                            rmcp::handler::server::router::resource::ResourceRoute::new(
+                                Self::#attr_fn_ident(),
+                                Self::#fn_ident,
+                            )
+                            #scopes
+                        )
+                    });
+                }
+            } else if let Some(attr) = find_attr(fn_item, "resource_template") {
+                let attr_fn_ident = format_ident!("{}_resource_template_attr", fn_ident);
+                let handler_fn_ident = format_ident!("{}_resource_template_handler", fn_ident);
+                let attribute: ResourceTemplateAttribute = parse_attribute_args(attr)?;
+                let scopes = scopes_call(&attribute.scopes)?;
+                if scopes.is_empty() {
+                    route_calls.push(quote! {
+                        .with_template((Self::#attr_fn_ident(), Self::#handler_fn_ident))
+                    });
+                } else {
+                    route_calls.push(quote! {
+                        .with_template(
+                            rmcp::handler::server::router::resource::ResourceTemplateRoute::new(
+                                Self::#attr_fn_ident(),
+                                Self::#handler_fn_ident,
+                            )
+                            #scopes
+                        )
+                    });
+                }
+            }
+        }
+    }
+
+    let router_fn: ImplItem = parse_quote! {
+        #vis fn #router_fn_ident() -> rmcp::handler::server::router::resource::ResourceRouter<#self_ty> {
+            rmcp::handler::server::router::resource::ResourceRouter::new()
+                #(#route_calls)*
+        }
+    };
+
+    impl_block.items.push(router_fn);
+
+    Ok(quote! {
+        #impl_block
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resource_router_macro() -> syn::Result<()> {
+        let input = quote! {
+            impl MyResourceHandler {
+                #[resource(uri = "file:///README.md")]
+                async fn readme(&self) -> String {
+                    String::new()
+                }
+
+                #[resource_template(uri_template = "file:///{path}")]
+                async fn file(&self, path: String) -> String {
+                    path
+                }
+            }
+        };
+
+        let result = resource_router(TokenStream::new(), input)?;
+        let result_str = result.to_string();
+
+        assert!(result_str.contains("fn resource_router"));
+        assert!(result_str.contains("ResourceRouter :: new"));
+        assert!(result_str.contains("readme_resource_attr"));
+        assert!(result_str.contains("file_resource_template_attr"));
+        assert!(result_str.contains("file_resource_template_handler"));
+
+        Ok(())
+    }
+}
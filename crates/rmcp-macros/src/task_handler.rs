@@ -66,7 +66,7 @@ pub fn task_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenS
                 let task_id = context.id.to_string();
                 let operation_name = request.name.to_string();
                 let future_request = request.clone();
-                let future_context = context.clone();
+                let mut future_context = context.clone();
                 let server = self.clone();
 
                 let descriptor = OperationDescriptor::new(task_id.clone(), operation_name)
@@ -74,6 +74,8 @@ pub fn task_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenS
                     .with_client_request(rmcp::model::ClientRequest::CallToolRequest(
                         rmcp::model::Request::new(request),
                     ));
+                // the task outlives this request, whose token fires once it is answered
+                future_context.ct = descriptor.ct.clone();
 
                 let task_result_id = task_id.clone();
                 let future = Box::pin(async move {
@@ -3,7 +3,10 @@ use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, format_ident, quote};
 use syn::{Expr, Ident, ImplItemFn, LitStr, ReturnType, parse_quote};
 
-use crate::common::extract_doc_line;
+use crate::{
+    common::{extract_doc_line, parse_attribute_args, scopes_call},
+    validation::{check_description, check_name, route_name},
+};
 
 /// Check if a type is Json<T> and extract the inner type T
 fn extract_json_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
@@ -75,11 +78,36 @@ fn extract_schema_from_return_type(ret_type: &syn::Type) -> Option<Expr> {
     })
     .ok()
 }
+
+/// Whether a tool returning `ret_type` can produce structured content:
+/// `Json<T>`, `CallToolResult`, or a `Result` of either.
+fn returns_structured_content(ret_type: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ret_type else {
+        return false;
+    };
+    let Some(last_segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    if last_segment.ident == "Json" || last_segment.ident == "CallToolResult" {
+        return true;
+    }
+    if last_segment.ident != "Result" {
+        return false;
+    }
+    match &last_segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => matches!(
+            args.args.first(),
+            Some(syn::GenericArgument::Type(ok_type)) if returns_structured_content(ok_type)
+        ),
+        _ => false,
+    }
+}
+
 #[derive(FromMeta, Default, Debug)]
 #[darling(default)]
 pub struct ToolAttribute {
     /// The name of the tool
-    pub name: Option<String>,
+    pub name: Option<LitStr>,
     /// Human readable title of tool
     pub title: Option<String>,
     pub description: Option<String>,
@@ -98,13 +126,116 @@ pub struct ToolAttribute {
     /// When true, the generated future will not require `Send`. Useful for `!Send` handlers
     /// (e.g. single-threaded database connections). Also enabled globally by the `local` crate feature.
     pub local: bool,
+    /// Abort the handler after this long, e.g. `"30s"` or `"500ms"`.
+    pub timeout: Option<LitStr>,
+    /// Maximum number of calls of this tool that may run at once, across every
+    /// instance of the server in the process.
+    pub max_concurrency: Option<usize>,
+    /// What to do with calls beyond `max_concurrency`: `"queue"` (default) or `"reject"`.
+    pub on_busy: Option<LitStr>,
+    /// OAuth scopes the caller must hold, e.g. `["files:write"]`.
+    pub scopes: Vec<LitStr>,
+}
+
+impl ToolAttribute {
+    /// Parse the arguments of a `#[tool(...)]` attribute found on a method.
+    pub fn from_attribute(attr: &syn::Attribute) -> syn::Result<Self> {
+        parse_attribute_args(attr)
+    }
+
+    /// Builder calls applied to the `ToolRoute` for `timeout`, `max_concurrency`,
+    /// `on_busy` and `scopes`, in a router for the server type `server`.
+    pub fn route_options(&self, server: &TokenStream) -> syn::Result<TokenStream> {
+        let mut options = scopes_call(&self.scopes)?;
+        if let Some(timeout) = &self.timeout {
+            let millis = parse_duration_millis(timeout)?;
+            options.extend(quote! {
+                .with_timeout(::std::time::Duration::from_millis(#millis))
+            });
+        }
+        if let Some(max) = self.max_concurrency {
+            if max == 0 {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "max_concurrency must be at least 1",
+                ));
+            }
+            // `tool_router()` may be rebuilt for every call or session, so the
+            // slots live in a static that all those routers share. The static
+            // is shared by every instantiation of a generic router too, so it
+            // holds a limit per server type.
+            options.extend(quote! {
+                .with_concurrency_limit({
+                    static LIMITS: ::std::sync::LazyLock<
+                        rmcp::handler::server::router::tool::ConcurrencyLimits,
+                    > = ::std::sync::LazyLock::new(|| {
+                        rmcp::handler::server::router::tool::ConcurrencyLimits::new(#max)
+                    });
+                    LIMITS.for_type::<#server>()
+                })
+            });
+        }
+        if let Some(on_busy) = &self.on_busy {
+            let policy = match on_busy.value().as_str() {
+                "queue" => quote! { Queue },
+                "reject" => quote! { Reject },
+                other => {
+                    return Err(syn::Error::new(
+                        on_busy.span(),
+                        format!("Invalid on_busy value '{other}'. Expected 'queue' or 'reject'"),
+                    ));
+                }
+            };
+            if self.max_concurrency.is_none() {
+                return Err(syn::Error::new(
+                    on_busy.span(),
+                    "on_busy requires max_concurrency",
+                ));
+            }
+            options.extend(quote! {
+                .with_busy_policy(rmcp::handler::server::router::tool::BusyPolicy::#policy)
+            });
+        }
+        Ok(options)
+    }
+}
+
+/// Parse durations like `"500ms"`, `"30s"`, `"5m"` or `"1h"` into milliseconds.
+fn parse_duration_millis(lit: &LitStr) -> syn::Result<u64> {
+    let value = lit.value();
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let factor = match unit {
+        "ms" => Some(1),
+        "s" => Some(1_000),
+        "m" => Some(60_000),
+        "h" => Some(3_600_000),
+        _ => None,
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .zip(factor)
+        .and_then(|(number, factor)| number.checked_mul(factor))
+        .filter(|millis| *millis > 0)
+        .ok_or_else(|| {
+            syn::Error::new(
+                lit.span(),
+                format!(
+                    "Invalid timeout '{value}'. Expected a positive duration such as \"500ms\", \"30s\", \"5m\" or \"1h\""
+                ),
+            )
+        })
 }
 
 #[derive(FromMeta, Debug, Default)]
 #[darling(default)]
 pub struct ToolExecutionAttribute {
     /// Task support mode: "forbidden", "optional", or "required"
-    pub task_support: Option<String>,
+    pub task_support: Option<LitStr>,
 }
 
 pub struct ResolvedToolAttribute {
@@ -213,18 +344,40 @@ pub struct ToolAnnotationsAttribute {
 }
 
 pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
+    tool_in(attr, input, quote! { Self:: })
+}
+
+/// Expand a tool whose siblings are reached through `prefix`: `Self::` in an
+/// impl block or a trait, nothing in a module.
+pub fn tool_in(
+    attr: TokenStream,
+    input: TokenStream,
+    prefix: TokenStream,
+) -> syn::Result<TokenStream> {
     let attribute = if attr.is_empty() {
         Default::default()
     } else {
         let attr_args = NestedMeta::parse_meta_list(attr)?;
         ToolAttribute::from_list(&attr_args)?
     };
+    // `#[tool_router]` applies the route options; checking them here reports
+    // bad values for tools that are routed by hand as well.
+    attribute.route_options(&quote! { Self })?;
     let mut fn_item = syn::parse2::<ImplItemFn>(input.clone())?;
+    // the output schema comes from the return type as written
+    let original_output = fn_item.sig.output.clone();
+    let flat_arguments = crate::tool_args::take_flat_arguments(&mut fn_item)?;
+    let mut handler = flat_arguments
+        .as_ref()
+        .map(|flat_arguments| flat_arguments.handler(&fn_item, &prefix))
+        .transpose()?;
     let fn_ident = &fn_item.sig.ident;
 
     let tool_attr_fn_ident = format_ident!("{}_tool_attr", fn_ident);
     let input_schema_expr = if let Some(input_schema) = attribute.input_schema {
         input_schema
+    } else if let Some(flat_arguments) = &flat_arguments {
+        flat_arguments.input_schema()?
     } else {
         // try to find some parameters wrapper in the function
         let params_ty = crate::common::find_parameters_type_impl(&fn_item);
@@ -275,16 +428,16 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
         let ToolExecutionAttribute { task_support } = execution;
 
         let task_support_expr = if let Some(ts) = task_support {
-            let ts_ident = match ts.as_str() {
+            let ts_ident = match ts.value().as_str() {
                 "forbidden" => quote! { rmcp::model::TaskSupport::Forbidden },
                 "optional" => quote! { rmcp::model::TaskSupport::Optional },
                 "required" => quote! { rmcp::model::TaskSupport::Required },
                 _ => {
                     return Err(syn::Error::new(
-                        Span::call_site(),
+                        ts.span(),
                         format!(
                             "Invalid task_support value '{}'. Expected 'forbidden', 'optional', or 'required'",
-                            ts
+                            ts.value()
                         ),
                     ));
                 }
@@ -303,10 +456,24 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
     } else {
         None
     };
+    // an explicit output_schema needs a return type that can carry
+    // structured content, or every call would fail output validation
+    if let Some(output_schema) = &attribute.output_schema {
+        let structured = match &original_output {
+            syn::ReturnType::Type(_, ret_type) => returns_structured_content(ret_type),
+            syn::ReturnType::Default => false,
+        };
+        if !structured {
+            return Err(syn::Error::new_spanned(
+                output_schema,
+                "output_schema requires the tool to return `Json<T>` or `CallToolResult`, optionally wrapped in a `Result`",
+            ));
+        }
+    }
     // Handle output_schema - either explicit or generated from return type
     let output_schema_expr = attribute.output_schema.or_else(|| {
         // Try to generate schema from return type
-        match &fn_item.sig.output {
+        match &original_output {
             syn::ReturnType::Type(_, ret_type) => extract_schema_from_return_type(ret_type),
             _ => None,
         }
@@ -320,8 +487,16 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
     } else {
         fn_item.attrs.iter().try_fold(None, extract_doc_line)?
     };
+    check_description(
+        "tool",
+        description_expr.is_some(),
+        fn_ident,
+        cfg!(feature = "strict"),
+    )?;
+    let (name, name_span) = route_name(attribute.name.as_ref(), fn_ident);
+    check_name("tool", &name, name_span)?;
     let resolved_tool_attr = ResolvedToolAttribute {
-        name: attribute.name.unwrap_or_else(|| fn_ident.to_string()),
+        name,
         description: description_expr,
         input_schema: input_schema_expr,
         output_schema: output_schema_expr,
@@ -332,13 +507,25 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
         meta: attribute.meta,
     };
     let tool_attr_fn = resolved_tool_attr.into_fn(tool_attr_fn_ident)?;
-    // modify the the input function
+    let omit_send = cfg!(feature = "local") || attribute.local;
+    box_future(&mut fn_item, omit_send)?;
+    if let Some(handler) = &mut handler {
+        box_future(handler, omit_send)?;
+    }
+    Ok(quote! {
+        #tool_attr_fn
+        #fn_item
+        #handler
+    })
+}
+
+/// Turn an async `fn_item` into a function returning a boxed future.
+fn box_future(fn_item: &mut ImplItemFn, omit_send: bool) -> syn::Result<()> {
     if fn_item.sig.asyncness.is_some() {
         // 1. remove asyncness from sig
         // 2. make return type: `std::pin::Pin<Box<dyn std::future::Future<Output = #ReturnType> + Send + '_>>`
         //    (omit `+ Send` when the `local` crate feature is active or `#[tool(local)]` is used)
         // 3. make body: { Box::pin(async move { #body }) }
-        let omit_send = cfg!(feature = "local") || attribute.local;
         let new_output = syn::parse2::<ReturnType>({
             let mut lt = quote! { 'static };
             if let Some(receiver) = fn_item.sig.receiver() {
@@ -375,10 +562,7 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
         fn_item.sig.output = new_output;
         fn_item.block = new_block;
     }
-    Ok(quote! {
-        #tool_attr_fn
-        #fn_item
-    })
+    Ok(())
 }
 
 #[cfg(test)]
@@ -400,6 +584,44 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_route_options() -> syn::Result<()> {
+        let attr = quote! {
+            timeout = "2m", max_concurrency = 4, on_busy = "reject", scopes = ["files:write"]
+        };
+        let attribute = ToolAttribute::from_list(&NestedMeta::parse_meta_list(attr)?)?;
+        let options = attribute.route_options(&quote! { S })?.to_string();
+        assert!(options.contains("from_millis (120000u64)"), "{options}");
+        assert!(
+            options.contains("ConcurrencyLimits :: new (4usize)"),
+            "{options}"
+        );
+        assert!(options.contains("LIMITS . for_type :: < S > ()"), "{options}");
+        assert!(options.contains("BusyPolicy :: Reject"), "{options}");
+        assert!(
+            options.contains(r#"with_scopes (["files:write"])"#),
+            "{options}"
+        );
+
+        for attr in [
+            quote! { timeout = "30" },
+            quote! { timeout = "0s" },
+            quote! { timeout = "1d" },
+            quote! { max_concurrency = 0 },
+            quote! { on_busy = "reject" },
+            quote! { max_concurrency = 1, on_busy = "drop" },
+            quote! { scopes = ["files:read files:write"] },
+            quote! { scopes = [""] },
+        ] {
+            let input = quote! { async fn slow(&self) {} };
+            assert!(
+                tool(attr.clone(), input).is_err(),
+                "{attr} should be rejected"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_doc_comment_description() -> syn::Result<()> {
         let attr = quote! {}; // No explicit description
@@ -456,4 +678,43 @@ mod test {
         assert!(result_str.contains("include_str"));
         Ok(())
     }
+
+    #[test]
+    fn test_metadata_validation() {
+        for (attr, input) in [
+            (
+                quote! { name = "get weather" },
+                quote! { async fn weather(&self) -> String { String::new() } },
+            ),
+            (
+                quote! { execution(task_support = "sometimes") },
+                quote! { async fn weather(&self) -> String { String::new() } },
+            ),
+            (
+                quote! { output_schema = schema() },
+                quote! { async fn weather(&self) -> Result<String, String> { Ok(String::new()) } },
+            ),
+            (
+                quote! { output_schema = schema() },
+                quote! { async fn weather(&self) {} },
+            ),
+        ] {
+            assert!(
+                tool(attr.clone(), input).is_err(),
+                "{attr} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_explicit_output_schema_on_structured_returns() -> syn::Result<()> {
+        for input in [
+            quote! { async fn weather(&self) -> Json<Weather> { todo!() } },
+            quote! { async fn weather(&self) -> Result<Json<Weather>, ErrorData> { todo!() } },
+            quote! { async fn weather(&self) -> Result<CallToolResult, ErrorData> { todo!() } },
+        ] {
+            tool(quote! { output_schema = schema() }, input)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,348 @@
+//! Plain function arguments for `#[tool]` (see `lib.rs`).
+//!
+//! A tool like `fn add(&self, #[arg(description = "...")] a: i64, b: Option<i64>)`
+//! gets a local `Deserialize + JsonSchema` struct with one field per argument,
+//! and a hidden `__add_tool_handler` that takes the `JsonObject` extractor in
+//! their place, deserializes it into that struct and calls `add`. The tool
+//! itself keeps its signature, so it can still be called directly, and
+//! `#[tool_router]` routes to the handler instead.
+
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Expr, FnArg, Ident, ImplItemFn, ReturnType, Signature, Type};
+
+use crate::common::parse_attribute_args;
+
+#[derive(FromMeta, Default, Debug)]
+#[darling(default)]
+struct ArgAttribute {
+    /// Description of the argument in the input schema
+    description: Option<String>,
+    /// Use `Default::default()` when the argument is missing
+    default: bool,
+}
+
+struct FlatArgument {
+    ident: Ident,
+    ty: Type,
+    attribute: ArgAttribute,
+}
+
+pub struct FlatArguments {
+    arguments: Vec<FlatArgument>,
+    /// For each input of the tool, whether it is one of `arguments`.
+    is_argument: Vec<bool>,
+}
+
+/// Types that are never extractors, so they are tool arguments even without
+/// `#[arg]`.
+fn is_plain_type(ty: &Type) -> bool {
+    const PLAIN: &[&str] = &[
+        "bool", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+        "u128", "usize", "f32", "f64", "String", "Value", "Option", "Vec", "HashMap", "BTreeMap",
+        "HashSet", "BTreeSet",
+    ];
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| PLAIN.iter().any(|plain| segment.ident == plain)),
+        Type::Paren(paren) => is_plain_type(&paren.elem),
+        _ => false,
+    }
+}
+
+fn is_parameters_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Parameters"))
+}
+
+fn is_argument(input: &FnArg) -> bool {
+    match input {
+        FnArg::Typed(pat_type) => {
+            pat_type.attrs.iter().any(|attr| attr.path().is_ident("arg"))
+                || is_plain_type(&pat_type.ty)
+        }
+        FnArg::Receiver(_) => false,
+    }
+}
+
+/// The function a tool with signature `sig` is routed to: its handler when
+/// it takes plain arguments, the tool itself otherwise.
+pub fn handler_ident(sig: &Signature) -> Ident {
+    if sig.inputs.iter().any(is_argument) {
+        format_ident!("__{}_tool_handler", sig.ident)
+    } else {
+        sig.ident.clone()
+    }
+}
+
+impl FlatArguments {
+    fn struct_ident() -> Ident {
+        format_ident!("__RmcpToolArguments")
+    }
+
+    /// The definition of the struct the arguments are deserialized into.
+    fn struct_def(&self) -> TokenStream {
+        let struct_ident = Self::struct_ident();
+        let fields = self.arguments.iter().map(|argument| {
+            let FlatArgument {
+                ident,
+                ty,
+                attribute,
+            } = argument;
+            let description = attribute
+                .description
+                .as_ref()
+                .map(|description| quote! { #[doc = #description] });
+            let default = attribute.default.then(|| quote! { #[serde(default)] });
+            quote! {
+                #description
+                #default
+                #ident: #ty
+            }
+        });
+        quote! {
+            #[allow(dead_code, non_camel_case_types)]
+            #[derive(rmcp::serde::Deserialize, rmcp::schemars::JsonSchema)]
+            #[serde(crate = "rmcp::serde")]
+            #[schemars(crate = "rmcp::schemars")]
+            struct #struct_ident {
+                #(#fields,)*
+            }
+        }
+    }
+
+    /// The input schema of the tool.
+    pub fn input_schema(&self) -> syn::Result<Expr> {
+        let struct_def = self.struct_def();
+        let struct_ident = Self::struct_ident();
+        syn::parse2::<Expr>(quote! {
+            {
+                #struct_def
+                rmcp::handler::server::common::schema_for_type::<#struct_ident>()
+            }
+        })
+    }
+
+    /// The handler of the tool `fn_item`, which its siblings are reached
+    /// through `prefix`: it takes a `JsonObject` in place of the arguments,
+    /// after the receiver and the extractors before them, and answers with
+    /// invalid params if they fail to deserialize.
+    pub fn handler(&self, fn_item: &ImplItemFn, prefix: &TokenStream) -> syn::Result<ImplItemFn> {
+        let ident = &fn_item.sig.ident;
+        let mut inputs = Vec::<FnArg>::new();
+        let mut call_arguments = Vec::new();
+        let mut arguments = self.arguments.iter();
+        let mut has_object = false;
+        for (index, (input, is_argument)) in
+            fn_item.sig.inputs.iter().zip(&self.is_argument).enumerate()
+        {
+            match input {
+                FnArg::Receiver(_) => {
+                    inputs.push(input.clone());
+                    call_arguments.push(quote! { self });
+                }
+                FnArg::Typed(_) if *is_argument => {
+                    // the extractor takes the place of the first argument
+                    if !has_object {
+                        inputs.push(syn::parse_quote! {
+                            __rmcp_tool_arguments: rmcp::model::JsonObject
+                        });
+                        has_object = true;
+                    }
+                    let argument = arguments.next().expect("an argument per flag");
+                    let argument_ident = &argument.ident;
+                    call_arguments.push(quote! { #argument_ident });
+                }
+                FnArg::Typed(pat_type) => {
+                    let extractor = format_ident!("__rmcp_extractor_{index}");
+                    let ty = &pat_type.ty;
+                    inputs.push(syn::parse_quote! { #extractor: #ty });
+                    call_arguments.push(quote! { #extractor });
+                }
+            }
+        }
+
+        let struct_def = self.struct_def();
+        let struct_ident = Self::struct_ident();
+        let bindings = self.arguments.iter().map(|argument| &argument.ident);
+        let output = match &fn_item.sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => quote! { #ty },
+        };
+        let call = if fn_item.sig.asyncness.is_some() {
+            quote! { #prefix #ident(#(#call_arguments),*).await }
+        } else {
+            quote! { #prefix #ident(#(#call_arguments),*) }
+        };
+        let mut sig = fn_item.sig.clone();
+        sig.ident = handler_ident(&fn_item.sig);
+        sig.inputs = inputs.into_iter().collect();
+        sig.output = syn::parse2(quote! { -> Result<#output, rmcp::ErrorData> })?;
+        let doc_comment = format!("Generated handler deserializing the arguments of `{ident}`");
+        let vis = &fn_item.vis;
+        syn::parse2(quote! {
+            #[doc = #doc_comment]
+            #[doc(hidden)]
+            #vis #sig {
+                #struct_def
+                let #struct_ident { #(#bindings),* } = match rmcp::serde_json::from_value(
+                    rmcp::serde_json::Value::Object(__rmcp_tool_arguments),
+                ) {
+                    Ok(arguments) => arguments,
+                    Err(error) => {
+                        return Err(rmcp::ErrorData::invalid_params(
+                            format!("failed to deserialize parameters: {error}"),
+                            None,
+                        ));
+                    }
+                };
+                Ok(#call)
+            }
+        })
+    }
+}
+
+/// Collect the plain arguments of a tool, removing their `#[arg]`
+/// attributes. Returns `None` if it has no such arguments.
+pub fn take_flat_arguments(fn_item: &mut ImplItemFn) -> syn::Result<Option<FlatArguments>> {
+    let mut arguments = Vec::new();
+    let mut is_argument = Vec::new();
+    let mut has_parameters = false;
+    for input in fn_item.sig.inputs.iter_mut() {
+        let FnArg::Typed(pat_type) = input else {
+            is_argument.push(false);
+            continue;
+        };
+        has_parameters |= is_parameters_type(&pat_type.ty);
+        let arg_attr = pat_type
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("arg"));
+        let attribute = match arg_attr {
+            Some(position) => {
+                parse_attribute_args::<ArgAttribute>(&pat_type.attrs.remove(position))?
+            }
+            None if is_plain_type(&pat_type.ty) => ArgAttribute::default(),
+            None => {
+                is_argument.push(false);
+                continue;
+            }
+        };
+        let syn::Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.pat,
+                "tool arguments must be plain identifiers, e.g. `a: i64`",
+            ));
+        };
+        if pat_ident.by_ref.is_some() || pat_ident.subpat.is_some() {
+            return Err(syn::Error::new_spanned(
+                pat_ident,
+                "tool arguments must be plain identifiers, e.g. `a: i64`",
+            ));
+        }
+        is_argument.push(true);
+        arguments.push(FlatArgument {
+            ident: pat_ident.ident.clone(),
+            ty: (*pat_type.ty).clone(),
+            attribute,
+        });
+    }
+    if arguments.is_empty() {
+        return Ok(None);
+    }
+    if has_parameters {
+        return Err(syn::Error::new_spanned(
+            &fn_item.sig,
+            "a tool takes either `Parameters<T>` or plain arguments, not both",
+        ));
+    }
+    Ok(Some(FlatArguments {
+        arguments,
+        is_argument,
+    }))
+}
+
+
+#[cfg(test)]
+mod test {
+    use quote::ToTokens;
+
+    use super::*;
+
+    #[test]
+    fn test_flat_arguments() -> syn::Result<()> {
+        let mut fn_item: ImplItemFn = syn::parse_quote! {
+            async fn add(
+                &self,
+                peer: Peer<RoleServer>,
+                #[arg(description = "first")] a: i64,
+                b: Option<i64>,
+                #[arg] point: Point,
+                meta: Meta,
+            ) -> String {
+                (a + b.unwrap_or_default()).to_string()
+            }
+        };
+        let flat = take_flat_arguments(&mut fn_item)?.expect("plain arguments");
+        let names: Vec<_> = flat
+            .arguments
+            .iter()
+            .map(|argument| argument.ident.to_string())
+            .collect();
+        assert_eq!(names, ["a", "b", "point"]);
+        // the tool keeps its signature, without the `#[arg]` attributes
+        let inputs = fn_item.sig.inputs.to_token_stream().to_string();
+        assert_eq!(
+            inputs,
+            "& self , peer : Peer < RoleServer > , a : i64 , b : Option < i64 > , point : Point , meta : Meta ,"
+        );
+        assert_eq!(handler_ident(&fn_item.sig), "__add_tool_handler");
+
+        let handler = flat.handler(&fn_item, &quote::quote! { Self:: })?;
+        assert_eq!(handler.sig.ident, "__add_tool_handler");
+        let inputs = handler.sig.inputs.to_token_stream().to_string();
+        assert_eq!(
+            inputs,
+            "& self , __rmcp_extractor_1 : Peer < RoleServer > , __rmcp_tool_arguments : rmcp :: model :: JsonObject , __rmcp_extractor_5 : Meta"
+        );
+        let output = handler.sig.output.to_token_stream().to_string();
+        assert_eq!(output, "-> Result < String , rmcp :: ErrorData >");
+        let body = handler.block.to_token_stream().to_string();
+        assert!(body.contains(
+            "Self :: add (self , __rmcp_extractor_1 , a , b , point , __rmcp_extractor_5) . await"
+        ));
+        let schema = flat.input_schema()?.to_token_stream().to_string();
+        assert!(schema.contains("# [doc = \"first\"] a : i64"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extractors_only() -> syn::Result<()> {
+        let mut fn_item: ImplItemFn = syn::parse_quote! {
+            async fn get(&self, Parameters(request): Parameters<Request>, peer: Peer<RoleServer>) {}
+        };
+        assert!(take_flat_arguments(&mut fn_item)?.is_none());
+        assert_eq!(fn_item.sig.inputs.len(), 3);
+        assert_eq!(handler_ident(&fn_item.sig), "get");
+        Ok(())
+    }
+
+    #[test]
+    fn test_flat_arguments_errors() {
+        let mut fn_item: ImplItemFn = syn::parse_quote! {
+            async fn get(&self, Parameters(request): Parameters<Request>, a: i64) {}
+        };
+        assert!(take_flat_arguments(&mut fn_item).is_err());
+        let mut fn_item: ImplItemFn = syn::parse_quote! {
+            async fn get(&self, (a, b): (i64, i64), #[arg] Point { x, y }: Point) {}
+        };
+        assert!(take_flat_arguments(&mut fn_item).is_err());
+    }
+}
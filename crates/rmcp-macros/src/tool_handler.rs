@@ -65,14 +65,13 @@ pub fn tool_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenS
         let tool_list_fn = syn::parse2::<ImplItem>(quote! {
             async fn list_tools(
                 &self,
-                _request: Option<rmcp::model::PaginatedRequestParams>,
-                _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+                request: Option<rmcp::model::PaginatedRequestParams>,
+                context: rmcp::service::RequestContext<rmcp::RoleServer>,
             ) -> Result<rmcp::model::ListToolsResult, rmcp::ErrorData> {
-                Ok(rmcp::model::ListToolsResult{
-                    tools: #router.list_all(),
-                    meta: #result_meta,
-                    next_cursor: None,
-                })
+                let cursor = request.and_then(|request| request.cursor);
+                let mut result = #router.list_page(cursor.as_deref(), &context.extensions)?;
+                result.meta = #result_meta;
+                Ok(result)
             }
         })?;
         item_impl.items.push(tool_list_fn);
@@ -107,13 +106,16 @@ pub fn tool_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenS
 pub(crate) enum CallerCapability {
     Tools,
     Prompts,
+    Resources,
+    Completions,
     Tasks,
 }
 
 /// Build a `get_info()` method that returns `ServerInfo` with the appropriate capabilities.
 ///
 /// The caller declares its own capability via `caller`. Sibling handler attributes
-/// (`prompt_handler`, `task_handler`, `tool_handler`) are detected automatically
+/// (`prompt_handler`, `resource_handler`, `completion_handler`, `task_handler`, `tool_handler`) are
+/// detected automatically
 /// and their capabilities are included.
 pub(crate) fn build_get_info(
     item_impl: &ItemImpl,
@@ -126,6 +128,10 @@ pub(crate) fn build_get_info(
         caller == CallerCapability::Tools || has_sibling_handler(item_impl, "tool_handler");
     let has_prompts =
         caller == CallerCapability::Prompts || has_sibling_handler(item_impl, "prompt_handler");
+    let has_resources =
+        caller == CallerCapability::Resources || has_sibling_handler(item_impl, "resource_handler");
+    let has_completions = caller == CallerCapability::Completions
+        || has_sibling_handler(item_impl, "completion_handler");
     let has_tasks =
         caller == CallerCapability::Tasks || has_sibling_handler(item_impl, "task_handler");
 
@@ -136,6 +142,12 @@ pub(crate) fn build_get_info(
     if has_prompts {
         capability_calls.push(quote! { .enable_prompts() });
     }
+    if has_resources {
+        capability_calls.push(quote! { .enable_resources() });
+    }
+    if has_completions {
+        capability_calls.push(quote! { .enable_completions() });
+    }
     if has_tasks {
         capability_calls.push(quote! { .enable_tasks() });
     }
@@ -7,7 +7,16 @@
 use darling::{FromMeta, ast::NestedMeta};
 use proc_macro2::TokenStream;
 use quote::{ToTokens, format_ident, quote};
-use syn::{Ident, ImplItem, ItemImpl, Visibility};
+use syn::{
+    Attribute, Ident, ImplItem, ImplItemFn, Item, ItemImpl, ItemMod, ItemTrait, Signature,
+    TraitItem, TraitItemFn, Visibility, WherePredicate,
+};
+
+use crate::{
+    tool::ToolAttribute,
+    tool_args::handler_ident,
+    validation::{RouteNames, route_name},
+};
 
 #[derive(FromMeta)]
 #[darling(default)]
@@ -29,14 +38,76 @@ impl Default for ToolRouterAttribute {
     }
 }
 
+fn is_tool_attr(attr: &Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "tool")
+}
+
+/// The `.with_route(...)` calls for the tools with signatures `tools`, which
+/// are reached through `prefix`, e.g. `Self::`, in a router for `server`.
+fn routes(
+    tools: Vec<(&Signature, &Attribute)>,
+    prefix: TokenStream,
+    server: TokenStream,
+) -> syn::Result<TokenStream> {
+    let mut routers = TokenStream::new();
+    let mut names = RouteNames::new("tool");
+    for (sig, attr) in tools {
+        let tool_attr_fn_ident = format_ident!("{}_tool_attr", sig.ident);
+        let handler = handler_ident(sig);
+        let attribute = ToolAttribute::from_attribute(attr)?;
+        let (name, span) = route_name(attribute.name.as_ref(), &sig.ident);
+        names.insert(name, span)?;
+        let options = attribute.route_options(&server)?;
+        if options.is_empty() {
+            routers.extend(quote! {
+                .with_route((#prefix #tool_attr_fn_ident(), #prefix #handler))
+            })
+        } else {
+            routers.extend(quote! {
+                .with_route(
+                    rmcp::handler::server::router::tool::ToolRoute::new(
+                        #prefix #tool_attr_fn_ident(),
+                        #prefix #handler,
+                    )
+                    #options
+                )
+            })
+        }
+    }
+    Ok(routers)
+}
+
 pub fn tool_router(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
     let attr_args = NestedMeta::parse_meta_list(attr)?;
-    let ToolRouterAttribute {
+    let attribute = ToolRouterAttribute::from_list(&attr_args)?;
+    match syn::parse2::<Item>(input)? {
+        Item::Impl(item_impl) => impl_tool_router(item_impl, attribute),
+        item @ (Item::Trait(_) | Item::Mod(_)) if attribute.server_handler => {
+            Err(syn::Error::new_spanned(
+                item,
+                "`server_handler` is only supported on inherent impl blocks (e.g. `impl MyType { ... }`)",
+            ))
+        }
+        Item::Trait(item_trait) => trait_tool_router(item_trait, attribute),
+        Item::Mod(item_mod) => module_tool_router(item_mod, attribute),
+        item => Err(syn::Error::new_spanned(
+            item,
+            "#[tool_router] applies to an impl block, a trait or an inline module",
+        )),
+    }
+}
+
+fn impl_tool_router(
+    mut item_impl: ItemImpl,
+    ToolRouterAttribute {
         router,
         vis,
         server_handler,
-    } = ToolRouterAttribute::from_list(&attr_args)?;
-    let mut item_impl = syn::parse2::<ItemImpl>(input)?;
+    }: ToolRouterAttribute,
+) -> syn::Result<TokenStream> {
     // find all function marked with `#[rmcp::tool]`
     let tool_attr_fns: Vec<_> = item_impl
         .items
@@ -46,29 +117,18 @@ pub fn tool_router(attr: TokenStream, input: TokenStream) -> syn::Result<TokenSt
                 fn_item
                     .attrs
                     .iter()
-                    .any(|attr| {
-                        attr.path()
-                            .segments
-                            .last()
-                            .is_some_and(|seg| seg.ident == "tool")
-                    })
-                    .then_some(&fn_item.sig.ident)
+                    .find(|attr| is_tool_attr(attr))
+                    .map(|attr| (&fn_item.sig, attr))
             } else {
                 None
             }
         })
         .collect();
-    let mut routers = Vec::with_capacity(tool_attr_fns.len());
-    for handler in tool_attr_fns {
-        let tool_attr_fn_ident = format_ident!("{handler}_tool_attr");
-        routers.push(quote! {
-            .with_route((Self::#tool_attr_fn_ident(), Self::#handler))
-        })
-    }
+    let routers = routes(tool_attr_fns, quote! { Self:: }, quote! { Self })?;
     let router_fn = syn::parse2::<ImplItem>(quote! {
         #vis fn #router() -> rmcp::handler::server::router::tool::ToolRouter<Self> {
             rmcp::handler::server::router::tool::ToolRouter::<Self>::new()
-                #(#routers)*
+                #routers
         }
     })?;
     item_impl.items.push(router_fn);
@@ -95,6 +155,159 @@ pub fn tool_router(attr: TokenStream, input: TokenStream) -> syn::Result<TokenSt
     })
 }
 
+/// Tools are free functions of the module; the router is generic over the
+/// server, as the tools do not take `&self`. They are expanded here, so the
+/// handlers of tools with plain arguments call them without `Self::`.
+fn module_tool_router(
+    mut item_mod: ItemMod,
+    ToolRouterAttribute { router, vis, .. }: ToolRouterAttribute,
+) -> syn::Result<TokenStream> {
+    let Some((_, items)) = &mut item_mod.content else {
+        return Err(syn::Error::new_spanned(
+            &item_mod,
+            "#[tool_router] needs an inline module, e.g. `mod tools { ... }`",
+        ));
+    };
+    let mut tools = Vec::new();
+    for item in std::mem::take(items) {
+        let Item::Fn(mut fn_item) = item else {
+            items.push(item);
+            continue;
+        };
+        let Some(position) = fn_item.attrs.iter().position(is_tool_attr) else {
+            items.push(Item::Fn(fn_item));
+            continue;
+        };
+        let attr = fn_item.attrs.remove(position);
+        let tool_args = match &attr.meta {
+            syn::Meta::List(list) => list.tokens.clone(),
+            _ => TokenStream::new(),
+        };
+        let sig = fn_item.sig.clone();
+        let expanded =
+            crate::tool::tool_in(tool_args, fn_item.into_token_stream(), TokenStream::new())?;
+        items.extend(syn::parse2::<syn::File>(expanded)?.items);
+        tools.push((sig, attr));
+    }
+    let routers = routes(
+        tools.iter().map(|(sig, attr)| (sig, attr)).collect(),
+        TokenStream::new(),
+        quote! { S },
+    )?;
+    let vis = vis.unwrap_or_else(|| syn::parse_quote! { pub });
+    let doc_comment = format!(
+        "Generated tool router for the tools of module `{}`",
+        item_mod.ident
+    );
+    items.push(syn::parse2::<Item>(quote! {
+        #[doc = #doc_comment]
+        #vis fn #router<S>() -> rmcp::handler::server::router::tool::ToolRouter<S>
+        where
+            S: rmcp::service::MaybeSend + 'static,
+        {
+            rmcp::handler::server::router::tool::ToolRouter::<S>::new()
+                #routers
+        }
+    })?);
+    Ok(item_mod.into_token_stream())
+}
+
+/// Tools are default methods of the trait, expanded here since `#[tool]`
+/// only knows about impl blocks. The router and the generated functions are
+/// bounded on `Self: Sized`, so the trait stays dyn compatible.
+fn trait_tool_router(
+    mut item_trait: ItemTrait,
+    ToolRouterAttribute { router, vis, .. }: ToolRouterAttribute,
+) -> syn::Result<TokenStream> {
+    if let Some(vis) = vis {
+        return Err(syn::Error::new_spanned(
+            vis,
+            "`vis` does not apply to the router of a trait",
+        ));
+    }
+    let mut tools = Vec::new();
+    let mut items = Vec::with_capacity(item_trait.items.len());
+    for item in std::mem::take(&mut item_trait.items) {
+        let TraitItem::Fn(mut fn_item) = item else {
+            items.push(item);
+            continue;
+        };
+        let Some(position) = fn_item.attrs.iter().position(is_tool_attr) else {
+            items.push(TraitItem::Fn(fn_item));
+            continue;
+        };
+        let attr = fn_item.attrs.remove(position);
+        let Some(block) = fn_item.default.take() else {
+            return Err(syn::Error::new_spanned(
+                &fn_item.sig,
+                "a tool in a trait needs a default body",
+            ));
+        };
+        let attribute = ToolAttribute::from_attribute(&attr)?;
+        let boxed_send =
+            fn_item.sig.asyncness.is_some() && !(cfg!(feature = "local") || attribute.local);
+        let tool_args = match &attr.meta {
+            syn::Meta::List(list) => list.tokens.clone(),
+            _ => TokenStream::new(),
+        };
+        let method = ImplItemFn {
+            attrs: fn_item.attrs,
+            vis: Visibility::Inherited,
+            defaultness: None,
+            sig: fn_item.sig,
+            block,
+        };
+        let sig = method.sig.clone();
+        let expanded = crate::tool::tool(tool_args, method.into_token_stream())?;
+        let expanded = syn::parse2::<ItemImpl>(quote! { impl Tools { #expanded } })?;
+        for item in expanded.items {
+            let ImplItem::Fn(generated) = item else {
+                continue;
+            };
+            let mut sig = generated.sig;
+            let bound: Option<WherePredicate> = if sig.receiver().is_none() {
+                Some(syn::parse_quote! { Self: Sized })
+            } else if boxed_send {
+                // the boxed future holds `&self` and must be `Send`
+                Some(syn::parse_quote! { Self: Sync })
+            } else {
+                None
+            };
+            if let Some(bound) = bound {
+                sig.generics.make_where_clause().predicates.push(bound);
+            }
+            items.push(TraitItem::Fn(TraitItemFn {
+                attrs: generated.attrs,
+                sig,
+                default: Some(generated.block),
+                semi_token: None,
+            }));
+        }
+        tools.push((sig, attr));
+    }
+    let routers = routes(
+        tools.iter().map(|(sig, attr)| (sig, attr)).collect(),
+        quote! { Self:: },
+        quote! { Self },
+    )?;
+    let doc_comment = format!(
+        "Generated tool router for the tools of `{}`",
+        item_trait.ident
+    );
+    items.push(syn::parse2::<TraitItem>(quote! {
+        #[doc = #doc_comment]
+        fn #router() -> rmcp::handler::server::router::tool::ToolRouter<Self>
+        where
+            Self: Sized + rmcp::service::MaybeSend + 'static,
+        {
+            rmcp::handler::server::router::tool::ToolRouter::<Self>::new()
+                #routers
+        }
+    })?);
+    item_trait.items = items;
+    Ok(item_trait.into_token_stream())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -137,4 +350,72 @@ mod test {
         assert!(server_handler);
         Ok(())
     }
+
+    #[test]
+    fn tool_router_on_module_adds_generic_router() -> syn::Result<()> {
+        let input = quote! {
+            mod math {
+                #[tool(description = "Add")]
+                pub async fn add(a: i64, b: i64) -> String {
+                    (a + b).to_string()
+                }
+            }
+        };
+        let result = tool_router(TokenStream::new(), input)?.to_string();
+        assert!(result.contains("pub fn tool_router < S > ()"));
+        assert!(result.contains("with_route ((add_tool_attr () , __add_tool_handler))"));
+        assert!(result.contains("Ok (add (a , b) . await)"));
+        Ok(())
+    }
+
+    #[test]
+    fn tool_router_on_trait_expands_default_tools() -> syn::Result<()> {
+        let input = quote! {
+            trait Greeter {
+                #[tool(description = "Greet")]
+                async fn greet(&self) -> String {
+                    "hello".to_string()
+                }
+            }
+        };
+        let result = tool_router(quote! { router = greeter_router }, input)?.to_string();
+        assert!(result.contains("fn greet_tool_attr ()"));
+        assert!(result.contains("Self : Sized"));
+        assert!(result.contains("fn greeter_router ()"));
+        assert!(result.contains("with_route ((Self :: greet_tool_attr () , Self :: greet))"));
+        Ok(())
+    }
+
+    #[test]
+    fn tool_router_on_trait_rejects_tools_without_body() {
+        let input = quote! {
+            trait Greeter {
+                #[tool]
+                fn greet(&self) -> String;
+            }
+        };
+        assert!(tool_router(TokenStream::new(), input).is_err());
+    }
+
+    #[test]
+    fn tool_router_rejects_server_handler_outside_impl() {
+        let input = quote! {
+            mod tools {}
+        };
+        assert!(tool_router(quote! { server_handler }, input).is_err());
+    }
+
+    #[test]
+    fn tool_router_rejects_duplicate_names() {
+        let input = quote! {
+            impl Server {
+                #[tool]
+                async fn search(&self) {}
+                #[tool(name = "search")]
+                async fn find(&self) {}
+            }
+        };
+        let error = tool_router(TokenStream::new(), input).unwrap_err();
+        assert!(error.to_string().contains("duplicate tool name \"search\""));
+    }
 }
@@ -0,0 +1,263 @@
+//! Compile-time checks of tool and prompt metadata.
+//!
+//! `rmcp` checks tool names when a route is added and only warns about bad
+//! ones. The macros know the names up front, so they reject them with an error
+//! pointing at the name instead. Prompt templates are read and checked the
+//! same way, so a broken template fails the build instead of the router.
+
+use std::{collections::HashSet, path::Path};
+
+use proc_macro2::Span;
+use syn::{Ident, LitStr};
+
+/// The name of a tool or prompt, `name = "..."` or else the function's, with
+/// the span to report problems with it at.
+pub fn route_name(name: Option<&LitStr>, fn_ident: &Ident) -> (String, Span) {
+    match name {
+        Some(name) => (name.value(), name.span()),
+        None => (fn_ident.to_string(), fn_ident.span()),
+    }
+}
+
+/// Check that `name` has 1 to 128 characters, all of `A-Z`, `a-z`, `0-9`,
+/// `_`, `-` and `.`. `kind` is `"tool"` or `"prompt"`.
+pub fn check_name(kind: &str, name: &str, span: Span) -> syn::Result<()> {
+    if name.is_empty() || name.len() > 128 {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "{kind} name must be between 1 and 128 characters long (current: {})",
+                name.len()
+            ),
+        ));
+    }
+    if let Some(invalid) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "{kind} name \"{name}\" contains the invalid character {invalid:?}. \
+                 Allowed characters are: A-Z, a-z, 0-9, underscore (_), dash (-), and dot (.)"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// With `strict`, a tool or prompt needs a description, given either as
+/// `description = "..."` or as a doc comment.
+pub fn check_description(
+    kind: &str,
+    has_description: bool,
+    fn_ident: &Ident,
+    strict: bool,
+) -> syn::Result<()> {
+    if strict && !has_description {
+        return Err(syn::Error::new(
+            fn_ident.span(),
+            format!(
+                "{kind} `{fn_ident}` has no description; add `description = \"...\"` or a doc comment"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Read the prompt template at `path`, relative to the crate root unless it is
+/// absolute, and check its syntax. Returns the resolved path of the file.
+pub fn check_prompt_template(path: &LitStr) -> syn::Result<String> {
+    let relative = path.value();
+    let resolved = if Path::new(&relative).is_absolute() {
+        relative.clone()
+    } else {
+        let root = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+            syn::Error::new(
+                path.span(),
+                "CARGO_MANIFEST_DIR is not set; give the template as an absolute path",
+            )
+        })?;
+        Path::new(&root).join(&relative).display().to_string()
+    };
+    let text = std::fs::read_to_string(&resolved).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("failed to read prompt template {resolved}: {error}"),
+        )
+    })?;
+    check_template_syntax(&text).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("invalid prompt template {relative}: {error}"),
+        )
+    })?;
+    Ok(resolved)
+}
+
+/// The checks of `PromptTemplate::parse` in `rmcp`, with the same messages,
+/// to report a bad template at compile time. `rmcp` parses the template
+/// again and answers with its error should the two ever disagree.
+fn check_template_syntax(text: &str) -> Result<(), String> {
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        if let Some(directive) = line
+            .trim()
+            .strip_prefix("{{@")
+            .and_then(|rest| rest.strip_suffix("}}"))
+        {
+            let directive = directive.trim();
+            let (keyword, value) = directive
+                .split_once(char::is_whitespace)
+                .map(|(keyword, value)| (keyword, value.trim()))
+                .unwrap_or((directive, ""));
+            match (keyword, value) {
+                ("user" | "assistant", "") => {}
+                ("resource" | "image", uri) if !uri.is_empty() => {}
+                _ => {
+                    return Err(format!(
+                        "line {line_number}: unknown directive `{{{{@{directive}}}}}`"
+                    ));
+                }
+            }
+            continue;
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| format!("line {line_number}: unclosed placeholder"))?;
+            let placeholder = after[..end].trim();
+            if placeholder.starts_with('@') {
+                return Err(format!(
+                    "line {line_number}: a directive must be on a line of its own"
+                ));
+            }
+            let name = placeholder
+                .strip_suffix('?')
+                .or_else(|| placeholder.split_once('|').map(|(name, _)| name))
+                .map(str::trim)
+                .unwrap_or(placeholder);
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+            {
+                return Err(format!(
+                    "line {line_number}: invalid placeholder `{{{{{placeholder}}}}}`"
+                ));
+            }
+            rest = &after[end + 2..];
+        }
+    }
+    Ok(())
+}
+
+/// The names of the routes of one router, so a name is only used once.
+pub struct RouteNames {
+    kind: &'static str,
+    names: HashSet<String>,
+}
+
+impl RouteNames {
+    pub fn new(kind: &'static str) -> Self {
+        Self {
+            kind,
+            names: HashSet::new(),
+        }
+    }
+
+    pub fn insert(&mut self, name: String, span: Span) -> syn::Result<()> {
+        if self.names.insert(name.clone()) {
+            return Ok(());
+        }
+        Err(syn::Error::new(
+            span,
+            format!(
+                "duplicate {} name \"{name}\" in this router; set a distinct `name = \"...\"`",
+                self.kind
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use quote::format_ident;
+
+    use super::*;
+
+    #[test]
+    fn test_check_name() {
+        let span = Span::call_site();
+        assert!(check_name("tool", "get_weather", span).is_ok());
+        assert!(check_name("tool", "admin.tools-list_v2", span).is_ok());
+        assert!(check_name("tool", &"a".repeat(128), span).is_ok());
+        assert!(check_name("tool", "", span).is_err());
+        assert!(check_name("tool", &"a".repeat(129), span).is_err());
+        assert!(check_name("tool", "get weather", span).is_err());
+        assert!(check_name("prompt", "review,code", span).is_err());
+        assert!(check_name("prompt", "café", span).is_err());
+    }
+
+    #[test]
+    fn test_check_description() {
+        let ident = format_ident!("undocumented");
+        assert!(check_description("tool", false, &ident, false).is_ok());
+        assert!(check_description("tool", true, &ident, true).is_ok());
+        assert!(check_description("tool", false, &ident, true).is_err());
+    }
+
+    #[test]
+    fn test_check_template_syntax() {
+        assert!(check_template_syntax("Greet {{name}} {{style|warm, kind}}{{end?}}.").is_ok());
+        assert!(
+            check_template_syntax("{{@user}}\n{{@resource file:///a.rs}}\n  {{@assistant}}  ")
+                .is_ok()
+        );
+        assert_eq!(
+            check_template_syntax("ok\nGreet {{name").unwrap_err(),
+            "line 2: unclosed placeholder"
+        );
+        assert_eq!(
+            check_template_syntax("Greet {{the name}}").unwrap_err(),
+            "line 1: invalid placeholder `{{the name}}`"
+        );
+        assert_eq!(
+            check_template_syntax("{{@system}}").unwrap_err(),
+            "line 1: unknown directive `{{@system}}`"
+        );
+        assert_eq!(
+            check_template_syntax("{{@resource}}").unwrap_err(),
+            "line 1: unknown directive `{{@resource}}`"
+        );
+        assert_eq!(
+            check_template_syntax("Now {{@assistant}}").unwrap_err(),
+            "line 1: a directive must be on a line of its own"
+        );
+    }
+
+    #[test]
+    fn test_check_prompt_template() {
+        let path = std::env::temp_dir().join("rmcp_macros_check_prompt_template.md");
+        let literal = LitStr::new(&path.display().to_string(), Span::call_site());
+        std::fs::write(&path, "Greet {{name}}").unwrap();
+        assert_eq!(
+            check_prompt_template(&literal).unwrap(),
+            path.display().to_string()
+        );
+        std::fs::write(&path, "Greet {{name").unwrap();
+        assert!(check_prompt_template(&literal).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(check_prompt_template(&literal).is_err());
+    }
+
+    #[test]
+    fn test_route_names() {
+        let mut names = RouteNames::new("tool");
+        assert!(names.insert("a".into(), Span::call_site()).is_ok());
+        assert!(names.insert("b".into(), Span::call_site()).is_ok());
+        assert!(names.insert("a".into(), Span::call_site()).is_err());
+    }
+}
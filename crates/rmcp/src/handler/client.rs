@@ -5,7 +5,8 @@ use crate::{
     error::ErrorData as McpError,
     model::*,
     service::{
-        MaybeSendFuture, NotificationContext, RequestContext, RoleClient, Service, ServiceRole,
+        MaybeSendFuture, NotificationContext, PanicPolicy, RequestContext, RoleClient, Service,
+        ServiceRole,
     },
 };
 
@@ -78,6 +79,10 @@ impl<H: ClientHandler> Service<RoleClient> for H {
     fn get_info(&self) -> <RoleClient as ServiceRole>::Info {
         self.get_info()
     }
+
+    fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy()
+    }
 }
 
 #[allow(unused_variables)]
@@ -257,6 +262,12 @@ pub trait ClientHandler: Sized + Send + Sync + 'static {
     fn get_info(&self) -> ClientInfo {
         ClientInfo::default()
     }
+
+    /// How the service reacts when one of the handlers above panics. See
+    /// [`PanicPolicy`].
+    fn panic_policy(&self) -> PanicPolicy {
+        PanicPolicy::default()
+    }
 }
 
 /// Do nothing, with default client info.
@@ -374,6 +385,10 @@ macro_rules! impl_client_handler_for_wrapper {
             fn get_info(&self) -> ClientInfo {
                 (**self).get_info()
             }
+
+            fn panic_policy(&self) -> PanicPolicy {
+                (**self).panic_policy()
+            }
         }
     };
 }
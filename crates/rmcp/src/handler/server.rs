@@ -4,14 +4,27 @@ use crate::{
     error::ErrorData as McpError,
     model::{TaskSupport, *},
     service::{
-        MaybeSendFuture, NotificationContext, RequestContext, RoleServer, Service, ServiceRole,
+        MaybeSendFuture, NotificationContext, PanicPolicy, RequestContext, RoleServer, Service,
+        ServiceRole,
     },
 };
 
 pub mod common;
+pub mod completion;
+pub mod composite;
+#[cfg(feature = "client")]
+pub mod gateway;
+pub mod layer;
+#[cfg(feature = "server-logging")]
+pub mod logging;
+pub mod pagination;
+pub mod progress;
 pub mod prompt;
-mod resource;
+pub mod rate_limit;
+pub mod resource;
 pub mod router;
+pub mod scopes;
+pub mod subscription;
 pub mod tool;
 pub mod tool_name_validation;
 pub mod wrapper;
@@ -158,6 +171,10 @@ impl<H: ServerHandler> Service<RoleServer> for H {
     fn get_info(&self) -> <RoleServer as ServiceRole>::Info {
         self.get_info()
     }
+
+    fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy()
+    }
 }
 
 macro_rules! server_handler_methods {
@@ -333,6 +350,12 @@ macro_rules! server_handler_methods {
             ServerInfo::default()
         }
 
+        /// How the service reacts when one of the handlers above panics. See
+        /// [`PanicPolicy`].
+        fn panic_policy(&self) -> PanicPolicy {
+            PanicPolicy::default()
+        }
+
         fn list_tasks(
             &self,
             request: Option<PaginatedRequestParams>,
@@ -551,6 +574,10 @@ macro_rules! impl_server_handler_for_wrapper {
                 (**self).get_info()
             }
 
+            fn panic_policy(&self) -> PanicPolicy {
+                (**self).panic_policy()
+            }
+
             fn list_tasks(
                 &self,
                 request: Option<PaginatedRequestParams>,
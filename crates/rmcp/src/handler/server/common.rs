@@ -120,6 +120,26 @@ where
     }
 }
 
+/// Extracts the cancellation token of the request being handled.
+///
+/// The token fires when the client sends `notifications/cancelled` for the request,
+/// when the connection closes and, for tools called as tasks, on
+/// `tasks/cancel`. Handlers can watch it to stop child processes or database
+/// queries cooperatively:
+///
+/// ```rust,ignore
+/// #[tool(description = "Run the test suite")]
+/// async fn test(&self, ct: CancellationToken) -> Result<String, ErrorData> {
+///     let mut child = Command::new("cargo").arg("test").spawn()?;
+///     tokio::select! {
+///         status = child.wait() => Ok(format!("{}", status?)),
+///         _ = ct.cancelled() => {
+///             child.kill().await?;
+///             Err(ErrorData::internal_error("cancelled", None))
+///         }
+///     }
+/// }
+/// ```
 impl<C> FromContextPart<C> for tokio_util::sync::CancellationToken
 where
     C: AsRequestContext,
@@ -0,0 +1,316 @@
+//! Completion handling infrastructure for MCP servers
+//!
+//! This module provides the core types and traits for implementing completion
+//! handlers in MCP servers. A completion handler suggests values for a single
+//! prompt argument or resource template variable, and can look at the arguments
+//! the client has already resolved through [`CompletionContext`].
+
+use std::{future::Future, marker::PhantomData};
+
+#[cfg(not(feature = "local"))]
+use futures::future::BoxFuture;
+
+use super::common::{AsRequestContext, FromContextPart};
+pub use super::common::{Extension, RequestId};
+use crate::{
+    RoleServer,
+    model::{
+        ArgumentInfo, CompleteRequestParams, CompleteResult, CompletionContext, CompletionInfo,
+        Reference,
+    },
+    service::{MaybeBoxFuture, MaybeSend, MaybeSendFuture, RequestContext},
+};
+
+/// Context for completion requests
+#[non_exhaustive]
+pub struct CompleteContext<'a, S> {
+    pub server: &'a S,
+    pub r#ref: Reference,
+    pub argument: ArgumentInfo,
+    pub completion_context: CompletionContext,
+    pub context: RequestContext<RoleServer>,
+}
+
+impl<'a, S> CompleteContext<'a, S> {
+    pub fn new(
+        server: &'a S,
+        request: CompleteRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Self {
+        Self {
+            server,
+            r#ref: request.r#ref,
+            argument: request.argument,
+            completion_context: request.context.unwrap_or_default(),
+            context,
+        }
+    }
+}
+
+impl<S> AsRequestContext for CompleteContext<'_, S> {
+    fn as_request_context(&self) -> &RequestContext<RoleServer> {
+        &self.context
+    }
+
+    fn as_request_context_mut(&mut self) -> &mut RequestContext<RoleServer> {
+        &mut self.context
+    }
+}
+
+/// Trait for handling completion requests
+pub trait CompleteHandler<S, A> {
+    fn handle(
+        self,
+        context: CompleteContext<'_, S>,
+    ) -> MaybeBoxFuture<'_, Result<CompleteResult, crate::ErrorData>>;
+}
+
+/// Type alias for dynamic completion handlers
+#[cfg(not(feature = "local"))]
+pub type DynCompleteHandler<S> = dyn for<'a> Fn(CompleteContext<'a, S>) -> BoxFuture<'a, Result<CompleteResult, crate::ErrorData>>
+    + Send
+    + Sync;
+
+#[cfg(feature = "local")]
+pub type DynCompleteHandler<S> =
+    dyn for<'a> Fn(
+        CompleteContext<'a, S>,
+    )
+        -> futures::future::LocalBoxFuture<'a, Result<CompleteResult, crate::ErrorData>>;
+
+/// Adapter types for macro-generated implementations
+#[allow(clippy::type_complexity)]
+pub struct AsyncCompleteAdapter<P, Fut, R>(PhantomData<fn(P) -> fn(Fut) -> R>);
+pub struct SyncCompleteAdapter<P, R>(PhantomData<fn(P) -> R>);
+pub struct SyncCompleteMethodAdapter<P, R>(PhantomData<fn(P) -> R>);
+
+/// Trait for types that can be converted into a [`CompleteResult`]
+pub trait IntoCompleteResult {
+    fn into_complete_result(self) -> Result<CompleteResult, crate::ErrorData>;
+}
+
+impl IntoCompleteResult for CompleteResult {
+    fn into_complete_result(self) -> Result<CompleteResult, crate::ErrorData> {
+        self.completion.into_complete_result()
+    }
+}
+
+impl IntoCompleteResult for CompletionInfo {
+    fn into_complete_result(self) -> Result<CompleteResult, crate::ErrorData> {
+        self.validate()
+            .map_err(|e| crate::ErrorData::internal_error(e, None))?;
+        Ok(CompleteResult::new(self))
+    }
+}
+
+/// Values beyond [`CompletionInfo::MAX_VALUES`] are dropped, and the result is
+/// marked as having more values available.
+impl IntoCompleteResult for Vec<String> {
+    fn into_complete_result(mut self) -> Result<CompleteResult, crate::ErrorData> {
+        let total = self.len();
+        let has_more = total > CompletionInfo::MAX_VALUES;
+        self.truncate(CompletionInfo::MAX_VALUES);
+        CompletionInfo::with_pagination(self, Some(total as u32), has_more)
+            .map_err(|e| crate::ErrorData::internal_error(e, None))?
+            .into_complete_result()
+    }
+}
+
+impl IntoCompleteResult for Vec<&'static str> {
+    fn into_complete_result(self) -> Result<CompleteResult, crate::ErrorData> {
+        self.into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .into_complete_result()
+    }
+}
+
+impl<T: IntoCompleteResult> IntoCompleteResult for Result<T, crate::ErrorData> {
+    fn into_complete_result(self) -> Result<CompleteResult, crate::ErrorData> {
+        self.and_then(|v| v.into_complete_result())
+    }
+}
+
+// Completion-specific extractor for the partial value typed by the user
+#[expect(clippy::exhaustive_structs, reason = "intentionally exhaustive")]
+pub struct CompletionValue(pub String);
+
+impl<S> FromContextPart<CompleteContext<'_, S>> for CompletionValue {
+    fn from_context_part(context: &mut CompleteContext<S>) -> Result<Self, crate::ErrorData> {
+        Ok(Self(context.argument.value.clone()))
+    }
+}
+
+impl<S> FromContextPart<CompleteContext<'_, S>> for ArgumentInfo {
+    fn from_context_part(context: &mut CompleteContext<S>) -> Result<Self, crate::ErrorData> {
+        Ok(context.argument.clone())
+    }
+}
+
+impl<S> FromContextPart<CompleteContext<'_, S>> for Reference {
+    fn from_context_part(context: &mut CompleteContext<S>) -> Result<Self, crate::ErrorData> {
+        Ok(context.r#ref.clone())
+    }
+}
+
+impl<S> FromContextPart<CompleteContext<'_, S>> for CompletionContext {
+    fn from_context_part(context: &mut CompleteContext<S>) -> Result<Self, crate::ErrorData> {
+        Ok(context.completion_context.clone())
+    }
+}
+
+// Macro to generate CompleteHandler implementations for various parameter combinations
+macro_rules! impl_complete_handler_for {
+    ($($T: ident)*) => {
+        impl_complete_handler_for!([] [$($T)*]);
+    };
+    // finished
+    ([$($Tn: ident)*] []) => {
+        impl_complete_handler_for!(@impl $($Tn)*);
+    };
+    ([$($Tn: ident)*] [$Tn_1: ident $($Rest: ident)*]) => {
+        impl_complete_handler_for!(@impl $($Tn)*);
+        impl_complete_handler_for!([$($Tn)* $Tn_1] [$($Rest)*]);
+    };
+    (@impl $($Tn: ident)*) => {
+        // Implementation for async methods (transformed by #[completion] macro)
+        impl<$($Tn,)* S, F, R> CompleteHandler<S, ($($Tn,)*)> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<CompleteContext<'a, S>> + MaybeSendFuture,
+            )*
+            F: FnOnce(&S, $($Tn,)*) -> MaybeBoxFuture<'_, R> + MaybeSendFuture,
+            R: IntoCompleteResult + MaybeSendFuture + 'static,
+            S: MaybeSend + 'static,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: CompleteContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<CompleteResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let service = context.server;
+                let fut = self(service, $($Tn,)*);
+                Box::pin(async move {
+                    let result = fut.await;
+                    result.into_complete_result()
+                })
+            }
+        }
+
+        // Implementation for sync methods
+        impl<$($Tn,)* S, F, R> CompleteHandler<S, SyncCompleteMethodAdapter<($($Tn,)*), R>> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<CompleteContext<'a, S>> + MaybeSendFuture,
+            )*
+            F: FnOnce(&S, $($Tn,)*) -> R + MaybeSendFuture,
+            R: IntoCompleteResult + MaybeSendFuture,
+            S: MaybeSend,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: CompleteContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<CompleteResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let service = context.server;
+                let result = self(service, $($Tn,)*);
+                Box::pin(std::future::ready(result.into_complete_result()))
+            }
+        }
+
+        // AsyncCompleteAdapter - for standalone async functions
+        impl<$($Tn,)* S, F, Fut, R> CompleteHandler<S, AsyncCompleteAdapter<($($Tn,)*), Fut, R>> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<CompleteContext<'a, S>> + MaybeSendFuture + 'static,
+            )*
+            F: FnOnce($($Tn,)*) -> Fut + MaybeSendFuture + 'static,
+            Fut: Future<Output = R> + MaybeSendFuture + 'static,
+            R: IntoCompleteResult + MaybeSendFuture + 'static,
+            S: MaybeSend + 'static,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: CompleteContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<CompleteResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                Box::pin(async move {
+                    let result = self($($Tn,)*).await;
+                    result.into_complete_result()
+                })
+            }
+        }
+
+        // SyncCompleteAdapter - for standalone sync functions
+        impl<$($Tn,)* S, F, R> CompleteHandler<S, SyncCompleteAdapter<($($Tn,)*), R>> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<CompleteContext<'a, S>> + MaybeSendFuture + 'static,
+            )*
+            F: FnOnce($($Tn,)*) -> R + MaybeSendFuture + 'static,
+            R: IntoCompleteResult + MaybeSendFuture + 'static,
+            S: MaybeSend,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: CompleteContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<CompleteResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let result = self($($Tn,)*);
+                Box::pin(std::future::ready(result.into_complete_result()))
+            }
+        }
+    };
+}
+
+impl_complete_handler_for!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vec_into_complete_result_truncates() {
+        let values: Vec<String> = (0..150).map(|i| format!("value-{i}")).collect();
+        let result = values.into_complete_result().unwrap();
+        assert_eq!(result.completion.values.len(), CompletionInfo::MAX_VALUES);
+        assert_eq!(result.completion.total, Some(150));
+        assert!(result.completion.has_more_results());
+
+        let result = vec!["rust", "go"].into_complete_result().unwrap();
+        assert_eq!(result.completion.values, ["rust", "go"]);
+        assert!(!result.completion.has_more_results());
+    }
+}
@@ -0,0 +1,939 @@
+//! Serve several independent servers as one.
+//!
+//! A [`CompositeServer`] owns any number of server-side [`Service`]s, be they
+//! [`ServerHandler`](crate::ServerHandler)s, [`Router`](super::router::Router)s
+//! or [`Layered`](super::layer::Layered) services, and presents them to the
+//! client as a single server:
+//!
+//! - `tools/list`, `prompts/list`, `resources/list` and
+//!   `resources/templates/list` return the lists of every child, sorted by
+//!   name (resources and resource templates by URI) and paginated like the
+//!   lists of a router, see [`with_page_size`](CompositeServer::with_page_size).
+//!   When two children offer the same name (or URI), the one mounted first
+//!   wins.
+//! - `tools/call`, `prompts/get`, `resources/read`, `resources/subscribe`,
+//!   `resources/unsubscribe` and `completion/complete` go to the child that
+//!   lists the tool, prompt or resource. Owners are looked up in an index that
+//!   is built when the client requests the first page of a list. A child's
+//!   `list_changed` notification drops the index of that list, and a name the
+//!   index does not know makes the composite list the children again, so
+//!   children may change their lists at any time.
+//! - Capabilities are the union of the children's, and their `instructions`
+//!   are joined in mount order.
+//! - Client notifications are delivered to every child.
+//! - A panic in any child follows the composite's
+//!   [`with_panic_policy`](CompositeServer::with_panic_policy); the
+//!   children's own policies are not consulted.
+//!
+//! Children talk to the client through the composite's
+//! [`Peer`](crate::service::Peer), so notifications they send, such as
+//! progress, logging or `list_changed`, reach the client unchanged. A composite
+//! serves a single connection and rejects messages from any other peer.
+//!
+//! [`mount_with_prefix`](CompositeServer::mount_with_prefix) puts a prefix in
+//! front of the names of a child's tools, prompts, resources and resource
+//! templates. Resource URIs are global and are never rewritten.
+//!
+//! ```rust,ignore
+//! use rmcp::handler::server::composite::CompositeServer;
+//!
+//! let server = CompositeServer::new()
+//!     .mount(CounterServer::new())
+//!     .mount_with_prefix("git_", GitServer::new());
+//! server.serve(transport).await?;
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, hash_map::Entry},
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+};
+
+use super::pagination::paginate;
+
+use crate::{
+    error::ErrorData as McpError,
+    model::{
+        ClientNotification, ClientRequest, CompleteResult, Cursor, ErrorCode, Implementation,
+        ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult, ListToolsResult,
+        PaginatedRequestParams, Prompt, Reference, RequestOptionalParam, Resource,
+        ResourceTemplate, ServerCapabilities, ServerInfo, ServerNotification, ServerResult, Tool,
+        UriTemplate,
+    },
+    service::{
+        DynService, NotificationContext, PanicPolicy, Peer, RequestContext, RoleServer, Service,
+    },
+};
+
+/// A child of a [`CompositeServer`].
+struct Mounted {
+    prefix: Option<String>,
+    service: Box<dyn DynService<RoleServer>>,
+}
+
+impl Mounted {
+    fn prefixed(&self, name: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}{name}"),
+            None => name.to_owned(),
+        }
+    }
+
+    /// The child's own name for `name`, if `name` can belong to this child.
+    fn local_name<'a>(&self, name: &'a str) -> Option<&'a str> {
+        match &self.prefix {
+            Some(prefix) => name.strip_prefix(prefix.as_str()),
+            None => Some(name),
+        }
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        DynService::get_info(self.service.as_ref()).capabilities
+    }
+
+    async fn request(
+        &self,
+        request: ClientRequest,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        DynService::handle_request(self.service.as_ref(), request, context.clone()).await
+    }
+
+    /// Every item of a paginated list, following `next_cursor` to the end.
+    async fn list_all<T>(
+        &self,
+        context: &RequestContext<RoleServer>,
+        request: impl Fn(PaginatedRequestParams) -> ClientRequest,
+        page: impl Fn(ServerResult) -> Option<(Vec<T>, Option<Cursor>)>,
+    ) -> Result<Vec<T>, McpError> {
+        let mut items = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let params = PaginatedRequestParams::default().with_cursor(cursor.clone());
+            let result = self.request(request(params), context).await?;
+            let (page_items, next_cursor) = page(result).ok_or_else(|| {
+                McpError::internal_error("unexpected response to a list request", None)
+            })?;
+            items.extend(page_items);
+            if next_cursor.is_none() || next_cursor == cursor {
+                return Ok(items);
+            }
+            cursor = next_cursor;
+        }
+    }
+
+    async fn tools(&self, context: &RequestContext<RoleServer>) -> Result<Vec<Tool>, McpError> {
+        if self.capabilities().tools.is_none() {
+            return Ok(Vec::new());
+        }
+        self.list_all(
+            context,
+            |params| ClientRequest::ListToolsRequest(RequestOptionalParam::with_param(params)),
+            |result| match result {
+                ServerResult::ListToolsResult(result) => Some((result.tools, result.next_cursor)),
+                _ => None,
+            },
+        )
+        .await
+    }
+
+    async fn prompts(&self, context: &RequestContext<RoleServer>) -> Result<Vec<Prompt>, McpError> {
+        if self.capabilities().prompts.is_none() {
+            return Ok(Vec::new());
+        }
+        self.list_all(
+            context,
+            |params| ClientRequest::ListPromptsRequest(RequestOptionalParam::with_param(params)),
+            |result| match result {
+                ServerResult::ListPromptsResult(result) => {
+                    Some((result.prompts, result.next_cursor))
+                }
+                _ => None,
+            },
+        )
+        .await
+    }
+
+    async fn resources(
+        &self,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Vec<Resource>, McpError> {
+        if self.capabilities().resources.is_none() {
+            return Ok(Vec::new());
+        }
+        self.list_all(
+            context,
+            |params| ClientRequest::ListResourcesRequest(RequestOptionalParam::with_param(params)),
+            |result| match result {
+                ServerResult::ListResourcesResult(result) => {
+                    Some((result.resources, result.next_cursor))
+                }
+                _ => None,
+            },
+        )
+        .await
+    }
+
+    async fn resource_templates(
+        &self,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Vec<ResourceTemplate>, McpError> {
+        if self.capabilities().resources.is_none() {
+            return Ok(Vec::new());
+        }
+        self.list_all(
+            context,
+            |params| {
+                ClientRequest::ListResourceTemplatesRequest(RequestOptionalParam::with_param(
+                    params,
+                ))
+            },
+            |result| match result {
+                ServerResult::ListResourceTemplatesResult(result) => {
+                    Some((result.resource_templates, result.next_cursor))
+                }
+                _ => None,
+            },
+        )
+        .await
+    }
+}
+
+/// The merged list of one kind of item, sorted by key, and the child owning
+/// each key.
+struct Listing<T> {
+    items: Vec<T>,
+    owners: HashMap<String, usize>,
+}
+
+impl<T> Listing<T> {
+    /// Merge the lists of the children, given in mount order. The first child
+    /// to list a key owns it.
+    fn merge(lists: Vec<Vec<T>>, key: impl Fn(&T) -> &str, kind: &str) -> Self {
+        let mut owners = HashMap::new();
+        let mut items = Vec::new();
+        for (child, list) in lists.into_iter().enumerate() {
+            for item in list {
+                match owners.entry(key(&item).to_owned()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(child);
+                        items.push(item);
+                    }
+                    Entry::Occupied(entry) => {
+                        tracing::warn!(kind, key = %entry.key(), "shadowed by an earlier child");
+                    }
+                }
+            }
+        }
+        items.sort_by(|a, b| key(a).cmp(key(b)));
+        Self { items, owners }
+    }
+
+    /// The child owning `key`.
+    fn owner(&self, key: &str) -> Option<usize> {
+        self.owners.get(key).copied()
+    }
+}
+
+type Slot<T> = Option<Arc<Listing<T>>>;
+
+/// The listings of the children, built on demand.
+#[derive(Default)]
+struct Index {
+    /// Bumped whenever a listing is dropped, so a listing that was being built
+    /// at the time is not stored.
+    generation: u64,
+    tools: Slot<Tool>,
+    prompts: Slot<Prompt>,
+    resources: Slot<Resource>,
+    resource_templates: Slot<ResourceTemplate>,
+}
+
+impl Index {
+    /// Drop the listings `notification` reports a change of.
+    fn invalidate(&mut self, notification: &ServerNotification) {
+        match notification {
+            ServerNotification::ToolListChangedNotification(_) => self.tools = None,
+            ServerNotification::PromptListChangedNotification(_) => self.prompts = None,
+            ServerNotification::ResourceListChangedNotification(_) => {
+                self.resources = None;
+                self.resource_templates = None;
+            }
+            _ => return,
+        }
+        self.generation += 1;
+    }
+}
+
+/// Several servers served as one. See the [module docs](self).
+#[derive(Default)]
+pub struct CompositeServer {
+    children: Vec<Mounted>,
+    server_info: Option<Implementation>,
+    instructions: Option<String>,
+    page_size: Option<usize>,
+    panic_policy: PanicPolicy,
+    index: Arc<Mutex<Index>>,
+    /// The peer of the client, set on the first message, and the peer handed
+    /// to the children.
+    peer: OnceLock<(Peer<RoleServer>, Peer<RoleServer>)>,
+}
+
+impl std::fmt::Debug for CompositeServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompositeServer")
+            .field(
+                "prefixes",
+                &self
+                    .children
+                    .iter()
+                    .map(|child| child.prefix.as_deref())
+                    .collect::<Vec<_>>(),
+            )
+            .field("server_info", &self.server_info)
+            .field("instructions", &self.instructions)
+            .field("page_size", &self.page_size)
+            .field("panic_policy", &self.panic_policy)
+            .finish()
+    }
+}
+
+impl CompositeServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a child whose names are served as they are.
+    pub fn mount<S: Service<RoleServer>>(mut self, service: S) -> Self {
+        self.children.push(Mounted {
+            prefix: None,
+            service: Box::new(service),
+        });
+        self
+    }
+
+    /// Add a child whose tool, prompt, resource and resource template names
+    /// are served with `prefix` in front, e.g. `git_status` for the `status`
+    /// tool of a child mounted with `"git_"`.
+    pub fn mount_with_prefix<S: Service<RoleServer>>(
+        mut self,
+        prefix: impl Into<String>,
+        service: S,
+    ) -> Self {
+        self.children.push(Mounted {
+            prefix: Some(prefix.into()),
+            service: Box::new(service),
+        });
+        self
+    }
+
+    /// The implementation reported to clients. Defaults to this crate's.
+    pub fn with_server_info(mut self, server_info: Implementation) -> Self {
+        self.server_info = Some(server_info);
+        self
+    }
+
+    /// Instructions put before those of the children.
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// List at most `page_size` tools, prompts, resources or resource
+    /// templates per page.
+    ///
+    /// See [`pagination`](super::pagination).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        assert!(page_size != 0, "page size must be at least 1");
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// How the composite reacts to a panicking request handler of any child.
+    /// Defaults to [`PanicPolicy::default`].
+    pub fn with_panic_policy(mut self, panic_policy: PanicPolicy) -> Self {
+        self.panic_policy = panic_policy;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn index(&self) -> MutexGuard<'_, Index> {
+        self.index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The peer the children send through: it reaches the client of `peer`
+    /// and drops the listings a child reports a change of.
+    ///
+    /// A composite serves a single connection, so this fails for any peer
+    /// but the first.
+    pub(super) fn child_peer(&self, peer: &Peer<RoleServer>) -> Result<Peer<RoleServer>, McpError> {
+        let (client, child_peer) = self.peer.get_or_init(|| {
+            let index = self.index.clone();
+            let child_peer = peer.tapped(move |notification| {
+                index
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .invalidate(notification)
+            });
+            (peer.clone(), child_peer)
+        });
+        if !client.is_same_peer(peer) {
+            return Err(McpError::invalid_request(
+                "this server is already serving another connection",
+                None,
+            ));
+        }
+        Ok(child_peer.clone())
+    }
+
+    /// The cached listing in `slot`, unless `refresh` is set, and the
+    /// generation a new listing is built in.
+    fn cached<T>(&self, slot: fn(&mut Index) -> &mut Slot<T>, refresh: bool) -> (Slot<T>, u64) {
+        let mut index = self.index();
+        let cached = if refresh {
+            None
+        } else {
+            slot(&mut index).clone()
+        };
+        (cached, index.generation)
+    }
+
+    /// Cache `listing` in `slot`, unless a listing was dropped since
+    /// `generation`.
+    fn store<T>(
+        &self,
+        slot: fn(&mut Index) -> &mut Slot<T>,
+        generation: u64,
+        listing: Listing<T>,
+    ) -> Arc<Listing<T>> {
+        let listing = Arc::new(listing);
+        let mut index = self.index();
+        if index.generation == generation {
+            *slot(&mut index) = Some(listing.clone());
+        }
+        listing
+    }
+
+    async fn tools(
+        &self,
+        context: &RequestContext<RoleServer>,
+        refresh: bool,
+    ) -> Result<Arc<Listing<Tool>>, McpError> {
+        let (cached, generation) = self.cached(|index| &mut index.tools, refresh);
+        if let Some(tools) = cached {
+            return Ok(tools);
+        }
+        let mut lists = Vec::with_capacity(self.children.len());
+        for child in &self.children {
+            let mut tools = child.tools(context).await?;
+            for tool in &mut tools {
+                tool.name = Cow::Owned(child.prefixed(&tool.name));
+            }
+            lists.push(tools);
+        }
+        let listing = Listing::merge(lists, |tool| tool.name.as_ref(), "tool");
+        Ok(self.store(|index| &mut index.tools, generation, listing))
+    }
+
+    async fn prompts(
+        &self,
+        context: &RequestContext<RoleServer>,
+        refresh: bool,
+    ) -> Result<Arc<Listing<Prompt>>, McpError> {
+        let (cached, generation) = self.cached(|index| &mut index.prompts, refresh);
+        if let Some(prompts) = cached {
+            return Ok(prompts);
+        }
+        let mut lists = Vec::with_capacity(self.children.len());
+        for child in &self.children {
+            let mut prompts = child.prompts(context).await?;
+            for prompt in &mut prompts {
+                prompt.name = child.prefixed(&prompt.name);
+            }
+            lists.push(prompts);
+        }
+        let listing = Listing::merge(lists, |prompt| prompt.name.as_str(), "prompt");
+        Ok(self.store(|index| &mut index.prompts, generation, listing))
+    }
+
+    async fn resources(
+        &self,
+        context: &RequestContext<RoleServer>,
+        refresh: bool,
+    ) -> Result<Arc<Listing<Resource>>, McpError> {
+        let (cached, generation) = self.cached(|index| &mut index.resources, refresh);
+        if let Some(resources) = cached {
+            return Ok(resources);
+        }
+        let mut lists = Vec::with_capacity(self.children.len());
+        for child in &self.children {
+            let mut resources = child.resources(context).await?;
+            for resource in &mut resources {
+                resource.raw.name = child.prefixed(&resource.raw.name);
+            }
+            lists.push(resources);
+        }
+        let listing = Listing::merge(lists, |resource| resource.raw.uri.as_str(), "resource");
+        Ok(self.store(|index| &mut index.resources, generation, listing))
+    }
+
+    async fn resource_templates(
+        &self,
+        context: &RequestContext<RoleServer>,
+        refresh: bool,
+    ) -> Result<Arc<Listing<ResourceTemplate>>, McpError> {
+        let (cached, generation) = self.cached(|index| &mut index.resource_templates, refresh);
+        if let Some(templates) = cached {
+            return Ok(templates);
+        }
+        let mut lists = Vec::with_capacity(self.children.len());
+        for child in &self.children {
+            let mut templates = child.resource_templates(context).await?;
+            for template in &mut templates {
+                template.raw.name = child.prefixed(&template.raw.name);
+            }
+            lists.push(templates);
+        }
+        let listing = Listing::merge(
+            lists,
+            |template| template.raw.uri_template.as_str(),
+            "resource template",
+        );
+        Ok(self.store(|index| &mut index.resource_templates, generation, listing))
+    }
+
+    /// The child at `index` with its name for `name`.
+    fn child<'a>(&self, index: Option<usize>, name: &'a str) -> Option<(&Mounted, &'a str)> {
+        let child = &self.children[index?];
+        Some((child, child.local_name(name)?))
+    }
+
+    /// The child serving the tool `name`, and the child's name for it.
+    async fn tool_owner<'a>(
+        &self,
+        name: &'a str,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Option<(&Mounted, &'a str)>, McpError> {
+        let cached = self.index().tools.is_some();
+        let mut owner = self.tools(context, false).await?.owner(name);
+        if owner.is_none() && cached {
+            owner = self.tools(context, true).await?.owner(name);
+        }
+        Ok(self.child(owner, name))
+    }
+
+    /// The child serving the prompt `name`, and the child's name for it.
+    async fn prompt_owner<'a>(
+        &self,
+        name: &'a str,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Option<(&Mounted, &'a str)>, McpError> {
+        let cached = self.index().prompts.is_some();
+        let mut owner = self.prompts(context, false).await?.owner(name);
+        if owner.is_none() && cached {
+            owner = self.prompts(context, true).await?.owner(name);
+        }
+        Ok(self.child(owner, name))
+    }
+
+    /// The child serving `uri`, preferring a child that lists the resource
+    /// over one with a matching template.
+    async fn resource_owner(
+        &self,
+        uri: &str,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Option<&Mounted>, McpError> {
+        let cached = self.index().resources.is_some();
+        let mut owner = self.resources(context, false).await?.owner(uri);
+        if owner.is_none() && cached {
+            owner = self.resources(context, true).await?.owner(uri);
+        }
+        if owner.is_none() {
+            let cached = self.index().resource_templates.is_some();
+            owner = template_owner(&*self.resource_templates(context, false).await?, uri);
+            if owner.is_none() && cached {
+                owner = template_owner(&*self.resource_templates(context, true).await?, uri);
+            }
+        }
+        Ok(owner.map(|owner| &self.children[owner]))
+    }
+
+    /// The child offering completions for the resource template (or resource)
+    /// `uri`.
+    async fn completion_owner(
+        &self,
+        uri: &str,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Option<&Mounted>, McpError> {
+        let owner = self.resource_templates(context, false).await?.owner(uri);
+        if let Some(owner) = owner {
+            return Ok(Some(&self.children[owner]));
+        }
+        self.resource_owner(uri, context).await
+    }
+
+    async fn forward_to_resource_owner(
+        &self,
+        uri: &str,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        match self.resource_owner(uri, &context).await? {
+            Some(child) => child.request(request, &context).await,
+            None => Err(McpError::resource_not_found(
+                format!("resource '{uri}' not found"),
+                None,
+            )),
+        }
+    }
+
+    /// Send `request` to each child in turn until one supports it.
+    async fn first_supported(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        let mut result = Err(McpError::new(
+            ErrorCode::METHOD_NOT_FOUND,
+            request.method().to_owned(),
+            None,
+        ));
+        for child in &self.children {
+            result = child.request(request.clone(), &context).await;
+            if !matches!(&result, Err(error) if error.code == ErrorCode::METHOD_NOT_FOUND) {
+                break;
+            }
+        }
+        result
+    }
+}
+
+/// The cursor of a list request.
+fn cursor(params: Option<PaginatedRequestParams>) -> Option<Cursor> {
+    params.and_then(|params| params.cursor)
+}
+
+/// The first mounted child with a template matching `uri`.
+fn template_owner(templates: &Listing<ResourceTemplate>, uri: &str) -> Option<usize> {
+    templates
+        .items
+        .iter()
+        .filter(|template| {
+            UriTemplate::parse(&template.raw.uri_template)
+                .is_ok_and(|template| template.match_uri(uri).is_some())
+        })
+        .filter_map(|template| templates.owner(&template.raw.uri_template))
+        .min()
+}
+
+/// Add the capabilities of `other` to `capabilities`.
+fn merge_capabilities(capabilities: &mut ServerCapabilities, other: ServerCapabilities) {
+    fn or(a: &mut Option<bool>, b: Option<bool>) {
+        *a = match (*a, b) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (a, b) => a.or(b),
+        };
+    }
+    if let Some(experimental) = other.experimental {
+        capabilities
+            .experimental
+            .get_or_insert_with(Default::default)
+            .extend(experimental);
+    }
+    if let Some(extensions) = other.extensions {
+        capabilities
+            .extensions
+            .get_or_insert_with(Default::default)
+            .extend(extensions);
+    }
+    if let Some(logging) = other.logging {
+        capabilities.logging.get_or_insert(logging);
+    }
+    if let Some(completions) = other.completions {
+        capabilities.completions.get_or_insert(completions);
+    }
+    if let Some(prompts) = other.prompts {
+        let merged = capabilities.prompts.get_or_insert_with(Default::default);
+        or(&mut merged.list_changed, prompts.list_changed);
+    }
+    if let Some(resources) = other.resources {
+        let merged = capabilities.resources.get_or_insert_with(Default::default);
+        or(&mut merged.subscribe, resources.subscribe);
+        or(&mut merged.list_changed, resources.list_changed);
+    }
+    if let Some(tools) = other.tools {
+        let merged = capabilities.tools.get_or_insert_with(Default::default);
+        or(&mut merged.list_changed, tools.list_changed);
+    }
+    if let Some(tasks) = other.tasks {
+        capabilities.tasks.get_or_insert(tasks);
+    }
+}
+
+impl Service<RoleServer> for CompositeServer {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        mut context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        context.peer = self.child_peer(&context.peer)?;
+        match request {
+            ClientRequest::InitializeRequest(_) => {
+                for child in &self.children {
+                    child.request(request.clone(), &context).await?;
+                }
+                Ok(ServerResult::InitializeResult(Service::get_info(self)))
+            }
+            ClientRequest::PingRequest(_) => Ok(ServerResult::empty(())),
+            ClientRequest::ListToolsRequest(request) => {
+                let cursor = cursor(request.params);
+                let tools = self.tools(&context, cursor.is_none()).await?;
+                let (tools, next_cursor) = paginate(
+                    tools.items.clone(),
+                    |tool| tool.name.as_ref(),
+                    self.page_size,
+                    cursor.as_deref(),
+                )?;
+                Ok(ServerResult::ListToolsResult(ListToolsResult {
+                    tools,
+                    next_cursor,
+                    ..Default::default()
+                }))
+            }
+            ClientRequest::ListPromptsRequest(request) => {
+                let cursor = cursor(request.params);
+                let prompts = self.prompts(&context, cursor.is_none()).await?;
+                let (prompts, next_cursor) = paginate(
+                    prompts.items.clone(),
+                    |prompt| prompt.name.as_str(),
+                    self.page_size,
+                    cursor.as_deref(),
+                )?;
+                Ok(ServerResult::ListPromptsResult(ListPromptsResult {
+                    prompts,
+                    next_cursor,
+                    ..Default::default()
+                }))
+            }
+            ClientRequest::ListResourcesRequest(request) => {
+                let cursor = cursor(request.params);
+                let resources = self.resources(&context, cursor.is_none()).await?;
+                let (resources, next_cursor) = paginate(
+                    resources.items.clone(),
+                    |resource| resource.raw.uri.as_str(),
+                    self.page_size,
+                    cursor.as_deref(),
+                )?;
+                Ok(ServerResult::ListResourcesResult(ListResourcesResult {
+                    resources,
+                    next_cursor,
+                    ..Default::default()
+                }))
+            }
+            ClientRequest::ListResourceTemplatesRequest(request) => {
+                let cursor = cursor(request.params);
+                let templates = self.resource_templates(&context, cursor.is_none()).await?;
+                let (resource_templates, next_cursor) = paginate(
+                    templates.items.clone(),
+                    |template| template.raw.uri_template.as_str(),
+                    self.page_size,
+                    cursor.as_deref(),
+                )?;
+                Ok(ServerResult::ListResourceTemplatesResult(
+                    ListResourceTemplatesResult {
+                        resource_templates,
+                        next_cursor,
+                        ..Default::default()
+                    },
+                ))
+            }
+            ClientRequest::CallToolRequest(mut request) => {
+                let name = request.params.name.clone();
+                let Some((child, local)) = self.tool_owner(&name, &context).await? else {
+                    return Err(McpError::invalid_params("tool not found", None));
+                };
+                request.params.name = Cow::Owned(local.to_owned());
+                child
+                    .request(ClientRequest::CallToolRequest(request), &context)
+                    .await
+            }
+            ClientRequest::GetPromptRequest(mut request) => {
+                let name = request.params.name.clone();
+                let Some((child, local)) = self.prompt_owner(&name, &context).await? else {
+                    return Err(McpError::invalid_params(
+                        format!("prompt '{name}' not found"),
+                        None,
+                    ));
+                };
+                request.params.name = local.to_owned();
+                child
+                    .request(ClientRequest::GetPromptRequest(request), &context)
+                    .await
+            }
+            ClientRequest::ReadResourceRequest(ref inner) => {
+                let uri = inner.params.uri.clone();
+                self.forward_to_resource_owner(&uri, request, context).await
+            }
+            ClientRequest::SubscribeRequest(ref inner) => {
+                let uri = inner.params.uri.clone();
+                self.forward_to_resource_owner(&uri, request, context).await
+            }
+            ClientRequest::UnsubscribeRequest(ref inner) => {
+                let uri = inner.params.uri.clone();
+                self.forward_to_resource_owner(&uri, request, context).await
+            }
+            ClientRequest::CompleteRequest(mut request) => {
+                let child = match &mut request.params.r#ref {
+                    Reference::Prompt(reference) => {
+                        let name = reference.name.clone();
+                        match self.prompt_owner(&name, &context).await? {
+                            Some((child, local)) => {
+                                reference.name = local.to_owned();
+                                Some(child)
+                            }
+                            None => None,
+                        }
+                    }
+                    Reference::Resource(reference) => {
+                        self.completion_owner(&reference.uri, &context).await?
+                    }
+                };
+                match child {
+                    Some(child) => {
+                        child
+                            .request(ClientRequest::CompleteRequest(request), &context)
+                            .await
+                    }
+                    None => Ok(ServerResult::CompleteResult(CompleteResult::default())),
+                }
+            }
+            ClientRequest::SetLevelRequest(_) => {
+                let mut result = Ok(());
+                for child in &self.children {
+                    if child.capabilities().logging.is_none() {
+                        continue;
+                    }
+                    if let Err(error) = child.request(request.clone(), &context).await {
+                        result = result.and(Err(error));
+                    }
+                }
+                result.map(ServerResult::empty)
+            }
+            request => self.first_supported(request, context).await,
+        }
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        mut context: NotificationContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        context.peer = self.child_peer(&context.peer)?;
+        for child in &self.children {
+            if let Err(error) = DynService::handle_notification(
+                child.service.as_ref(),
+                notification.clone(),
+                context.clone(),
+            )
+            .await
+            {
+                tracing::warn!(%error, "child failed to handle notification");
+            }
+        }
+        Ok(())
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        let mut capabilities = ServerCapabilities::default();
+        let mut instructions: Vec<String> = self.instructions.iter().cloned().collect();
+        for child in &self.children {
+            let info = DynService::get_info(child.service.as_ref());
+            merge_capabilities(&mut capabilities, info.capabilities);
+            instructions.extend(info.instructions);
+        }
+        let mut info = ServerInfo::new(capabilities);
+        if let Some(server_info) = &self.server_info {
+            info.server_info = server_info.clone();
+        }
+        if !instructions.is_empty() {
+            info.instructions = Some(instructions.join("\n\n"));
+        }
+        info
+    }
+
+    fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        model::{ResourcesCapability, ToolsCapability},
+        service::AtomicU32RequestIdProvider,
+    };
+
+    #[tokio::test]
+    async fn test_a_second_peer_is_rejected() {
+        let peer =
+            || Peer::<RoleServer>::new(Arc::new(AtomicU32RequestIdProvider::default()), None);
+        let (first, _first_rx) = peer();
+        let (second, _second_rx) = peer();
+        let server = CompositeServer::new();
+        assert!(server.child_peer(&first).is_ok());
+        assert!(server.child_peer(&first.clone()).is_ok());
+        let error = server.child_peer(&second).unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_merge_capabilities() {
+        let mut capabilities = ServerCapabilities::builder()
+            .enable_tools()
+            .enable_resources()
+            .build();
+        merge_capabilities(
+            &mut capabilities,
+            ServerCapabilities {
+                tools: Some(ToolsCapability {
+                    list_changed: Some(true),
+                }),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: None,
+                }),
+                logging: Some(Default::default()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            capabilities.tools,
+            Some(ToolsCapability {
+                list_changed: Some(true)
+            })
+        );
+        assert_eq!(
+            capabilities.resources,
+            Some(ResourcesCapability {
+                subscribe: Some(true),
+                list_changed: None,
+            })
+        );
+        assert!(capabilities.logging.is_some());
+        assert!(capabilities.prompts.is_none());
+    }
+}
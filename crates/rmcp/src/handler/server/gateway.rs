@@ -0,0 +1,504 @@
+//! Aggregate upstream MCP servers behind a single server.
+//!
+//! A [`Gateway`] holds client connections to any number of upstream servers,
+//! be they child processes, streamable HTTP endpoints or unix sockets, and
+//! serves their tools, prompts and resources to one downstream client under a
+//! per-upstream prefix. Routing works like [`CompositeServer`]; on top of
+//! that, the gateway relays traffic in both directions:
+//!
+//! - Requests from the downstream client are forwarded to the owning
+//!   upstream. Progress notifications for them are passed back with the
+//!   downstream progress token, and cancelling them downstream cancels them
+//!   upstream.
+//! - Requests from an upstream, such as sampling, elicitation or
+//!   `roots/list`, are forwarded to the downstream client in the same way.
+//! - Logging, resource updates and `list_changed` notifications are passed
+//!   on. Upstreams that announce `listChanged` have their lists cached until
+//!   they report a change.
+//!
+//! Upstream connections are made with an [`UpstreamHandler`] taken from the
+//! gateway they will be mounted on:
+//!
+//! ```rust,ignore
+//! use rmcp::{handler::server::gateway::Gateway, transport::TokioChildProcess};
+//!
+//! let gateway = Gateway::new()
+//!     .connect("git_", TokioChildProcess::new(Command::new("git-mcp"))?)
+//!     .await?
+//!     .connect("docs_", StreamableHttpClientTransport::from_uri(docs_url))
+//!     .await?;
+//! gateway.serve(downstream_transport).await?.waiting().await?;
+//! ```
+//!
+//! A gateway relays for a single downstream connection, the first one that
+//! talks to it, and rejects requests from any other. Upstream requests and
+//! notifications can then only ever reach that connection. Behind a server
+//! with many sessions, such as the streamable HTTP server, build one gateway,
+//! with its own upstream connections, per session.
+//!
+//! Upstreams are services of their own and apply their own panic policies;
+//! the gateway's [`with_panic_policy`](Gateway::with_panic_policy) covers
+//! panics while it relays a request.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use tokio_util::sync::CancellationToken;
+
+use super::composite::CompositeServer;
+use crate::{
+    error::ErrorData as McpError,
+    model::{
+        CancelledNotification, CancelledNotificationParam, ClientCapabilities, ClientInfo,
+        ClientNotification, ClientRequest, ClientResult, GetMeta, Implementation,
+        ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, Meta,
+        ProgressToken, Prompt, Resource, ResourceTemplate, ServerInfo, ServerNotification,
+        ServerRequest, ServerResult, Tool,
+    },
+    service::{
+        ClientInitializeError, NotificationContext, PanicPolicy, Peer, PeerRequestOptions,
+        RequestContext, RoleClient, RoleServer, RunningService, Service, ServiceError, ServiceExt,
+        ServiceRole,
+    },
+    transport::IntoTransport,
+};
+
+/// Progress tokens of relayed requests, from the token the gateway sent to
+/// the token of the original request.
+type ProgressTokens = Mutex<HashMap<ProgressToken, ProgressToken>>;
+
+fn into_mcp_error(error: ServiceError) -> McpError {
+    match error {
+        ServiceError::McpError(error) => error,
+        error => McpError::internal_error(error.to_string(), None),
+    }
+}
+
+/// Send `request` to `peer` and wait for the response, cancelling it when
+/// `ct` is cancelled.
+async fn relay<R: ServiceRole>(
+    peer: &Peer<R>,
+    mut request: R::Req,
+    meta: Meta,
+    ct: &CancellationToken,
+    progress: &ProgressTokens,
+) -> Result<R::PeerResp, McpError> {
+    let token = meta.get_progress_token();
+    *request.get_meta_mut() = meta;
+    let handle = peer
+        .send_cancellable_request(request, PeerRequestOptions::no_options())
+        .await
+        .map_err(into_mcp_error)?;
+    let id = handle.id.clone();
+    let relayed_token = handle.progress_token.clone();
+    if let Some(token) = token {
+        progress
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(relayed_token.clone(), token);
+    }
+    let result = tokio::select! {
+        result = handle.await_response() => result.map_err(into_mcp_error),
+        _ = ct.cancelled() => {
+            let cancelled = CancelledNotification::new(CancelledNotificationParam {
+                request_id: id,
+                reason: Some("cancelled by the peer of the gateway".to_owned()),
+            });
+            if let Err(error) = peer.send_notification(cancelled.into()).await {
+                tracing::warn!(%error, "failed to relay cancellation");
+            }
+            Err(McpError::internal_error("request cancelled", None))
+        }
+    };
+    progress
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(&relayed_token);
+    result
+}
+
+#[derive(Debug, Default)]
+struct ListCache {
+    tools: Mutex<Option<Vec<Tool>>>,
+    prompts: Mutex<Option<Vec<Prompt>>>,
+    resources: Mutex<Option<Vec<Resource>>>,
+    resource_templates: Mutex<Option<Vec<ResourceTemplate>>>,
+}
+
+fn invalidate<T>(cache: &Mutex<Option<Vec<T>>>) {
+    cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take();
+}
+
+/// The client side of a gateway's connection to one upstream server.
+///
+/// Get one from [`Gateway::upstream_handler`], serve it over the upstream
+/// transport and hand the running service to [`Gateway::with_upstream`].
+#[derive(Debug, Clone)]
+pub struct UpstreamHandler {
+    prefix: String,
+    downstream: Arc<OnceLock<Peer<RoleServer>>>,
+    /// Requests relayed to the upstream, keyed by the upstream token.
+    calls: Arc<ProgressTokens>,
+    /// Requests relayed to the downstream client, keyed by the downstream
+    /// token.
+    requests: Arc<ProgressTokens>,
+    lists: Arc<ListCache>,
+}
+
+impl UpstreamHandler {
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn downstream(&self) -> Result<&Peer<RoleServer>, McpError> {
+        self.downstream
+            .get()
+            .ok_or_else(|| McpError::internal_error("no downstream client connected", None))
+    }
+}
+
+impl Service<RoleClient> for UpstreamHandler {
+    async fn handle_request(
+        &self,
+        request: ServerRequest,
+        context: RequestContext<RoleClient>,
+    ) -> Result<ClientResult, McpError> {
+        match request {
+            ServerRequest::PingRequest(_) => Ok(ClientResult::empty(())),
+            request => {
+                relay(
+                    self.downstream()?,
+                    request,
+                    context.meta,
+                    &context.ct,
+                    &self.requests,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ServerNotification,
+        context: NotificationContext<RoleClient>,
+    ) -> Result<(), McpError> {
+        let mut notification = match notification {
+            // the service has already cancelled the relayed request's token
+            ServerNotification::CancelledNotification(_) => return Ok(()),
+            ServerNotification::ProgressNotification(mut progress) => {
+                let token = self
+                    .calls
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .get(&progress.params.progress_token)
+                    .cloned();
+                let Some(token) = token else {
+                    return Ok(());
+                };
+                progress.params.progress_token = token;
+                ServerNotification::ProgressNotification(progress)
+            }
+            notification @ ServerNotification::ToolListChangedNotification(_) => {
+                invalidate(&self.lists.tools);
+                notification
+            }
+            notification @ ServerNotification::PromptListChangedNotification(_) => {
+                invalidate(&self.lists.prompts);
+                notification
+            }
+            notification @ ServerNotification::ResourceListChangedNotification(_) => {
+                invalidate(&self.lists.resources);
+                invalidate(&self.lists.resource_templates);
+                notification
+            }
+            notification => notification,
+        };
+        *notification.get_meta_mut() = context.meta;
+        self.downstream()?
+            .send_notification(notification)
+            .await
+            .map_err(into_mcp_error)
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::new(
+            ClientCapabilities::builder()
+                .enable_roots()
+                .enable_roots_list_changed()
+                .enable_sampling()
+                .enable_elicitation()
+                .build(),
+            Implementation::from_build_env(),
+        )
+    }
+}
+
+/// A running upstream connection, served to the gateway's composite.
+struct Upstream(RunningService<RoleClient, UpstreamHandler>);
+
+impl Upstream {
+    fn handler(&self) -> &UpstreamHandler {
+        self.0.service()
+    }
+
+    /// The list in `cache`, fetched with `fetch` unless cached. Lists are
+    /// only cached when the upstream announces changes to them.
+    async fn list<T: Clone>(
+        cache: &Mutex<Option<Vec<T>>>,
+        cacheable: bool,
+        fetch: impl Future<Output = Result<Vec<T>, ServiceError>>,
+    ) -> Result<Vec<T>, McpError> {
+        if let Some(items) = cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+        {
+            return Ok(items.clone());
+        }
+        let items = fetch.await.map_err(into_mcp_error)?;
+        if cacheable {
+            *cache
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(items.clone());
+        }
+        Ok(items)
+    }
+}
+
+impl Service<RoleServer> for Upstream {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        let capabilities = Service::get_info(self).capabilities;
+        let lists = &self.handler().lists;
+        match request {
+            // the upstream was initialized when it was connected
+            ClientRequest::InitializeRequest(_) => {
+                Ok(ServerResult::InitializeResult(Service::get_info(self)))
+            }
+            ClientRequest::PingRequest(_) => Ok(ServerResult::empty(())),
+            ClientRequest::ListToolsRequest(_) => {
+                let cacheable = capabilities.tools.and_then(|c| c.list_changed) == Some(true);
+                let tools = Self::list(&lists.tools, cacheable, self.0.list_all_tools()).await?;
+                Ok(ServerResult::ListToolsResult(ListToolsResult {
+                    tools,
+                    ..Default::default()
+                }))
+            }
+            ClientRequest::ListPromptsRequest(_) => {
+                let cacheable = capabilities.prompts.and_then(|c| c.list_changed) == Some(true);
+                let prompts =
+                    Self::list(&lists.prompts, cacheable, self.0.list_all_prompts()).await?;
+                Ok(ServerResult::ListPromptsResult(ListPromptsResult {
+                    prompts,
+                    ..Default::default()
+                }))
+            }
+            ClientRequest::ListResourcesRequest(_) => {
+                let cacheable = capabilities.resources.and_then(|c| c.list_changed) == Some(true);
+                let resources =
+                    Self::list(&lists.resources, cacheable, self.0.list_all_resources()).await?;
+                Ok(ServerResult::ListResourcesResult(ListResourcesResult {
+                    resources,
+                    ..Default::default()
+                }))
+            }
+            ClientRequest::ListResourceTemplatesRequest(_) => {
+                let cacheable = capabilities.resources.and_then(|c| c.list_changed) == Some(true);
+                let resource_templates = Self::list(
+                    &lists.resource_templates,
+                    cacheable,
+                    self.0.list_all_resource_templates(),
+                )
+                .await?;
+                Ok(ServerResult::ListResourceTemplatesResult(
+                    ListResourceTemplatesResult {
+                        resource_templates,
+                        ..Default::default()
+                    },
+                ))
+            }
+            request => {
+                relay(
+                    self.0.peer(),
+                    request,
+                    context.meta,
+                    &context.ct,
+                    &self.handler().calls,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        let mut notification = match notification {
+            // the upstream was initialized when it was connected, and
+            // cancellation reaches relayed requests through their tokens
+            ClientNotification::InitializedNotification(_)
+            | ClientNotification::CancelledNotification(_) => return Ok(()),
+            ClientNotification::ProgressNotification(mut progress) => {
+                let token = self
+                    .handler()
+                    .requests
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .get(&progress.params.progress_token)
+                    .cloned();
+                let Some(token) = token else {
+                    return Ok(());
+                };
+                progress.params.progress_token = token;
+                ClientNotification::ProgressNotification(progress)
+            }
+            notification => notification,
+        };
+        *notification.get_meta_mut() = context.meta;
+        self.0
+            .send_notification(notification)
+            .await
+            .map_err(into_mcp_error)
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.0.peer_info().cloned().unwrap_or_default()
+    }
+}
+
+/// Upstream servers served as one. See the [module docs](self).
+#[derive(Debug, Default)]
+pub struct Gateway {
+    composite: CompositeServer,
+    downstream: Arc<OnceLock<Peer<RoleServer>>>,
+}
+
+impl Gateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A handler for connecting to an upstream whose names will be served
+    /// with `prefix` in front. An empty prefix serves them as they are.
+    pub fn upstream_handler(&self, prefix: impl Into<String>) -> UpstreamHandler {
+        UpstreamHandler {
+            prefix: prefix.into(),
+            downstream: self.downstream.clone(),
+            calls: Default::default(),
+            requests: Default::default(),
+            lists: Default::default(),
+        }
+    }
+
+    /// Mount a connected upstream under the prefix of its handler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handler was taken from another gateway.
+    pub fn with_upstream(mut self, upstream: RunningService<RoleClient, UpstreamHandler>) -> Self {
+        let handler = upstream.service();
+        assert!(
+            Arc::ptr_eq(&handler.downstream, &self.downstream),
+            "upstream handler belongs to another gateway"
+        );
+        let prefix = handler.prefix.clone();
+        self.composite = if prefix.is_empty() {
+            self.composite.mount(Upstream(upstream))
+        } else {
+            self.composite.mount_with_prefix(prefix, Upstream(upstream))
+        };
+        self
+    }
+
+    /// Connect to an upstream over `transport` and mount it under `prefix`.
+    pub async fn connect<T, E, A>(
+        self,
+        prefix: impl Into<String>,
+        transport: T,
+    ) -> Result<Self, ClientInitializeError>
+    where
+        T: IntoTransport<RoleClient, E, A>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let upstream = self.upstream_handler(prefix).serve(transport).await?;
+        Ok(self.with_upstream(upstream))
+    }
+
+    /// The implementation reported to clients. Defaults to this crate's.
+    pub fn with_server_info(mut self, server_info: Implementation) -> Self {
+        self.composite = self.composite.with_server_info(server_info);
+        self
+    }
+
+    /// Instructions put before those of the upstreams.
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.composite = self.composite.with_instructions(instructions);
+        self
+    }
+
+    /// How the gateway reacts to a panic while relaying a request. Defaults
+    /// to [`PanicPolicy::default`].
+    pub fn with_panic_policy(mut self, panic_policy: PanicPolicy) -> Self {
+        self.composite = self.composite.with_panic_policy(panic_policy);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.composite.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.composite.is_empty()
+    }
+
+    /// Relay upstream traffic to `peer`. Relays go through the composite, so
+    /// upstream `list_changed` notifications refresh its index. Fails for any
+    /// peer but the first.
+    fn bind(&self, peer: &Peer<RoleServer>) -> Result<(), McpError> {
+        let peer = self.composite.child_peer(peer)?;
+        let _ = self.downstream.set(peer);
+        Ok(())
+    }
+}
+
+impl Service<RoleServer> for Gateway {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        self.bind(&context.peer)?;
+        self.composite.handle_request(request, context).await
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.bind(&context.peer)?;
+        self.composite
+            .handle_notification(notification, context)
+            .await
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        Service::get_info(&self.composite)
+    }
+
+    fn panic_policy(&self) -> PanicPolicy {
+        Service::panic_policy(&self.composite)
+    }
+}
@@ -0,0 +1,176 @@
+//! Middleware that runs around every request and notification of a server.
+//!
+//! An [`McpLayer`] sees the raw [`ClientRequest`] together with its
+//! [`RequestContext`] and decides what happens next: it can inspect or rewrite
+//! the request, short-circuit with an [`ErrorData`](crate::ErrorData) without
+//! calling the inner service, or await [`Next::run`] and look at the result.
+//!
+//! Layers are stacked with [`McpLayerExt::layer`]. Each call wraps the service
+//! built so far, so the layer added **last** runs **first**:
+//!
+//! ```rust,ignore
+//! use rmcp::handler::server::layer::{McpLayer, McpLayerExt, Next};
+//!
+//! struct AuditLayer;
+//!
+//! impl McpLayer for AuditLayer {
+//!     async fn handle_request(
+//!         &self,
+//!         request: ClientRequest,
+//!         context: RequestContext<RoleServer>,
+//!         next: Next<'_>,
+//!     ) -> Result<ServerResult, ErrorData> {
+//!         let method = request.method().to_owned();
+//!         let result = next.run(request, context).await;
+//!         tracing::info!(%method, ok = result.is_ok(), "audit");
+//!         result
+//!     }
+//! }
+//!
+//! // AuthLayer rejects a request before AuditLayer ever sees it.
+//! let service = MyServer::new().layer(AuditLayer).layer(AuthLayer);
+//! service.serve(transport).await?;
+//! ```
+
+use std::future::Future;
+
+use crate::{
+    error::ErrorData as McpError,
+    model::{ClientNotification, ClientRequest, ServerInfo, ServerResult},
+    service::{
+        DynService, MaybeSend, MaybeSendFuture, NotificationContext, PanicPolicy, RequestContext,
+        RoleServer, Service,
+    },
+};
+
+/// The rest of the stack below a layer.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    inner: &'a dyn DynService<RoleServer>,
+}
+
+impl std::fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next").finish_non_exhaustive()
+    }
+}
+
+impl<'a> Next<'a> {
+    /// Pass a request on to the inner service.
+    pub async fn run(
+        self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, McpError> {
+        self.inner.handle_request(request, context).await
+    }
+
+    /// Pass a notification on to the inner service.
+    pub async fn notify(
+        self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.inner.handle_notification(notification, context).await
+    }
+
+    /// The server info of the inner service.
+    pub fn get_info(&self) -> ServerInfo {
+        self.inner.get_info()
+    }
+}
+
+/// Cross-cutting logic around a server, e.g. auth checks, audit logging,
+/// metrics or argument rewriting.
+///
+/// Both methods pass everything through unchanged by default.
+pub trait McpLayer: MaybeSend + 'static {
+    fn handle_request<'a>(
+        &'a self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+        next: Next<'a>,
+    ) -> impl Future<Output = Result<ServerResult, McpError>> + MaybeSendFuture + 'a {
+        next.run(request, context)
+    }
+
+    /// Returning an error drops the notification; it is logged but not sent
+    /// back to the client.
+    fn handle_notification<'a>(
+        &'a self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+        next: Next<'a>,
+    ) -> impl Future<Output = Result<(), McpError>> + MaybeSendFuture + 'a {
+        next.notify(notification, context)
+    }
+}
+
+/// A service wrapped in an [`McpLayer`].
+#[derive(Debug, Clone)]
+pub struct Layered<S, L> {
+    inner: S,
+    layer: L,
+}
+
+impl<S, L> Layered<S, L> {
+    pub fn new(inner: S, layer: L) -> Self {
+        Self { inner, layer }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn layer_ref(&self) -> &L {
+        &self.layer
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, L> Service<RoleServer> for Layered<S, L>
+where
+    S: Service<RoleServer>,
+    L: McpLayer,
+{
+    fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<ServerResult, McpError>> + MaybeSendFuture + '_ {
+        self.layer
+            .handle_request(request, context, Next { inner: &self.inner })
+    }
+
+    fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), McpError>> + MaybeSendFuture + '_ {
+        self.layer
+            .handle_notification(notification, context, Next { inner: &self.inner })
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.inner.get_info()
+    }
+
+    fn panic_policy(&self) -> PanicPolicy {
+        self.inner.panic_policy()
+    }
+}
+
+/// Adds [`layer`](McpLayerExt::layer) to every server-side [`Service`],
+/// including [`ServerHandler`](crate::ServerHandler)s and
+/// [`Router`](super::router::Router)s.
+pub trait McpLayerExt: Service<RoleServer> + Sized {
+    /// Wrap this service in `layer`. The most recently added layer runs first.
+    fn layer<L: McpLayer>(self, layer: L) -> Layered<Self, L> {
+        Layered::new(self, layer)
+    }
+}
+
+impl<S: Service<RoleServer>> McpLayerExt for S {}
@@ -0,0 +1,309 @@
+//! Forward `tracing` events to clients as `notifications/message`.
+//!
+//! [`McpLogging`] is both an [`McpLayer`] and a `tracing-subscriber`
+//! [`Layer`]. As an `McpLayer` it runs every request inside an `mcp_request`
+//! span tied to the client that sent it, and remembers the level each client
+//! asks for with `logging/setLevel`. As a `Layer` it turns every event inside
+//! such a span into a [`LoggingMessageNotificationParam`] for that client:
+//!
+//! - the tracing level becomes the [`LoggingLevel`], with `TRACE` and `DEBUG`
+//!   both sent as `debug`;
+//! - the event's target becomes the `logger`;
+//! - the message and the event's fields become the `data` object.
+//!
+//! Events below the client's level are dropped. Until a client sets a level,
+//! [`with_default_level`](McpLogging::with_default_level) applies.
+//!
+//! ```rust,ignore
+//! use rmcp::handler::server::{layer::McpLayerExt, logging::McpLogging};
+//! use tracing_subscriber::prelude::*;
+//!
+//! let logging = McpLogging::new();
+//! tracing_subscriber::registry().with(logging.clone()).init();
+//! MyServer::new().layer(logging).serve(transport).await?;
+//! ```
+//!
+//! The server should announce the `logging` capability in its `get_info`.
+//! `logging/setLevel` is passed on to the server as well; if the server does
+//! not implement it, the layer answers it.
+//!
+//! Events are only forwarded from inside the request span, so work spawned
+//! onto other tasks needs to be [`instrument`](tracing::Instrument::instrument)ed
+//! with the current span.
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+use tokio::sync::mpsc;
+use tracing::{
+    Event, Instrument, Level, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id},
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+use super::layer::{McpLayer, Next};
+use crate::{
+    error::ErrorData as McpError,
+    model::{
+        ClientRequest, ErrorCode, LoggingLevel, LoggingMessageNotificationParam, ServerResult,
+    },
+    service::{Peer, RequestContext, RoleServer},
+};
+
+/// The field of the `mcp_request` span naming the client.
+const CLIENT_FIELD: &str = "mcp.client";
+
+/// The client of an `mcp_request` span, stored in the span's extensions.
+#[derive(Debug, Clone, Copy)]
+struct ClientId(u64);
+
+#[derive(Debug)]
+struct Client {
+    id: u64,
+    peer: Peer<RoleServer>,
+    level: Option<LoggingLevel>,
+    messages: mpsc::UnboundedSender<LoggingMessageNotificationParam>,
+}
+
+#[derive(Debug, Default)]
+struct Clients {
+    clients: Mutex<Vec<Client>>,
+    next_id: AtomicU64,
+}
+
+/// Sends the `tracing` events of requests to the clients that made them. See
+/// the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct McpLogging {
+    clients: Arc<Clients>,
+    default_level: LoggingLevel,
+}
+
+impl Default for McpLogging {
+    fn default() -> Self {
+        Self {
+            clients: Default::default(),
+            default_level: LoggingLevel::Info,
+        }
+    }
+}
+
+impl McpLogging {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The level for clients that have not sent `logging/setLevel`. Defaults
+    /// to [`LoggingLevel::Info`].
+    pub fn with_default_level(mut self, level: LoggingLevel) -> Self {
+        self.default_level = level;
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Client>> {
+        self.clients
+            .clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The id of `peer`, registering it on first use.
+    fn register(&self, peer: &Peer<RoleServer>) -> u64 {
+        let mut clients = self.lock();
+        if let Some(client) = clients.iter().find(|client| client.peer.is_same_peer(peer)) {
+            return client.id;
+        }
+        // clients that closed without failing a send are pruned here, so the
+        // list only grows with the number of live clients
+        clients.retain(|client| !client.peer.is_transport_closed());
+        let id = self.clients.next_id.fetch_add(1, Ordering::Relaxed);
+        let (messages, mut rx) = mpsc::unbounded_channel();
+        clients.push(Client {
+            id,
+            peer: peer.clone(),
+            level: None,
+            messages,
+        });
+        drop(clients);
+
+        // a task of its own keeps the messages in order and outside of the
+        // request span, so sending them is never logged back to the client
+        let peer = peer.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if peer.notify_logging_message(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+        id
+    }
+
+    /// Forget client `id`, once its messages can no longer be sent.
+    fn remove(&self, id: u64) {
+        self.lock().retain(|client| client.id != id);
+    }
+
+    fn set_level(&self, id: u64, level: LoggingLevel) {
+        if let Some(client) = self.lock().iter_mut().find(|client| client.id == id) {
+            client.level = Some(level);
+        }
+    }
+}
+
+impl McpLayer for McpLogging {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+        next: Next<'_>,
+    ) -> Result<ServerResult, McpError> {
+        let id = self.register(&context.peer);
+        let set_level = match &request {
+            ClientRequest::SetLevelRequest(request) => {
+                self.set_level(id, request.params.level);
+                true
+            }
+            _ => false,
+        };
+        // an ERROR span is enabled by any level filter, so the events inside
+        // it can always be matched to their client
+        let span = tracing::error_span!("mcp_request", mcp.client = id);
+        match next.run(request, context).instrument(span).await {
+            Err(error) if set_level && error.code == ErrorCode::METHOD_NOT_FOUND => {
+                Ok(ServerResult::empty(()))
+            }
+            result => result,
+        }
+    }
+}
+
+fn logging_level(level: &Level) -> LoggingLevel {
+    match *level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        Level::DEBUG | Level::TRACE => LoggingLevel::Debug,
+    }
+}
+
+/// Picks the client id out of the fields of a new span.
+#[derive(Default)]
+struct ClientVisitor(Option<u64>);
+
+impl Visit for ClientVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == CLIENT_FIELD {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// Collects the fields of an event into a JSON object.
+#[derive(Default)]
+struct JsonVisitor(serde_json::Map<String, serde_json::Value>);
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: impl Into<serde_json::Value>) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value);
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.insert(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, format!("{value:?}"));
+    }
+}
+
+impl<S> Layer<S> for McpLogging
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = ClientVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(client), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(ClientId(client));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(ClientId(id)) = ctx
+            .event_scope(event)
+            .and_then(|mut scope| scope.find_map(|span| span.extensions().get().copied()))
+        else {
+            return;
+        };
+        let level = logging_level(event.metadata().level());
+        // recording the event runs `Debug` impls, which may log themselves,
+        // so the lock is released first
+        let (min_level, messages) = {
+            let clients = self.lock();
+            let Some(client) = clients.iter().find(|client| client.id == id) else {
+                return;
+            };
+            (
+                client.level.unwrap_or(self.default_level),
+                client.messages.clone(),
+            )
+        };
+        // `LoggingLevel` is declared from least to most severe
+        if (level as u8) < (min_level as u8) {
+            return;
+        }
+        let mut data = JsonVisitor::default();
+        event.record(&mut data);
+        let message = LoggingMessageNotificationParam {
+            level,
+            logger: Some(event.metadata().target().to_owned()),
+            data: serde_json::Value::Object(data.0),
+        };
+        if messages.send(message).is_err() {
+            // the task sending to the client stopped, the client is gone
+            self.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logging_level() {
+        assert_eq!(logging_level(&Level::ERROR), LoggingLevel::Error);
+        assert_eq!(logging_level(&Level::WARN), LoggingLevel::Warning);
+        assert_eq!(logging_level(&Level::INFO), LoggingLevel::Info);
+        assert_eq!(logging_level(&Level::TRACE), LoggingLevel::Debug);
+        assert!((LoggingLevel::Debug as u8) < (LoggingLevel::Emergency as u8));
+    }
+}
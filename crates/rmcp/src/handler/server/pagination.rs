@@ -0,0 +1,115 @@
+//! Cursor pagination of `tools/list`, `prompts/list`, `resources/list` and
+//! `resources/templates/list`.
+//!
+//! Routers list their items sorted by name (or URI), so a cursor only has to
+//! remember the last key a client has seen: the next page starts with the first
+//! key after it. Such cursors stay valid when items are added or removed between
+//! two pages, and an item that exists for the whole listing is returned exactly
+//! once.
+//!
+//! Pagination is off until a page size is set on the router, e.g.
+//! [`ToolRouter::with_page_size`](super::router::tool::ToolRouter::with_page_size).
+//! Cursors are opaque to clients; one that was not produced here is rejected
+//! with `-32602`.
+
+use crate::{error::ErrorData as McpError, model::Cursor};
+
+/// Marks the cursor format, so it can change without misreading old cursors.
+const CURSOR_PREFIX: &str = "c1.";
+
+/// The cursor of a page that ends with the item keyed `last_key`.
+pub fn encode_cursor(last_key: &str) -> Cursor {
+    let mut cursor = String::with_capacity(CURSOR_PREFIX.len() + last_key.len() * 2);
+    cursor.push_str(CURSOR_PREFIX);
+    for byte in last_key.bytes() {
+        cursor.push_str(&format!("{byte:02x}"));
+    }
+    cursor
+}
+
+/// The key of the last item before `cursor`.
+pub fn decode_cursor(cursor: &str) -> Result<String, McpError> {
+    let invalid = || {
+        McpError::invalid_params(
+            "invalid cursor",
+            Some(serde_json::json!({ "cursor": cursor })),
+        )
+    };
+    let hex = cursor.strip_prefix(CURSOR_PREFIX).ok_or_else(invalid)?;
+    if hex.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// The page of `items` that follows `cursor`, and the cursor of the page after
+/// it, if any.
+///
+/// `items` must be sorted by `key`. Without a `page_size` the page holds every
+/// remaining item.
+pub fn paginate<T>(
+    mut items: Vec<T>,
+    key: impl Fn(&T) -> &str,
+    page_size: Option<usize>,
+    cursor: Option<&str>,
+) -> Result<(Vec<T>, Option<Cursor>), McpError> {
+    if let Some(cursor) = cursor {
+        let after = decode_cursor(cursor)?;
+        let start = items.partition_point(|item| key(item) <= after.as_str());
+        items.drain(..start);
+    }
+    match page_size {
+        Some(page_size) if items.len() > page_size => {
+            items.truncate(page_size);
+            let next_cursor = items.last().map(|item| encode_cursor(key(item)));
+            Ok((items, next_cursor))
+        }
+        _ => Ok((items, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ErrorCode;
+
+    fn page(items: &[&'static str], cursor: Option<&str>) -> (Vec<&'static str>, Option<Cursor>) {
+        paginate(items.to_vec(), |item| item, Some(2), cursor).unwrap()
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        for key in ["", "add", "ünïcödé/tool", "a.b-c_d"] {
+            assert_eq!(decode_cursor(&encode_cursor(key)).unwrap(), key);
+        }
+        for cursor in ["", "add", "c1.6", "c1.zz", "c0.61", "c1.ff"] {
+            let err = decode_cursor(cursor).unwrap_err();
+            assert_eq!(err.code, ErrorCode::INVALID_PARAMS, "{cursor}");
+        }
+    }
+
+    #[test]
+    fn test_pages_survive_list_changes() {
+        let (first, cursor) = page(&["a", "b", "c", "d", "e"], None);
+        assert_eq!(first, ["a", "b"]);
+
+        // "a" removed and "bb" added before the second page is requested
+        let (second, cursor) = page(&["b", "bb", "c", "d", "e"], cursor.as_deref());
+        assert_eq!(second, ["bb", "c"]);
+
+        let (third, cursor) = page(&["b", "bb", "c", "d", "e"], cursor.as_deref());
+        assert_eq!(third, ["d", "e"]);
+        assert_eq!(cursor, None);
+
+        let (all, cursor) = paginate(vec!["a", "b", "c"], |item| item, None, None).unwrap();
+        assert_eq!((all.len(), cursor), (3, None));
+    }
+}
@@ -0,0 +1,257 @@
+//! Progress reporting from request handlers.
+//!
+//! [`Progress`] is an extractor for tool, prompt, resource and completion
+//! handlers. It picks up the progress token the client sent in the request's
+//! `_meta` and sends `notifications/progress` for it:
+//!
+//! ```rust,ignore
+//! use rmcp::handler::server::progress::Progress;
+//!
+//! #[tool(description = "Import every file")]
+//! async fn import(&self, progress: Progress) -> String {
+//!     let files = self.files();
+//!     for (done, file) in files.iter().enumerate() {
+//!         self.import_file(file).await;
+//!         progress
+//!             .report((done + 1) as f64, Some(files.len() as f64), Some(file.name()))
+//!             .await;
+//!     }
+//!     "imported".to_string()
+//! }
+//! ```
+//!
+//! Reports are best effort:
+//!
+//! - without a progress token in the request, [`report`](Progress::report)
+//!   does nothing;
+//! - a value not greater than the last one sent is dropped, as the protocol
+//!   requires progress to increase;
+//! - reports closer together than
+//!   [`with_min_interval`](Progress::with_min_interval) are held back, and
+//!   only the latest of them is sent once the interval has passed. The report
+//!   that reaches `total` is sent at once.
+//!
+//! [`Meta`](crate::model::Meta) takes the request's `_meta` when it is
+//! extracted, so put `Progress` before it in the handler's arguments.
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::time::Instant;
+
+use super::common::{AsRequestContext, FromContextPart};
+use crate::{
+    error::ErrorData as McpError,
+    model::{ProgressNotificationParam, ProgressToken},
+    service::{Peer, RequestContext, RoleServer},
+};
+
+/// How long [`Progress`] waits between notifications by default.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Default)]
+struct Sent {
+    progress: Option<f64>,
+    at: Option<Instant>,
+    /// The latest throttled report, sent when the interval has passed.
+    pending: Option<ProgressNotificationParam>,
+}
+
+#[derive(Debug)]
+struct Reporter {
+    token: ProgressToken,
+    peer: Peer<RoleServer>,
+    // held while sending, so notifications go out in the order of their values
+    sent: tokio::sync::Mutex<Sent>,
+}
+
+impl Reporter {
+    async fn send(&self, sent: &mut Sent, param: ProgressNotificationParam) {
+        sent.progress = Some(param.progress);
+        sent.at = Some(Instant::now());
+        sent.pending = None;
+        if let Err(error) = self.peer.notify_progress(param).await {
+            tracing::debug!(%error, "failed to send progress notification");
+        }
+    }
+
+    /// Send the pending report once `min_interval` has passed since the last
+    /// notification. Reports sent in the meantime push the deadline back.
+    fn flush_later(self: Arc<Self>, min_interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                let mut sent = self.sent.lock().await;
+                if sent.pending.is_none() {
+                    return;
+                }
+                let due = sent.at.map(|at| at + min_interval);
+                if let Some(due) = due.filter(|due| *due > Instant::now()) {
+                    drop(sent);
+                    tokio::time::sleep_until(due).await;
+                    continue;
+                }
+                if let Some(param) = sent.pending.take() {
+                    self.send(&mut sent, param).await;
+                }
+                return;
+            }
+        });
+    }
+}
+
+/// Reports the progress of a request to the client. See the
+/// [module docs](self).
+///
+/// Clones report for the same request and share its throttling.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    reporter: Option<Arc<Reporter>>,
+    min_interval: Duration,
+}
+
+impl Progress {
+    /// Progress for the request of `context`.
+    pub fn new(context: &RequestContext<RoleServer>) -> Self {
+        Self {
+            reporter: context.meta.get_progress_token().map(|token| {
+                Arc::new(Reporter {
+                    token,
+                    peer: context.peer.clone(),
+                    sent: Default::default(),
+                })
+            }),
+            min_interval: DEFAULT_MIN_INTERVAL,
+        }
+    }
+
+    /// The shortest time between two notifications. Defaults to
+    /// [`DEFAULT_MIN_INTERVAL`].
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// The progress token of the request, if the client sent one.
+    pub fn token(&self) -> Option<&ProgressToken> {
+        self.reporter.as_ref().map(|reporter| &reporter.token)
+    }
+
+    /// Whether the client asked for progress, i.e. whether reports are sent.
+    pub fn is_enabled(&self) -> bool {
+        self.reporter.is_some()
+    }
+
+    /// Report that `progress` out of `total` (if known) is done.
+    pub async fn report(&self, progress: f64, total: Option<f64>, message: Option<String>) {
+        let Some(reporter) = &self.reporter else {
+            return;
+        };
+        let mut sent = reporter.sent.lock().await;
+        // a pending report is always ahead of the last one sent
+        let last = sent
+            .pending
+            .as_ref()
+            .map(|pending| pending.progress)
+            .or(sent.progress);
+        if last.is_some_and(|last| progress <= last) {
+            return;
+        }
+        let param = ProgressNotificationParam {
+            progress_token: reporter.token.clone(),
+            progress,
+            total,
+            message,
+        };
+        let finished = total.is_some_and(|total| progress >= total);
+        if !finished
+            && sent
+                .at
+                .is_some_and(|at| Instant::now().saturating_duration_since(at) < self.min_interval)
+        {
+            if sent.pending.replace(param).is_none() {
+                reporter.clone().flush_later(self.min_interval);
+            }
+            return;
+        }
+        reporter.send(&mut sent, param).await;
+    }
+}
+
+impl<C> FromContextPart<C> for Progress
+where
+    C: AsRequestContext,
+{
+    fn from_context_part(context: &mut C) -> Result<Self, McpError> {
+        Ok(Self::new(context.as_request_context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        model::{NumberOrString, ServerNotification},
+        service::{AtomicU32RequestIdProvider, PeerSinkMessage},
+    };
+
+    /// A request context whose peer records the progress it is sent.
+    fn context(token: Option<i64>) -> (RequestContext<RoleServer>, Arc<Mutex<Vec<f64>>>) {
+        let (peer, mut rx) =
+            Peer::<RoleServer>::new(Arc::new(AtomicU32RequestIdProvider::default()), None);
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let recorder = reported.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if let PeerSinkMessage::Notification {
+                    notification: ServerNotification::ProgressNotification(notification),
+                    responder,
+                } = message
+                {
+                    recorder.lock().unwrap().push(notification.params.progress);
+                    let _ = responder.send(Ok(()));
+                }
+            }
+        });
+        let mut context = RequestContext::new(NumberOrString::Number(1), peer);
+        if let Some(token) = token {
+            context
+                .meta
+                .set_progress_token(ProgressToken(NumberOrString::Number(token)));
+        }
+        (context, reported)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reports_increase_and_are_throttled() {
+        let (context, reported) = context(Some(7));
+        let progress = Progress::new(&context).with_min_interval(Duration::from_secs(1));
+        assert!(progress.is_enabled());
+
+        progress.report(1.0, Some(4.0), None).await;
+        // throttled, and replaced by the next report
+        progress.report(2.0, Some(4.0), None).await;
+        progress.report(2.5, Some(4.0), None).await;
+        // not increasing over the pending report
+        progress.report(2.0, Some(4.0), None).await;
+        // the pending report is sent when the interval has passed
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        progress.report(3.0, Some(4.0), None).await;
+        // throttled, then overtaken by the last report, which is never throttled
+        progress.report(3.5, Some(4.0), None).await;
+        progress.report(4.0, Some(4.0), None).await;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        assert_eq!(*reported.lock().unwrap(), [1.0, 2.5, 3.0, 4.0]);
+    }
+
+    #[tokio::test]
+    async fn test_no_token_is_a_no_op() {
+        let (context, reported) = context(None);
+        let progress = Progress::new(&context);
+        assert!(!progress.is_enabled());
+        progress.report(1.0, None, None).await;
+        tokio::task::yield_now().await;
+        assert!(reported.lock().unwrap().is_empty());
+    }
+}
@@ -4,6 +4,8 @@
 //! in MCP servers. Prompts allow servers to provide reusable templates for LLM
 //! interactions with customizable arguments.
 
+pub mod template;
+
 use std::{future::Future, marker::PhantomData};
 
 #[cfg(not(feature = "local"))]
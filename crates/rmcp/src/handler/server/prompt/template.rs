@@ -0,0 +1,452 @@
+//! Prompts written as text templates.
+//!
+//! A [`PromptTemplate`] is the text of a prompt with placeholders for its
+//! arguments. The arguments of the prompt are derived from the placeholders:
+//!
+//! - `{{name}}` is a required argument;
+//! - `{{name?}}` is an optional argument, rendered as nothing when missing;
+//! - `{{name|default}}` is an optional argument with a default.
+//!
+//! Lines holding nothing but a directive split the text into messages:
+//!
+//! - `{{@user}}` and `{{@assistant}}` start a message of that role. Text
+//!   before the first of them is sent as the user;
+//! - `{{@resource <uri>}}` embeds the resource at `<uri>`;
+//! - `{{@image <uri>}}` embeds the image served as the blob resource at
+//!   `<uri>`.
+//!
+//! Resources are read through the server's own
+//! [`read_resource`](crate::ServerHandler::read_resource) when the prompt is
+//! rendered.
+//!
+//! ```text
+//! Please review the {{language}} code below, focusing on {{focus|correctness}}.
+//! {{@resource file:///project/src/main.rs}}
+//! {{@assistant}}
+//! I will review it for {{focus|correctness}}.
+//! ```
+//!
+//! Placeholders are not substituted inside directives. Use
+//! [`PromptRoute::from_template`] or `#[prompt(template = "...")]` to serve a
+//! template.
+//!
+//! [`PromptRoute::from_template`]: crate::handler::server::router::prompt::PromptRoute::from_template
+
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    ServerHandler,
+    error::ErrorData as McpError,
+    model::{
+        AnnotateAble, JsonObject, PromptArgument, PromptMessage, PromptMessageContent,
+        PromptMessageRole, RawEmbeddedResource, RawImageContent, ReadResourceRequestParams,
+        ResourceContents,
+    },
+    service::{RequestContext, RoleServer},
+};
+
+/// Why a template could not be loaded or parsed.
+#[derive(Debug, Clone, thiserror::Error)]
+#[non_exhaustive]
+pub enum PromptTemplateError {
+    /// A `{{` without a matching `}}`.
+    #[error("line {line}: unclosed placeholder")]
+    Unclosed { line: usize },
+    /// A placeholder whose name is empty or has characters other than
+    /// `A-Z`, `a-z`, `0-9`, `_` and `-`.
+    #[error("line {line}: invalid placeholder `{{{{{placeholder}}}}}`")]
+    InvalidPlaceholder { line: usize, placeholder: String },
+    /// A `{{@...}}` that is not one of the known directives.
+    #[error("line {line}: unknown directive `{{{{@{directive}}}}}`")]
+    UnknownDirective { line: usize, directive: String },
+    /// A directive that shares its line with other text.
+    #[error("line {line}: a directive must be on a line of its own")]
+    DirectiveNotAlone { line: usize },
+    /// The template file could not be read.
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: String,
+        #[source]
+        source: Arc<std::io::Error>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Argument {
+        name: String,
+        default: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Role(PromptMessageRole),
+    Text(Vec<Piece>),
+    Resource(String),
+    Image(String),
+}
+
+/// A prompt template. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    blocks: Vec<Block>,
+    arguments: Vec<PromptArgument>,
+}
+
+impl PromptTemplate {
+    /// Parse the text of a template, e.g. one included with `include_str!`.
+    pub fn parse(text: &str) -> Result<Self, PromptTemplateError> {
+        let mut blocks = Vec::new();
+        let mut text_pieces: Vec<Piece> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            if let Some(directive) = parse_directive(line, line_number)? {
+                if !text_pieces.is_empty() {
+                    blocks.push(Block::Text(std::mem::take(&mut text_pieces)));
+                }
+                blocks.push(directive);
+                continue;
+            }
+            if !text_pieces.is_empty() {
+                push_literal(&mut text_pieces, "\n");
+            }
+            parse_line(line, line_number, &mut text_pieces)?;
+        }
+        if !text_pieces.is_empty() {
+            blocks.push(Block::Text(text_pieces));
+        }
+
+        let mut arguments: Vec<PromptArgument> = Vec::new();
+        for block in &blocks {
+            let Block::Text(pieces) = block else {
+                continue;
+            };
+            for piece in pieces {
+                let Piece::Argument { name, default } = piece else {
+                    continue;
+                };
+                let required = default.is_none();
+                match arguments.iter_mut().find(|argument| &argument.name == name) {
+                    // an argument is required if any of its placeholders is
+                    Some(argument) => {
+                        if required {
+                            argument.required = Some(true);
+                        }
+                    }
+                    None => arguments.push(PromptArgument::new(name).with_required(required)),
+                }
+            }
+        }
+        Ok(Self { blocks, arguments })
+    }
+
+    /// Read and parse the template at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PromptTemplateError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| PromptTemplateError::Read {
+            path: path.display().to_string(),
+            source: Arc::new(source),
+        })?;
+        Self::parse(&text)
+    }
+
+    /// The arguments of the prompt, in the order they first appear.
+    pub fn arguments(&self) -> &[PromptArgument] {
+        &self.arguments
+    }
+
+    /// Check that every required argument is in `arguments`, answering with
+    /// `invalid_params` otherwise.
+    pub fn check_arguments(&self, arguments: Option<&JsonObject>) -> Result<(), McpError> {
+        let missing: Vec<&str> = self
+            .arguments
+            .iter()
+            .filter(|argument| argument.required == Some(true))
+            .map(|argument| argument.name.as_str())
+            .filter(|name| {
+                arguments
+                    .and_then(|arguments| arguments.get(*name))
+                    .is_none_or(serde_json::Value::is_null)
+            })
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(McpError::invalid_params(
+            format!("missing required arguments: {}", missing.join(", ")),
+            Some(serde_json::json!({ "missing": missing })),
+        ))
+    }
+
+    /// Render the messages of the prompt for `arguments`, reading the
+    /// resources it refers to from `server`.
+    pub async fn render<S: ServerHandler>(
+        &self,
+        server: &S,
+        arguments: Option<&JsonObject>,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        self.check_arguments(arguments)?;
+        let mut role = PromptMessageRole::User;
+        let mut messages = Vec::new();
+        for block in &self.blocks {
+            match block {
+                Block::Role(new_role) => role = new_role.clone(),
+                Block::Text(pieces) => {
+                    let text = render_text(pieces, arguments);
+                    let text = text.trim_matches('\n');
+                    if !text.trim().is_empty() {
+                        messages.push(PromptMessage::new_text(role.clone(), text));
+                    }
+                }
+                Block::Resource(uri) => {
+                    for resource in read_resource(server, uri, context).await? {
+                        messages.push(PromptMessage::new(
+                            role.clone(),
+                            PromptMessageContent::Resource {
+                                resource: RawEmbeddedResource::new(resource).no_annotation(),
+                            },
+                        ));
+                    }
+                }
+                Block::Image(uri) => {
+                    for contents in read_resource(server, uri, context).await? {
+                        let ResourceContents::BlobResourceContents {
+                            mime_type, blob, ..
+                        } = contents
+                        else {
+                            return Err(McpError::internal_error(
+                                format!("image resource '{uri}' is not a blob"),
+                                None,
+                            ));
+                        };
+                        let image = RawImageContent {
+                            data: blob,
+                            mime_type: mime_type.unwrap_or_else(|| "image/png".to_owned()),
+                            meta: None,
+                        };
+                        messages.push(PromptMessage::new(
+                            role.clone(),
+                            PromptMessageContent::Image {
+                                image: image.no_annotation(),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
+
+impl std::str::FromStr for PromptTemplate {
+    type Err = PromptTemplateError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+async fn read_resource<S: ServerHandler>(
+    server: &S,
+    uri: &str,
+    context: &RequestContext<RoleServer>,
+) -> Result<Vec<ResourceContents>, McpError> {
+    let result = server
+        .read_resource(ReadResourceRequestParams::new(uri), context.clone())
+        .await?;
+    Ok(result.contents)
+}
+
+fn render_text(pieces: &[Piece], arguments: Option<&JsonObject>) -> String {
+    let mut text = String::new();
+    for piece in pieces {
+        match piece {
+            Piece::Literal(literal) => text.push_str(literal),
+            Piece::Argument { name, default } => {
+                match arguments.and_then(|arguments| arguments.get(name)) {
+                    Some(serde_json::Value::String(value)) => text.push_str(value),
+                    Some(serde_json::Value::Null) | None => {
+                        text.push_str(default.as_deref().unwrap_or_default())
+                    }
+                    Some(value) => text.push_str(&value.to_string()),
+                }
+            }
+        }
+    }
+    text
+}
+
+fn push_literal(pieces: &mut Vec<Piece>, literal: &str) {
+    if let Some(Piece::Literal(last)) = pieces.last_mut() {
+        last.push_str(literal);
+    } else {
+        pieces.push(Piece::Literal(literal.to_owned()));
+    }
+}
+
+/// The directive on `line`, if it holds one.
+fn parse_directive(line: &str, line_number: usize) -> Result<Option<Block>, PromptTemplateError> {
+    let trimmed = line.trim();
+    let Some(directive) = trimmed
+        .strip_prefix("{{@")
+        .and_then(|rest| rest.strip_suffix("}}"))
+    else {
+        return Ok(None);
+    };
+    let (keyword, value) = directive
+        .trim()
+        .split_once(char::is_whitespace)
+        .map(|(keyword, value)| (keyword, value.trim()))
+        .unwrap_or((directive.trim(), ""));
+    let block = match (keyword, value) {
+        ("user", "") => Block::Role(PromptMessageRole::User),
+        ("assistant", "") => Block::Role(PromptMessageRole::Assistant),
+        ("resource", uri) if !uri.is_empty() => Block::Resource(uri.to_owned()),
+        ("image", uri) if !uri.is_empty() => Block::Image(uri.to_owned()),
+        _ => {
+            return Err(PromptTemplateError::UnknownDirective {
+                line: line_number,
+                directive: directive.trim().to_owned(),
+            });
+        }
+    };
+    Ok(Some(block))
+}
+
+fn is_argument_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+/// Add the pieces of a line of text to `pieces`.
+fn parse_line(
+    line: &str,
+    line_number: usize,
+    pieces: &mut Vec<Piece>,
+) -> Result<(), PromptTemplateError> {
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        push_literal(pieces, &rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or(PromptTemplateError::Unclosed { line: line_number })?;
+        let placeholder = after[..end].trim();
+        if placeholder.starts_with('@') {
+            return Err(PromptTemplateError::DirectiveNotAlone { line: line_number });
+        }
+        let (name, default) = if let Some(name) = placeholder.strip_suffix('?') {
+            (name.trim(), Some(String::new()))
+        } else if let Some((name, default)) = placeholder.split_once('|') {
+            (name.trim(), Some(default.to_owned()))
+        } else {
+            (placeholder, None)
+        };
+        if !is_argument_name(name) {
+            return Err(PromptTemplateError::InvalidPlaceholder {
+                line: line_number,
+                placeholder: placeholder.to_owned(),
+            });
+        }
+        pieces.push(Piece::Argument {
+            name: name.to_owned(),
+            default,
+        });
+        rest = &after[end + 2..];
+    }
+    push_literal(pieces, rest);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(value: serde_json::Value) -> JsonObject {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_arguments_from_placeholders() {
+        let template = PromptTemplate::parse(
+            "Greet {{name}} in a {{style|friendly}} style{{suffix?}}.\n{{name}}",
+        )
+        .unwrap();
+        let arguments: Vec<_> = template
+            .arguments()
+            .iter()
+            .map(|argument| (argument.name.as_str(), argument.required))
+            .collect();
+        assert_eq!(
+            arguments,
+            [
+                ("name", Some(true)),
+                ("style", Some(false)),
+                ("suffix", Some(false))
+            ]
+        );
+
+        let Block::Text(pieces) = &template.blocks[0] else {
+            panic!("expected text");
+        };
+        let rendered = render_text(pieces, Some(&object(serde_json::json!({ "name": "Ada" }))));
+        assert_eq!(rendered, "Greet Ada in a friendly style.\nAda");
+    }
+
+    #[test]
+    fn test_missing_required_arguments() {
+        let template = PromptTemplate::parse("{{a}} {{b}} {{c?}}").unwrap();
+        let error = template
+            .check_arguments(Some(&object(serde_json::json!({ "b": "x" }))))
+            .unwrap_err();
+        assert_eq!(error.code, crate::model::ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.data, Some(serde_json::json!({ "missing": ["a"] })));
+        assert!(template.check_arguments(None).is_err());
+        assert!(
+            template
+                .check_arguments(Some(&object(serde_json::json!({ "a": "x", "b": "y" }))))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_directives() {
+        let template = PromptTemplate::parse(
+            "intro\n{{@assistant}}\nreply\n  {{@resource test://static-text}}\n{{@image test://image}}",
+        )
+        .unwrap();
+        assert_eq!(
+            template.blocks,
+            [
+                Block::Text(vec![Piece::Literal("intro".into())]),
+                Block::Role(PromptMessageRole::Assistant),
+                Block::Text(vec![Piece::Literal("reply".into())]),
+                Block::Resource("test://static-text".into()),
+                Block::Image("test://image".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (text, line) in [
+            ("ok\nHello {{name", 2),
+            ("Hello {{first name}}", 1),
+            ("Hello {{}}", 1),
+            ("{{@system}}", 1),
+            ("{{@resource}}", 1),
+            ("see {{@resource test://x}}", 1),
+        ] {
+            let error = PromptTemplate::parse(text).unwrap_err();
+            assert!(
+                error.to_string().starts_with(&format!("line {line}:")),
+                "{text}: {error}"
+            );
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Token-bucket rate limiting of client requests.
+//!
+//! A [`RateLimiter`] keeps one bucket per key, see [`RateLimitKey`]. Every
+//! limited request takes a token; tokens refill at a fixed rate up to the
+//! bucket's capacity. A request that finds its bucket empty is rejected with
+//! the time until the next token is available.
+//!
+//! The same limiter works at two levels:
+//!
+//! - as an [`McpLayer`](super::layer::McpLayer) for any transport, e.g. stdio,
+//!   where a rejected request fails with [`RATE_LIMITED`] and
+//!   `{"retryAfterMs": ...}` in the error data;
+//! - in [`StreamableHttpService`](crate::transport::StreamableHttpService) via
+//!   `StreamableHttpServerConfig::with_rate_limiter`, where it answers
+//!   `429 Too Many Requests` with a `Retry-After` header before the request
+//!   reaches the session.
+//!
+//! ```rust,ignore
+//! use rmcp::handler::server::{layer::McpLayerExt, rate_limit::{RateLimit, RateLimitKey, RateLimiter}};
+//!
+//! let limiter = RateLimiter::new(RateLimit::per_second(5).with_burst(20), RateLimitKey::Session)
+//!     .with_methods(["tools/call"]);
+//! let service = MyServer::new().layer(limiter);
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{
+    error::ErrorData as McpError,
+    model::{ClientRequest, ErrorCode, Extensions, GetExtensions, ServerResult},
+    service::{RequestContext, RoleServer},
+};
+
+/// Error code of requests rejected by a [`RateLimiter`].
+///
+/// JSON-RPC reserves `-32000` to `-32099` for implementation-defined server errors.
+pub const RATE_LIMITED: ErrorCode = ErrorCode(-32029);
+
+/// Buckets are pruned once a limiter tracks more keys than this.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// How many requests a bucket allows and how fast it refills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    capacity: u32,
+    refill_every: Duration,
+}
+
+impl RateLimit {
+    /// Allow bursts of `capacity` requests, adding one token every `refill_every`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or `refill_every` is zero.
+    pub fn new(capacity: u32, refill_every: Duration) -> Self {
+        assert!(capacity > 0, "rate limit capacity must be at least 1");
+        assert!(
+            !refill_every.is_zero(),
+            "rate limit refill interval must be non-zero"
+        );
+        Self {
+            capacity,
+            refill_every,
+        }
+    }
+
+    /// `requests` per second, with a burst of the same size.
+    pub fn per_second(requests: u32) -> Self {
+        Self::per(requests, Duration::from_secs(1))
+    }
+
+    /// `requests` per minute, with a burst of the same size.
+    pub fn per_minute(requests: u32) -> Self {
+        Self::per(requests, Duration::from_secs(60))
+    }
+
+    fn per(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        Self::new(requests, period / requests)
+    }
+
+    /// Change how many requests can be made at once without changing the rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_burst(self, capacity: u32) -> Self {
+        Self::new(capacity, self.refill_every)
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn refill_every(&self) -> Duration {
+        self.refill_every
+    }
+}
+
+type PrincipalFn = Arc<dyn Fn(&Extensions) -> Option<String> + Send + Sync>;
+
+/// What a [`RateLimiter`] keeps a separate bucket for.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum RateLimitKey {
+    /// One bucket per `Mcp-Session-Id`. Requests without a session, e.g. on
+    /// stdio or stateless HTTP, share one bucket per limiter.
+    Session,
+    /// One bucket per tool name. Only `tools/call` requests are limited.
+    Tool,
+    /// One bucket per principal, as returned by the function from the request
+    /// extensions. Requests without a principal share one bucket.
+    Principal(PrincipalFn),
+}
+
+impl RateLimitKey {
+    /// Key by the principal that `f` extracts from the request extensions.
+    ///
+    /// Over HTTP the extensions carry the request's [`http::request::Parts`],
+    /// including whatever an authentication middleware inserted into them.
+    pub fn principal(f: impl Fn(&Extensions) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self::Principal(Arc::new(f))
+    }
+
+    fn key_for(&self, request: &ClientRequest) -> Option<String> {
+        match self {
+            Self::Session => Some(session_id(request.extensions()).unwrap_or_default()),
+            Self::Tool => match request {
+                ClientRequest::CallToolRequest(call) => Some(call.params.name.to_string()),
+                _ => None,
+            },
+            Self::Principal(f) => Some(f(request.extensions()).unwrap_or_default()),
+        }
+    }
+}
+
+impl std::fmt::Debug for RateLimitKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Session => f.write_str("Session"),
+            Self::Tool => f.write_str("Tool"),
+            Self::Principal(_) => f.write_str("Principal(..)"),
+        }
+    }
+}
+
+#[cfg(feature = "server-side-http")]
+fn session_id(extensions: &Extensions) -> Option<String> {
+    use crate::transport::common::http_header::HEADER_SESSION_ID;
+    extensions
+        .get::<http::request::Parts>()?
+        .headers
+        .get(HEADER_SESSION_ID)?
+        .to_str()
+        .ok()
+        .map(str::to_owned)
+}
+
+#[cfg(not(feature = "server-side-http"))]
+fn session_id(_extensions: &Extensions) -> Option<String> {
+    None
+}
+
+/// A request rejected by a [`RateLimiter`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("rate limit exceeded, retry after {retry_after:?}")]
+#[non_exhaustive]
+pub struct RateLimited {
+    /// The bucket that ran out of tokens.
+    pub key: String,
+    /// Time until the bucket holds a token again.
+    pub retry_after: Duration,
+}
+
+impl From<RateLimited> for McpError {
+    fn from(value: RateLimited) -> Self {
+        McpError::new(
+            RATE_LIMITED,
+            "rate limit exceeded",
+            Some(serde_json::json!({
+                "retryAfterMs": u64::try_from(value.retry_after.as_millis()).unwrap_or(u64::MAX),
+            })),
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: u32,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        let earned = elapsed.as_nanos() / limit.refill_every.as_nanos();
+        let tokens = (u128::from(self.tokens) + earned).min(u128::from(limit.capacity));
+        self.tokens = tokens as u32;
+        if self.tokens == limit.capacity {
+            self.refilled_at = now;
+        } else {
+            // keep the fraction of a token earned so far
+            self.refilled_at += limit.refill_every * earned as u32;
+        }
+    }
+}
+
+struct RateLimiterInner {
+    limit: RateLimit,
+    key: RateLimitKey,
+    methods: Option<Vec<Cow<'static, str>>>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// Token-bucket limiter for client requests, see the [module docs](self).
+///
+/// Cloning is cheap and every clone shares the same buckets.
+#[derive(Clone)]
+pub struct RateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("limit", &self.inner.limit)
+            .field("key", &self.inner.key)
+            .field("methods", &self.inner.methods)
+            .finish()
+    }
+}
+
+impl RateLimiter {
+    /// Limit every request except `initialize` and `ping`.
+    pub fn new(limit: RateLimit, key: RateLimitKey) -> Self {
+        Self {
+            inner: Arc::new(RateLimiterInner {
+                limit,
+                key,
+                methods: None,
+                buckets: Default::default(),
+            }),
+        }
+    }
+
+    /// Only limit requests with one of these methods, e.g. `"tools/call"`.
+    pub fn with_methods(
+        self,
+        methods: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        let inner = RateLimiterInner {
+            limit: self.inner.limit,
+            key: self.inner.key.clone(),
+            methods: Some(methods.into_iter().map(Into::into).collect()),
+            buckets: Default::default(),
+        };
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    pub fn limit(&self) -> RateLimit {
+        self.inner.limit
+    }
+
+    fn applies_to(&self, request: &ClientRequest) -> bool {
+        match &self.inner.methods {
+            Some(methods) => methods.iter().any(|m| m == request.method()),
+            None => !matches!(
+                request,
+                ClientRequest::InitializeRequest(_) | ClientRequest::PingRequest(_)
+            ),
+        }
+    }
+
+    /// Take a token for `request`. Requests the limiter does not apply to
+    /// always pass.
+    pub fn check(&self, request: &ClientRequest) -> Result<(), RateLimited> {
+        if !self.applies_to(request) {
+            return Ok(());
+        }
+        match self.inner.key.key_for(request) {
+            Some(key) => self.check_key(&key),
+            None => Ok(()),
+        }
+    }
+
+    /// Take a token from the bucket for `key`.
+    pub fn check_key(&self, key: &str) -> Result<(), RateLimited> {
+        let limit = &self.inner.limit;
+        let now = Instant::now();
+        let mut buckets = self
+            .inner
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if buckets.len() >= PRUNE_THRESHOLD && !buckets.contains_key(key) {
+            // a full bucket behaves exactly like a missing one
+            buckets.retain(|_, bucket| {
+                bucket.refill(limit, now);
+                bucket.tokens < limit.capacity
+            });
+        }
+        let bucket = buckets.entry(key.to_owned()).or_insert(Bucket {
+            tokens: limit.capacity,
+            refilled_at: now,
+        });
+        bucket.refill(limit, now);
+        if bucket.tokens > 0 {
+            bucket.tokens -= 1;
+            Ok(())
+        } else {
+            Err(RateLimited {
+                key: key.to_owned(),
+                retry_after: limit
+                    .refill_every
+                    .saturating_sub(now.saturating_duration_since(bucket.refilled_at)),
+            })
+        }
+    }
+}
+
+impl super::layer::McpLayer for RateLimiter {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+        next: super::layer::Next<'_>,
+    ) -> Result<ServerResult, McpError> {
+        self.check(&request)?;
+        next.run(request, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(RateLimit::per_second(2), RateLimitKey::Session);
+        assert!(limiter.check_key("a").is_ok());
+        assert!(limiter.check_key("a").is_ok());
+        let err = limiter.check_key("a").unwrap_err();
+        assert_eq!(err.retry_after, Duration::from_millis(500));
+        // other keys have their own bucket
+        assert!(limiter.check_key("b").is_ok());
+
+        tokio::time::advance(Duration::from_millis(200)).await;
+        let err = limiter.check_key("a").unwrap_err();
+        assert_eq!(err.retry_after, Duration::from_millis(300));
+
+        tokio::time::advance(Duration::from_millis(300)).await;
+        assert!(limiter.check_key("a").is_ok());
+        assert!(limiter.check_key("a").is_err());
+
+        // never more than the burst
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(limiter.check_key("a").is_ok());
+        assert!(limiter.check_key("a").is_ok());
+        assert!(limiter.check_key("a").is_err());
+    }
+
+    #[test]
+    fn test_rate_limited_error_data() {
+        let error = McpError::from(RateLimited {
+            key: String::new(),
+            retry_after: Duration::from_millis(1500),
+        });
+        assert_eq!(error.code, RATE_LIMITED);
+        assert_eq!(
+            error.data,
+            Some(serde_json::json!({ "retryAfterMs": 1500 }))
+        );
+    }
+}
@@ -1 +1,553 @@
+//! Resource handling infrastructure for MCP servers
+//!
+//! This module provides the core types and traits for implementing resource handlers
+//! in MCP servers. Static resources are served for a fixed URI, while resource
+//! templates match a family of URIs and expose the matched template variables to
+//! the handler.
 
+use std::{collections::HashMap, future::Future, marker::PhantomData};
+
+#[cfg(not(feature = "local"))]
+use futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+
+use super::common::{AsRequestContext, FromContextPart};
+pub use super::common::{Extension, RequestId};
+use crate::{
+    RoleServer,
+    handler::server::wrapper::Parameters,
+    model::{ReadResourceResult, ResourceContents},
+    service::{MaybeBoxFuture, MaybeSend, MaybeSendFuture, RequestContext},
+};
+
+/// Context for resource read operations
+#[non_exhaustive]
+pub struct ResourceContext<'a, S> {
+    pub server: &'a S,
+    pub uri: String,
+    pub variables: TemplateVariables,
+    pub context: RequestContext<RoleServer>,
+}
+
+impl<'a, S> ResourceContext<'a, S> {
+    pub fn new(server: &'a S, uri: String, context: RequestContext<RoleServer>) -> Self {
+        Self {
+            server,
+            uri,
+            variables: TemplateVariables::default(),
+            context,
+        }
+    }
+
+    /// Attach the variables matched from a resource template.
+    pub fn with_variables(mut self, variables: TemplateVariables) -> Self {
+        self.variables = variables;
+        self
+    }
+}
+
+impl<S> AsRequestContext for ResourceContext<'_, S> {
+    fn as_request_context(&self) -> &RequestContext<RoleServer> {
+        &self.context
+    }
+
+    fn as_request_context_mut(&mut self) -> &mut RequestContext<RoleServer> {
+        &mut self.context
+    }
+}
+
+/// Variables extracted from a URI matched against a resource template.
+///
+/// Values are kept as the raw (percent-decoded) strings taken from the URI.
+/// Use [`TemplateVariables::parse`] or the [`Parameters`] extractor to turn them
+/// into typed values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TemplateVariables {
+    pub values: HashMap<String, String>,
+}
+
+impl TemplateVariables {
+    pub fn new(values: HashMap<String, String>) -> Self {
+        Self { values }
+    }
+
+    /// Get the raw value of a variable.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Parse a variable into a typed value.
+    ///
+    /// A missing variable parses as `None` when `T` is an `Option`. Otherwise an
+    /// `invalid_params` error is returned if the variable is missing or cannot be
+    /// parsed into `T`.
+    pub fn parse<T: DeserializeOwned>(&self, name: &str) -> Result<T, crate::ErrorData> {
+        let result = match self.get(name) {
+            Some(value) => T::deserialize(de::StrDeserializer(value)),
+            None => T::deserialize(de::MissingDeserializer),
+        };
+        result.map_err(|e| {
+            crate::ErrorData::invalid_params(
+                format!("failed to parse template variable '{name}': {e}"),
+                None,
+            )
+        })
+    }
+
+    /// Deserialize all variables into a struct.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, crate::ErrorData> {
+        T::deserialize(de::VariablesDeserializer(&self.values)).map_err(|e| {
+            crate::ErrorData::invalid_params(
+                format!("failed to deserialize template variables: {e}"),
+                None,
+            )
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl From<HashMap<String, String>> for TemplateVariables {
+    fn from(values: HashMap<String, String>) -> Self {
+        Self::new(values)
+    }
+}
+
+/// Trait for handling resource reads
+pub trait ReadResourceHandler<S, A> {
+    fn handle(
+        self,
+        context: ResourceContext<'_, S>,
+    ) -> MaybeBoxFuture<'_, Result<ReadResourceResult, crate::ErrorData>>;
+}
+
+/// Type alias for dynamic resource handlers
+#[cfg(not(feature = "local"))]
+pub type DynReadResourceHandler<S> = dyn for<'a> Fn(
+        ResourceContext<'a, S>,
+    ) -> BoxFuture<'a, Result<ReadResourceResult, crate::ErrorData>>
+    + Send
+    + Sync;
+
+#[cfg(feature = "local")]
+pub type DynReadResourceHandler<S> = dyn for<'a> Fn(
+    ResourceContext<'a, S>,
+) -> futures::future::LocalBoxFuture<
+    'a,
+    Result<ReadResourceResult, crate::ErrorData>,
+>;
+
+/// Adapter types for macro-generated implementations
+#[allow(clippy::type_complexity)]
+pub struct AsyncResourceAdapter<P, Fut, R>(PhantomData<fn(P) -> fn(Fut) -> R>);
+pub struct SyncResourceAdapter<P, R>(PhantomData<fn(P) -> R>);
+pub struct SyncResourceMethodAdapter<P, R>(PhantomData<fn(P) -> R>);
+
+/// Trait for types that can be converted into a [`ReadResourceResult`]
+///
+/// The requested URI is passed along so that plain values such as `String` can
+/// be turned into [`ResourceContents`] for that URI.
+pub trait IntoReadResourceResult {
+    fn into_read_resource_result(self, uri: &str) -> Result<ReadResourceResult, crate::ErrorData>;
+}
+
+impl IntoReadResourceResult for ReadResourceResult {
+    fn into_read_resource_result(self, _uri: &str) -> Result<ReadResourceResult, crate::ErrorData> {
+        Ok(self)
+    }
+}
+
+impl IntoReadResourceResult for Vec<ResourceContents> {
+    fn into_read_resource_result(self, _uri: &str) -> Result<ReadResourceResult, crate::ErrorData> {
+        Ok(ReadResourceResult::new(self))
+    }
+}
+
+impl IntoReadResourceResult for ResourceContents {
+    fn into_read_resource_result(self, _uri: &str) -> Result<ReadResourceResult, crate::ErrorData> {
+        Ok(ReadResourceResult::new(vec![self]))
+    }
+}
+
+impl IntoReadResourceResult for String {
+    fn into_read_resource_result(self, uri: &str) -> Result<ReadResourceResult, crate::ErrorData> {
+        Ok(ReadResourceResult::new(vec![ResourceContents::text(
+            self, uri,
+        )]))
+    }
+}
+
+impl IntoReadResourceResult for &'static str {
+    fn into_read_resource_result(self, uri: &str) -> Result<ReadResourceResult, crate::ErrorData> {
+        self.to_string().into_read_resource_result(uri)
+    }
+}
+
+impl<T: IntoReadResourceResult> IntoReadResourceResult for Result<T, crate::ErrorData> {
+    fn into_read_resource_result(self, uri: &str) -> Result<ReadResourceResult, crate::ErrorData> {
+        self.and_then(|v| v.into_read_resource_result(uri))
+    }
+}
+
+// Resource-specific extractor for the requested URI
+#[expect(clippy::exhaustive_structs, reason = "intentionally exhaustive")]
+pub struct ResourceUri(pub String);
+
+impl<S> FromContextPart<ResourceContext<'_, S>> for ResourceUri {
+    fn from_context_part(context: &mut ResourceContext<S>) -> Result<Self, crate::ErrorData> {
+        Ok(Self(context.uri.clone()))
+    }
+}
+
+impl<S> FromContextPart<ResourceContext<'_, S>> for TemplateVariables {
+    fn from_context_part(context: &mut ResourceContext<S>) -> Result<Self, crate::ErrorData> {
+        Ok(context.variables.clone())
+    }
+}
+
+// Special implementation for Parameters that handles template variables
+impl<S, P> FromContextPart<ResourceContext<'_, S>> for Parameters<P>
+where
+    P: DeserializeOwned,
+{
+    fn from_context_part(context: &mut ResourceContext<S>) -> Result<Self, crate::ErrorData> {
+        context.variables.deserialize().map(Parameters)
+    }
+}
+
+// Macro to generate ReadResourceHandler implementations for various parameter combinations
+macro_rules! impl_resource_handler_for {
+    ($($T: ident)*) => {
+        impl_resource_handler_for!([] [$($T)*]);
+    };
+    // finished
+    ([$($Tn: ident)*] []) => {
+        impl_resource_handler_for!(@impl $($Tn)*);
+    };
+    ([$($Tn: ident)*] [$Tn_1: ident $($Rest: ident)*]) => {
+        impl_resource_handler_for!(@impl $($Tn)*);
+        impl_resource_handler_for!([$($Tn)* $Tn_1] [$($Rest)*]);
+    };
+    (@impl $($Tn: ident)*) => {
+        // Implementation for async methods (transformed by #[resource] macro)
+        impl<$($Tn,)* S, F, R> ReadResourceHandler<S, ($($Tn,)*)> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<ResourceContext<'a, S>> + MaybeSendFuture,
+            )*
+            F: FnOnce(&S, $($Tn,)*) -> MaybeBoxFuture<'_, R> + MaybeSendFuture,
+            R: IntoReadResourceResult + MaybeSendFuture + 'static,
+            S: MaybeSend + 'static,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: ResourceContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<ReadResourceResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let service = context.server;
+                let uri = context.uri;
+                let fut = self(service, $($Tn,)*);
+                Box::pin(async move {
+                    let result = fut.await;
+                    result.into_read_resource_result(&uri)
+                })
+            }
+        }
+
+        // Implementation for sync methods
+        impl<$($Tn,)* S, F, R> ReadResourceHandler<S, SyncResourceMethodAdapter<($($Tn,)*), R>> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<ResourceContext<'a, S>> + MaybeSendFuture,
+            )*
+            F: FnOnce(&S, $($Tn,)*) -> R + MaybeSendFuture,
+            R: IntoReadResourceResult + MaybeSendFuture,
+            S: MaybeSend,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: ResourceContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<ReadResourceResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let service = context.server;
+                let result = self(service, $($Tn,)*);
+                Box::pin(std::future::ready(result.into_read_resource_result(&context.uri)))
+            }
+        }
+
+        // AsyncResourceAdapter - for standalone async functions
+        impl<$($Tn,)* S, F, Fut, R> ReadResourceHandler<S, AsyncResourceAdapter<($($Tn,)*), Fut, R>> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<ResourceContext<'a, S>> + MaybeSendFuture + 'static,
+            )*
+            F: FnOnce($($Tn,)*) -> Fut + MaybeSendFuture + 'static,
+            Fut: Future<Output = R> + MaybeSendFuture + 'static,
+            R: IntoReadResourceResult + MaybeSendFuture + 'static,
+            S: MaybeSend + 'static,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: ResourceContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<ReadResourceResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let uri = context.uri;
+                Box::pin(async move {
+                    let result = self($($Tn,)*).await;
+                    result.into_read_resource_result(&uri)
+                })
+            }
+        }
+
+        // SyncResourceAdapter - for standalone sync functions
+        impl<$($Tn,)* S, F, R> ReadResourceHandler<S, SyncResourceAdapter<($($Tn,)*), R>> for F
+        where
+            $(
+                $Tn: for<'a> FromContextPart<ResourceContext<'a, S>> + MaybeSendFuture + 'static,
+            )*
+            F: FnOnce($($Tn,)*) -> R + MaybeSendFuture + 'static,
+            R: IntoReadResourceResult + MaybeSendFuture + 'static,
+            S: MaybeSend,
+        {
+            #[allow(unused_variables, non_snake_case, unused_mut)]
+            fn handle(
+                self,
+                mut context: ResourceContext<'_, S>,
+            ) -> MaybeBoxFuture<'_, Result<ReadResourceResult, crate::ErrorData>>
+            {
+                $(
+                    let result = $Tn::from_context_part(&mut context);
+                    let $Tn = match result {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(std::future::ready(Err(e))),
+                    };
+                )*
+                let result = self($($Tn,)*);
+                Box::pin(std::future::ready(result.into_read_resource_result(&context.uri)))
+            }
+        }
+    };
+}
+
+impl_resource_handler_for!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+
+/// A minimal serde deserializer for template variables.
+///
+/// Template variables are always strings on the wire, so scalar targets such as
+/// integers and booleans are parsed from the string on demand.
+mod de {
+    use std::collections::HashMap;
+
+    use serde::de::{self, IntoDeserializer, Visitor};
+
+    #[derive(Debug)]
+    pub(super) struct Error(String);
+
+    impl std::fmt::Display for Error {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    impl std::error::Error for Error {}
+
+    impl de::Error for Error {
+        fn custom<T: std::fmt::Display>(msg: T) -> Self {
+            Error(msg.to_string())
+        }
+    }
+
+    pub(super) struct StrDeserializer<'a>(pub &'a str);
+
+    macro_rules! parse_scalar {
+        ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+            $(
+                fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                    let value = self.0.parse::<$ty>().map_err(|e| {
+                        Error(format!("invalid value '{}': {}", self.0, e))
+                    })?;
+                    visitor.$visit(value)
+                }
+            )*
+        };
+    }
+
+    impl<'de> de::Deserializer<'de> for StrDeserializer<'_> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_str(self.0)
+        }
+
+        parse_scalar! {
+            deserialize_bool => visit_bool: bool,
+            deserialize_i8 => visit_i8: i8,
+            deserialize_i16 => visit_i16: i16,
+            deserialize_i32 => visit_i32: i32,
+            deserialize_i64 => visit_i64: i64,
+            deserialize_i128 => visit_i128: i128,
+            deserialize_u8 => visit_u8: u8,
+            deserialize_u16 => visit_u16: u16,
+            deserialize_u32 => visit_u32: u32,
+            deserialize_u64 => visit_u64: u64,
+            deserialize_u128 => visit_u128: u128,
+            deserialize_f32 => visit_f32: f32,
+            deserialize_f64 => visit_f64: f64,
+            deserialize_char => visit_char: char,
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_enum(self.0.into_deserializer())
+        }
+
+        serde::forward_to_deserialize_any! {
+            str string bytes byte_buf unit unit_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+
+    /// Deserializer for a variable that is absent from the matched URI.
+    pub(super) struct MissingDeserializer;
+
+    impl<'de> de::Deserializer<'de> for MissingDeserializer {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(Error("variable is missing".to_string()))
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_none()
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    pub(super) struct VariablesDeserializer<'a>(pub &'a HashMap<String, String>);
+
+    impl<'de> de::Deserializer<'de> for VariablesDeserializer<'_> {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_map(de::value::MapDeserializer::new(
+                self.0
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), StrDeserializer(v.as_str()))),
+            ))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for StrDeserializer<'_> {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self::Deserializer {
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> TemplateVariables {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+
+    #[test]
+    fn test_parse_typed_variable() {
+        let vars = variables(&[("id", "42"), ("name", "alice"), ("flag", "true")]);
+        assert_eq!(vars.parse::<u32>("id").unwrap(), 42);
+        assert_eq!(vars.parse::<String>("name").unwrap(), "alice");
+        assert!(vars.parse::<bool>("flag").unwrap());
+        assert!(vars.parse::<u32>("name").is_err());
+        assert!(vars.parse::<u32>("missing").is_err());
+        assert_eq!(vars.parse::<Option<u32>>("missing").unwrap(), None);
+        assert_eq!(vars.parse::<Option<u32>>("id").unwrap(), Some(42));
+    }
+
+    #[test]
+    fn test_deserialize_struct_from_variables() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct UserPath {
+            user: String,
+            id: u64,
+            page: Option<u32>,
+        }
+
+        let vars = variables(&[("user", "bob"), ("id", "7")]);
+        let parsed: UserPath = vars.deserialize().unwrap();
+        assert_eq!(
+            parsed,
+            UserPath {
+                user: "bob".into(),
+                id: 7,
+                page: None,
+            }
+        );
+
+        let vars = variables(&[("user", "bob"), ("id", "seven")]);
+        let err = vars.deserialize::<UserPath>().unwrap_err();
+        assert_eq!(err.code, crate::model::ErrorCode::INVALID_PARAMS);
+    }
+}
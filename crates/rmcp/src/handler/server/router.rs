@@ -1,22 +1,47 @@
 use std::sync::Arc;
 
+use completion::{CompletionRoute, CompletionTarget, IntoCompletionRoute};
 use prompt::{IntoPromptRoute, PromptRoute};
+use resource::{
+    IntoResourceRoute, IntoResourceTemplateRoute, ResourceRoute, ResourceTemplateRoute,
+};
 use tool::{IntoToolRoute, ToolRoute};
+use tool_registry::SharedToolRegistry;
 
-use super::ServerHandler;
+use super::{ServerHandler, subscription::SubscriptionManager};
 use crate::{
     RoleServer, Service,
-    model::{ClientNotification, ClientRequest, ListPromptsResult, ListToolsResult, ServerResult},
+    model::{ClientNotification, ClientRequest, ListToolsResult, ServerCapabilities, ServerResult},
     service::NotificationContext,
 };
 
+pub mod completion;
 pub mod prompt;
+pub mod resource;
 pub mod tool;
+pub mod tool_registry;
+
+/// Why a strict merge such as [`ToolRouter::try_merge`](tool::ToolRouter::try_merge)
+/// was refused. The target router is left untouched.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum MergeError {
+    /// The name is already registered, or two merged routes map to it.
+    #[error("route `{0}` is already registered")]
+    Collision(String),
+    /// The (prefixed or renamed) tool name is not a valid tool name.
+    #[error("invalid tool name `{0}`")]
+    InvalidName(String),
+}
 
 #[non_exhaustive]
 pub struct Router<S> {
     pub tool_router: tool::ToolRouter<S>,
+    pub tool_registry: Option<SharedToolRegistry<S>>,
     pub prompt_router: prompt::PromptRouter<S>,
+    pub resource_router: resource::ResourceRouter<S>,
+    pub completion_router: completion::CompletionRouter<S>,
+    pub subscriptions: Option<SubscriptionManager>,
     pub service: Arc<S>,
     peer_slot: Arc<std::sync::OnceLock<crate::service::Peer<RoleServer>>>,
 }
@@ -29,9 +54,17 @@ where
         let (notifier, peer_slot) = tool::ToolRouter::<S>::deferred_peer_notifier();
         let mut tool_router = tool::ToolRouter::new();
         tool_router.set_notifier(notifier);
+        let mut prompt_router = prompt::PromptRouter::new();
+        prompt_router.set_notifier(prompt::PromptRouter::<S>::deferred_peer_notifier(
+            peer_slot.clone(),
+        ));
         Self {
             tool_router,
-            prompt_router: prompt::PromptRouter::new(),
+            tool_registry: None,
+            prompt_router,
+            resource_router: resource::ResourceRouter::new(),
+            completion_router: completion::CompletionRouter::new(),
+            subscriptions: None,
             service: Arc::new(service),
             peer_slot,
         }
@@ -52,6 +85,14 @@ where
         self
     }
 
+    /// Serve the tools of a registry shared with other sessions, in addition
+    /// to the tools of this router. Tools of [`Router::tool_router`] win on
+    /// name clashes.
+    pub fn with_tool_registry(mut self, registry: SharedToolRegistry<S>) -> Self {
+        self.tool_registry = Some(registry);
+        self
+    }
+
     pub fn with_prompt<R, A: 'static>(mut self, route: R) -> Self
     where
         R: IntoPromptRoute<S, A>,
@@ -66,6 +107,68 @@ where
         }
         self
     }
+
+    pub fn with_resource<R, A: 'static>(mut self, route: R) -> Self
+    where
+        R: IntoResourceRoute<S, A>,
+    {
+        self.resource_router.add_route(route.into_resource_route());
+        self
+    }
+
+    pub fn with_resources(mut self, routes: impl IntoIterator<Item = ResourceRoute<S>>) -> Self {
+        for route in routes {
+            self.resource_router.add_route(route);
+        }
+        self
+    }
+
+    pub fn with_resource_template<R, A: 'static>(mut self, route: R) -> Self
+    where
+        R: IntoResourceTemplateRoute<S, A>,
+    {
+        self.resource_router
+            .add_template(route.into_resource_template_route());
+        self
+    }
+
+    pub fn with_resource_templates(
+        mut self,
+        routes: impl IntoIterator<Item = ResourceTemplateRoute<S>>,
+    ) -> Self {
+        for route in routes {
+            self.resource_router.add_template(route);
+        }
+        self
+    }
+
+    pub fn with_completion<R, A: 'static>(mut self, route: R) -> Self
+    where
+        R: IntoCompletionRoute<S, A>,
+    {
+        self.completion_router
+            .add_route(route.into_completion_route());
+        self
+    }
+
+    pub fn with_completions(
+        mut self,
+        routes: impl IntoIterator<Item = CompletionRoute<S>>,
+    ) -> Self {
+        for route in routes {
+            self.completion_router.add_route(route);
+        }
+        self
+    }
+
+    /// Answer `resources/subscribe` and `resources/unsubscribe` with `subscriptions`.
+    ///
+    /// Pass a clone of the same manager to every connection to be able to
+    /// notify all of them through [`SubscriptionManager::notify_updated`].
+    pub fn with_subscriptions(mut self, subscriptions: SubscriptionManager) -> Self {
+        self.subscriptions = Some(subscriptions);
+        self
+    }
 }
 
 impl<S> Service<RoleServer> for Router<S>
@@ -82,6 +185,9 @@ where
             ClientNotification::InitializedNotification(_)
         ) {
             let _ = self.peer_slot.set(context.peer.clone());
+            if let Some(registry) = &self.tool_registry {
+                registry.register_peer(&context.peer);
+            }
         }
         self.service
             .handle_notification(notification, context)
@@ -93,11 +199,30 @@ where
         context: crate::service::RequestContext<RoleServer>,
     ) -> Result<<RoleServer as crate::service::ServiceRole>::Resp, crate::ErrorData> {
         match request {
+            ClientRequest::InitializeRequest(request) => {
+                let mut result = self
+                    .service
+                    .handle_request(ClientRequest::InitializeRequest(request), context)
+                    .await?;
+                if let ServerResult::InitializeResult(result) = &mut result {
+                    self.extend_capabilities(&mut result.capabilities);
+                }
+                Ok(result)
+            }
             ClientRequest::CallToolRequest(request) => {
-                if self
-                    .tool_router
-                    .map
-                    .contains_key(request.params.name.as_ref())
+                let name = request.params.name.as_ref();
+                if let Some(registry) = self.tool_registry.as_ref().filter(|registry| {
+                    !self.tool_router.map.contains_key(name)
+                        && (registry.has_route(name) || registry.is_disabled(name))
+                }) {
+                    let tool_call_context = crate::handler::server::tool::ToolCallContext::new(
+                        self.service.as_ref(),
+                        request.params,
+                        context,
+                    );
+                    let result = registry.call(tool_call_context).await?;
+                    Ok(ServerResult::CallToolResult(result))
+                } else if self.tool_router.map.contains_key(name)
                     || !self.tool_router.transparent_when_not_found
                 {
                     let tool_call_context = crate::handler::server::tool::ToolCallContext::new(
@@ -113,15 +238,43 @@ where
                         .await
                 }
             }
-            ClientRequest::ListToolsRequest(_) => {
-                let tools = self.tool_router.list_all();
+            ClientRequest::ListToolsRequest(request) => {
+                let cursor = request.params.and_then(|params| params.cursor);
+                let Some(registry) = &self.tool_registry else {
+                    let result = self
+                        .tool_router
+                        .list_page(cursor.as_deref(), &context.extensions)?;
+                    return Ok(ServerResult::ListToolsResult(result));
+                };
+                let mut tools = self.tool_router.list_for(&context.extensions);
+                tools.extend(
+                    registry
+                        .list_for(&context.extensions)
+                        .into_iter()
+                        .filter(|tool| !self.tool_router.map.contains_key(&tool.name)),
+                );
+                tools.sort_by(|a, b| a.name.cmp(&b.name));
+                // the router's page size applies to the registry's tools too
+                let (tools, next_cursor) = crate::handler::server::pagination::paginate(
+                    tools,
+                    |tool| &tool.name,
+                    self.tool_router.page_size(),
+                    cursor.as_deref(),
+                )?;
                 Ok(ServerResult::ListToolsResult(ListToolsResult {
                     tools,
+                    next_cursor,
                     ..Default::default()
                 }))
             }
             ClientRequest::GetPromptRequest(request) => {
-                if self.prompt_router.has_route(request.params.name.as_ref()) {
+                // Disabled prompts stay with the router so they are rejected
+                // instead of reaching the wrapped service.
+                if self
+                    .prompt_router
+                    .map
+                    .contains_key(request.params.name.as_str())
+                {
                     let prompt_context = crate::handler::server::prompt::PromptContext::new(
                         self.service.as_ref(),
                         request.params.name,
@@ -136,12 +289,80 @@ where
                         .await
                 }
             }
-            ClientRequest::ListPromptsRequest(_) => {
-                let prompts = self.prompt_router.list_all();
-                Ok(ServerResult::ListPromptsResult(ListPromptsResult {
-                    prompts,
-                    ..Default::default()
-                }))
+            ClientRequest::ListPromptsRequest(request) => {
+                let cursor = request.params.and_then(|params| params.cursor);
+                let result = self.prompt_router.list_page(cursor.as_deref())?;
+                Ok(ServerResult::ListPromptsResult(result))
+            }
+            ClientRequest::ReadResourceRequest(request) => {
+                if self.resource_router.can_read(&request.params.uri) {
+                    let resource_context = crate::handler::server::resource::ResourceContext::new(
+                        self.service.as_ref(),
+                        request.params.uri,
+                        context,
+                    );
+                    let result = self.resource_router.read_resource(resource_context).await?;
+                    Ok(ServerResult::ReadResourceResult(result))
+                } else {
+                    self.service
+                        .handle_request(ClientRequest::ReadResourceRequest(request), context)
+                        .await
+                }
+            }
+            ClientRequest::CompleteRequest(request) => {
+                self.resource_router.validate_completion(&request.params)?;
+                if self
+                    .completion_router
+                    .has_route(&CompletionTarget::from_request(&request.params))
+                {
+                    let complete_context = crate::handler::server::completion::CompleteContext::new(
+                        self.service.as_ref(),
+                        request.params,
+                        context,
+                    );
+                    let result = self.completion_router.complete(complete_context).await?;
+                    Ok(ServerResult::CompleteResult(result))
+                } else {
+                    self.service
+                        .handle_request(ClientRequest::CompleteRequest(request), context)
+                        .await
+                }
+            }
+            ClientRequest::SubscribeRequest(request) => match &self.subscriptions {
+                Some(subscriptions) => {
+                    subscriptions.subscribe(request.params.uri, &context.peer);
+                    Ok(ServerResult::empty(()))
+                }
+                None => {
+                    self.service
+                        .handle_request(ClientRequest::SubscribeRequest(request), context)
+                        .await
+                }
+            },
+            ClientRequest::UnsubscribeRequest(request) => match &self.subscriptions {
+                Some(subscriptions) => {
+                    subscriptions.unsubscribe(&request.params.uri, &context.peer);
+                    Ok(ServerResult::empty(()))
+                }
+                None => {
+                    self.service
+                        .handle_request(ClientRequest::UnsubscribeRequest(request), context)
+                        .await
+                }
+            },
+            ClientRequest::ListResourcesRequest(request) if !self.resource_router.is_empty() => {
+                let cursor = request.params.and_then(|params| params.cursor);
+                let result = self.resource_router.list_page(cursor.as_deref())?;
+                Ok(ServerResult::ListResourcesResult(result))
+            }
+            ClientRequest::ListResourceTemplatesRequest(request)
+                if !self.resource_router.is_empty() =>
+            {
+                let cursor = request.params.and_then(|params| params.cursor);
+                let result = self
+                    .resource_router
+                    .list_templates_page(cursor.as_deref())?;
+                Ok(ServerResult::ListResourceTemplatesResult(result))
             }
             rest => self.service.handle_request(rest, context).await,
         }
@@ -149,11 +370,45 @@ where
 
     fn get_info(&self) -> <RoleServer as crate::service::ServiceRole>::Info {
         let mut info = ServerHandler::get_info(&self.service);
-        info.capabilities
+        self.extend_capabilities(&mut info.capabilities);
+        info
+    }
+
+    fn panic_policy(&self) -> crate::service::PanicPolicy {
+        ServerHandler::panic_policy(&self.service)
+    }
+}
+
+impl<S> Router<S>
+where
+    S: ServerHandler,
+{
+    /// Advertise the capabilities backed by the registered routes.
+    fn extend_capabilities(&self, capabilities: &mut ServerCapabilities) {
+        capabilities
             .tools
             .get_or_insert_with(Default::default)
             .list_changed = Some(true);
-        info
+        if !self.prompt_router.is_empty() {
+            capabilities
+                .prompts
+                .get_or_insert_with(Default::default)
+                .list_changed = Some(true);
+        }
+        if !self.resource_router.is_empty() {
+            capabilities.resources.get_or_insert_with(Default::default);
+        }
+        if self.subscriptions.is_some() {
+            capabilities
+                .resources
+                .get_or_insert_with(Default::default)
+                .subscribe = Some(true);
+        }
+        if !self.completion_router.is_empty() {
+            capabilities
+                .completions
+                .get_or_insert_with(Default::default);
+        }
     }
 }
 
@@ -163,7 +418,10 @@ mod tests {
 
     use super::*;
     use crate::{
-        model::{CallToolResult, ClientNotification, ServerNotification, Tool},
+        model::{
+            CallToolResult, ClientNotification, GetPromptRequestParams, GetPromptResult, Prompt,
+            ServerNotification, Tool,
+        },
         service::{AtomicU32RequestIdProvider, Peer, PeerSinkMessage, RequestIdProvider},
     };
 
@@ -225,4 +483,55 @@ mod tests {
             ServerNotification::ToolListChangedNotification(_)
         ));
     }
+
+    #[tokio::test]
+    async fn test_router_prompt_list_changed_e2e() {
+        let mut router = Router::new(DummyHandler).with_prompt(prompt::PromptRoute::new_dyn(
+            Prompt::new("my_prompt", None::<String>, None),
+            |_ctx| Box::pin(async { Ok(GetPromptResult::new(vec![])) }),
+        ));
+
+        let id_provider: Arc<dyn RequestIdProvider> =
+            Arc::new(AtomicU32RequestIdProvider::default());
+        let (peer, mut rx) = Peer::<RoleServer>::new(id_provider, None);
+
+        let context = crate::service::NotificationContext {
+            peer: peer.clone(),
+            meta: Default::default(),
+            extensions: Default::default(),
+        };
+        router
+            .handle_notification(
+                ClientNotification::InitializedNotification(Default::default()),
+                context,
+            )
+            .await
+            .unwrap();
+
+        assert!(router.prompt_router.disable_route("my_prompt"));
+        assert!(matches!(
+            recv_notification(&mut rx).await,
+            ServerNotification::PromptListChangedNotification(_)
+        ));
+        assert!(router.prompt_router.list_all().is_empty());
+
+        let request = ClientRequest::GetPromptRequest(crate::model::GetPromptRequest::new(
+            GetPromptRequestParams::new("my_prompt"),
+        ));
+        let err = router
+            .handle_request(
+                request,
+                crate::service::RequestContext::new(crate::model::NumberOrString::Number(1), peer),
+            )
+            .await
+            .expect_err("disabled prompt should reject");
+        assert_eq!(err.code, crate::model::ErrorCode::INVALID_PARAMS);
+
+        assert!(router.prompt_router.enable_route("my_prompt"));
+        assert!(matches!(
+            recv_notification(&mut rx).await,
+            ServerNotification::PromptListChangedNotification(_)
+        ));
+        assert_eq!(router.prompt_router.list_all().len(), 1);
+    }
 }
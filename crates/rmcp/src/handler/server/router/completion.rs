@@ -0,0 +1,278 @@
+use std::{borrow::Cow, sync::Arc};
+
+use crate::{
+    handler::server::completion::{CompleteContext, CompleteHandler, DynCompleteHandler},
+    model::{CompleteRequestParams, CompleteResult, Reference},
+    service::{MaybeBoxFuture, MaybeSend},
+};
+
+/// The prompt argument or resource template variable a completion handler serves.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum CompletionTarget {
+    Prompt {
+        name: Cow<'static, str>,
+        argument: Cow<'static, str>,
+    },
+    Resource {
+        uri_template: Cow<'static, str>,
+        argument: Cow<'static, str>,
+    },
+}
+
+impl CompletionTarget {
+    /// Target an argument of the prompt called `name`.
+    pub fn prompt(
+        name: impl Into<Cow<'static, str>>,
+        argument: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self::Prompt {
+            name: name.into(),
+            argument: argument.into(),
+        }
+    }
+
+    /// Target a variable of the resource template `uri_template`.
+    pub fn resource(
+        uri_template: impl Into<Cow<'static, str>>,
+        argument: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self::Resource {
+            uri_template: uri_template.into(),
+            argument: argument.into(),
+        }
+    }
+
+    /// The target addressed by a `completion/complete` request.
+    pub fn from_request(request: &CompleteRequestParams) -> Self {
+        Self::from_reference(&request.r#ref, request.argument.name.clone())
+    }
+
+    pub fn from_reference(reference: &Reference, argument: impl Into<Cow<'static, str>>) -> Self {
+        match reference {
+            Reference::Prompt(prompt) => Self::prompt(prompt.name.clone(), argument),
+            Reference::Resource(resource) => Self::resource(resource.uri.clone(), argument),
+        }
+    }
+
+    pub fn argument(&self) -> &str {
+        match self {
+            Self::Prompt { argument, .. } | Self::Resource { argument, .. } => argument,
+        }
+    }
+}
+
+#[non_exhaustive]
+pub struct CompletionRoute<S> {
+    #[allow(clippy::type_complexity)]
+    pub complete: Arc<DynCompleteHandler<S>>,
+    pub target: CompletionTarget,
+}
+
+impl<S> std::fmt::Debug for CompletionRoute<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompletionRoute")
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+impl<S> Clone for CompletionRoute<S> {
+    fn clone(&self) -> Self {
+        Self {
+            complete: self.complete.clone(),
+            target: self.target.clone(),
+        }
+    }
+}
+
+impl<S: MaybeSend + 'static> CompletionRoute<S> {
+    pub fn new<H, A: 'static>(target: CompletionTarget, handler: H) -> Self
+    where
+        H: CompleteHandler<S, A> + MaybeSend + Clone + 'static,
+    {
+        Self {
+            complete: Arc::new(move |context: CompleteContext<S>| {
+                let handler = handler.clone();
+                handler.handle(context)
+            }),
+            target,
+        }
+    }
+
+    pub fn new_dyn<H>(target: CompletionTarget, handler: H) -> Self
+    where
+        H: for<'a> Fn(
+                CompleteContext<'a, S>,
+            ) -> MaybeBoxFuture<'a, Result<CompleteResult, crate::ErrorData>>
+            + MaybeSend
+            + 'static,
+    {
+        Self {
+            complete: Arc::new(handler),
+            target,
+        }
+    }
+}
+
+pub trait IntoCompletionRoute<S, A> {
+    fn into_completion_route(self) -> CompletionRoute<S>;
+}
+
+impl<S, H, A> IntoCompletionRoute<S, A> for (CompletionTarget, H)
+where
+    S: MaybeSend + 'static,
+    A: 'static,
+    H: CompleteHandler<S, A> + MaybeSend + Clone + 'static,
+{
+    fn into_completion_route(self) -> CompletionRoute<S> {
+        CompletionRoute::new(self.0, self.1)
+    }
+}
+
+impl<S> IntoCompletionRoute<S, ()> for CompletionRoute<S>
+where
+    S: MaybeSend + 'static,
+{
+    fn into_completion_route(self) -> CompletionRoute<S> {
+        self
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CompletionRouter<S> {
+    #[allow(clippy::type_complexity)]
+    pub map: std::collections::HashMap<CompletionTarget, CompletionRoute<S>>,
+}
+
+impl<S> Default for CompletionRouter<S> {
+    fn default() -> Self {
+        Self {
+            map: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl<S> Clone for CompletionRouter<S> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<S> CompletionRouter<S>
+where
+    S: MaybeSend + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_route<R, A: 'static>(mut self, route: R) -> Self
+    where
+        R: IntoCompletionRoute<S, A>,
+    {
+        self.add_route(route.into_completion_route());
+        self
+    }
+
+    pub fn add_route(&mut self, item: CompletionRoute<S>) {
+        self.map.insert(item.target.clone(), item);
+    }
+
+    pub fn merge(&mut self, other: CompletionRouter<S>) {
+        for item in other.map.into_values() {
+            self.add_route(item);
+        }
+    }
+
+    pub fn remove_route(&mut self, target: &CompletionTarget) {
+        self.map.remove(target);
+    }
+
+    pub fn has_route(&self, target: &CompletionTarget) -> bool {
+        self.map.contains_key(target)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Complete an argument. Requests for a target without a registered handler
+    /// get an empty completion, as the spec allows.
+    pub async fn complete(
+        &self,
+        context: CompleteContext<'_, S>,
+    ) -> Result<CompleteResult, crate::ErrorData> {
+        let target =
+            CompletionTarget::from_reference(&context.r#ref, context.argument.name.clone());
+        match self.map.get(&target) {
+            Some(item) => (item.complete)(context).await,
+            None => Ok(CompleteResult::default()),
+        }
+    }
+
+    /// All registered targets, prompts first.
+    pub fn list_all(&self) -> Vec<CompletionTarget> {
+        let mut targets: Vec<_> = self.map.keys().cloned().collect();
+        targets.sort();
+        targets
+    }
+}
+
+impl<S> std::ops::Add<CompletionRouter<S>> for CompletionRouter<S>
+where
+    S: MaybeSend + 'static,
+{
+    type Output = Self;
+
+    fn add(mut self, other: CompletionRouter<S>) -> Self::Output {
+        self.merge(other);
+        self
+    }
+}
+
+impl<S> std::ops::AddAssign<CompletionRouter<S>> for CompletionRouter<S>
+where
+    S: MaybeSend + 'static,
+{
+    fn add_assign(&mut self, other: CompletionRouter<S>) {
+        self.merge(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_target_from_request() {
+        let request = CompleteRequestParams::new(
+            Reference::for_prompt("code_review"),
+            crate::model::ArgumentInfo {
+                name: "language".to_string(),
+                value: "ru".to_string(),
+            },
+        );
+        assert_eq!(
+            CompletionTarget::from_request(&request),
+            CompletionTarget::prompt("code_review", "language")
+        );
+
+        let request = CompleteRequestParams::new(
+            Reference::for_resource("repo://{owner}/{repo}"),
+            crate::model::ArgumentInfo {
+                name: "owner".to_string(),
+                value: String::new(),
+            },
+        );
+        let target = CompletionTarget::from_request(&request);
+        assert_eq!(
+            target,
+            CompletionTarget::resource("repo://{owner}/{repo}", "owner")
+        );
+        assert_eq!(target.argument(), "owner");
+    }
+}
@@ -1,7 +1,11 @@
 use std::{borrow::Cow, sync::Arc};
 
+use super::MergeError;
 use crate::{
-    handler::server::prompt::{DynGetPromptHandler, GetPromptHandler, PromptContext},
+    ServerHandler,
+    handler::server::prompt::{
+        DynGetPromptHandler, GetPromptHandler, PromptContext, template::PromptTemplate,
+    },
     model::{GetPromptResult, Prompt},
     service::{MaybeBoxFuture, MaybeSend},
 };
@@ -11,6 +15,7 @@ pub struct PromptRoute<S> {
     #[allow(clippy::type_complexity)]
     pub get: Arc<DynGetPromptHandler<S>>,
     pub attr: crate::model::Prompt,
+    scopes: Vec<Cow<'static, str>>,
 }
 
 impl<S> std::fmt::Debug for PromptRoute<S> {
@@ -19,6 +24,7 @@ impl<S> std::fmt::Debug for PromptRoute<S> {
             .field("name", &self.attr.name)
             .field("description", &self.attr.description)
             .field("arguments", &self.attr.arguments)
+            .field("scopes", &self.scopes)
             .finish()
     }
 }
@@ -28,6 +34,7 @@ impl<S> Clone for PromptRoute<S> {
         Self {
             get: self.get.clone(),
             attr: self.attr.clone(),
+            scopes: self.scopes.clone(),
         }
     }
 }
@@ -43,6 +50,7 @@ impl<S: MaybeSend + 'static> PromptRoute<S> {
                 handler.handle(context)
             }),
             attr: attr.into(),
+            scopes: Vec::new(),
         }
     }
 
@@ -57,12 +65,65 @@ impl<S: MaybeSend + 'static> PromptRoute<S> {
         Self {
             get: Arc::new(handler),
             attr: attr.into(),
+            scopes: Vec::new(),
         }
     }
 
+    /// A prompt rendered from `template`. Unless `attr` lists its arguments,
+    /// they are the ones of the template.
+    ///
+    /// See [`template`](crate::handler::server::prompt::template).
+    pub fn from_template(attr: impl Into<Prompt>, template: PromptTemplate) -> Self
+    where
+        S: ServerHandler,
+    {
+        let mut attr = attr.into();
+        if attr.arguments.is_none() && !template.arguments().is_empty() {
+            attr.arguments = Some(template.arguments().to_vec());
+        }
+        let description = attr.description.clone();
+        let template = Arc::new(template);
+        Self::new_dyn(attr, move |context: PromptContext<'_, S>| {
+            let template = template.clone();
+            let description = description.clone();
+            Box::pin(async move {
+                let messages = template
+                    .render(context.server, context.arguments.as_ref(), &context.context)
+                    .await?;
+                Ok(GetPromptResult {
+                    description,
+                    messages,
+                })
+            })
+        })
+    }
+
     pub fn name(&self) -> &str {
         &self.attr.name
     }
+
+    /// Require the caller to hold every one of `scopes`.
+    ///
+    /// See [`scopes`](crate::handler::server::scopes).
+    pub fn with_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn scopes(&self) -> &[Cow<'static, str>] {
+        &self.scopes
+    }
+
+    /// Check that the caller behind `extensions` may get this prompt.
+    pub fn authorize(
+        &self,
+        extensions: &crate::model::Extensions,
+    ) -> Result<(), crate::handler::server::scopes::InsufficientScope> {
+        crate::handler::server::scopes::check_scopes(&self.scopes, extensions)
+    }
 }
 
 pub trait IntoPromptRoute<S, A> {
@@ -104,17 +165,36 @@ where
     }
 }
 
-#[derive(Debug)]
 #[non_exhaustive]
 pub struct PromptRouter<S> {
     #[allow(clippy::type_complexity)]
     pub map: std::collections::HashMap<Cow<'static, str>, PromptRoute<S>>,
+
+    disabled: std::collections::HashSet<Cow<'static, str>>,
+
+    notifier: Option<Arc<dyn Fn() + Send + Sync>>,
+
+    page_size: Option<usize>,
+}
+
+impl<S> std::fmt::Debug for PromptRouter<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PromptRouter")
+            .field("map", &self.map)
+            .field("disabled", &self.disabled)
+            .field("notifier", &self.notifier.as_ref().map(|_| "..."))
+            .field("page_size", &self.page_size)
+            .finish()
+    }
 }
 
 impl<S> Default for PromptRouter<S> {
     fn default() -> Self {
         Self {
             map: std::collections::HashMap::new(),
+            disabled: std::collections::HashSet::new(),
+            notifier: None,
+            page_size: None,
         }
     }
 }
@@ -123,6 +203,9 @@ impl<S> Clone for PromptRouter<S> {
     fn clone(&self) -> Self {
         Self {
             map: self.map.clone(),
+            disabled: self.disabled.clone(),
+            notifier: self.notifier.clone(),
+            page_size: self.page_size,
         }
     }
 }
@@ -132,7 +215,11 @@ impl<S> IntoIterator for PromptRouter<S> {
     type IntoIter = std::collections::hash_map::IntoValues<Cow<'static, str>, PromptRoute<S>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.map.into_values()
+        let mut map = self.map;
+        for name in &self.disabled {
+            map.remove(name);
+        }
+        map.into_values()
     }
 }
 
@@ -141,9 +228,7 @@ where
     S: MaybeSend + 'static,
 {
     pub fn new() -> Self {
-        Self {
-            map: std::collections::HashMap::new(),
-        }
+        Self::default()
     }
 
     pub fn with_route<R, A: 'static>(mut self, route: R) -> Self
@@ -158,25 +243,230 @@ where
         self.map.insert(item.attr.name.clone().into(), item);
     }
 
+    /// Add every prompt of `other`. A prompt that shares a name with an
+    /// existing one replaces it; use [`try_merge`](Self::try_merge) to refuse
+    /// that.
     pub fn merge(&mut self, other: PromptRouter<S>) {
-        for item in other.map.into_values() {
-            self.add_route(item);
+        self.merge_renamed(other, str::to_owned);
+    }
+
+    /// Like [`merge`](Self::merge), with `prefix` put in front of every name
+    /// of `other`.
+    pub fn merge_with_prefix(&mut self, prefix: &str, other: PromptRouter<S>) {
+        self.merge_renamed(other, |name| format!("{prefix}{name}"));
+    }
+
+    /// Like [`merge`](Self::merge), with every name of `other` replaced by
+    /// `rename(name)`.
+    pub fn merge_renamed(&mut self, other: PromptRouter<S>, rename: impl FnMut(&str) -> String) {
+        let (routes, disabled) = other.into_renamed(rename);
+        self.disabled.extend(disabled);
+        for route in routes {
+            if self.map.contains_key(route.name()) {
+                tracing::warn!(
+                    prompt = route.name(),
+                    "merged prompt replaces an existing prompt"
+                );
+            }
+            self.add_route(route);
+        }
+    }
+
+    /// Add every prompt of `other`, failing without changing `self` if a name
+    /// is already taken.
+    pub fn try_merge(&mut self, other: PromptRouter<S>) -> Result<(), MergeError> {
+        self.try_merge_renamed(other, str::to_owned)
+    }
+
+    /// Like [`try_merge`](Self::try_merge), with `prefix` put in front of
+    /// every name of `other`.
+    pub fn try_merge_with_prefix(
+        &mut self,
+        prefix: &str,
+        other: PromptRouter<S>,
+    ) -> Result<(), MergeError> {
+        self.try_merge_renamed(other, |name| format!("{prefix}{name}"))
+    }
+
+    /// Like [`try_merge`](Self::try_merge), with every name of `other`
+    /// replaced by `rename(name)`.
+    pub fn try_merge_renamed(
+        &mut self,
+        other: PromptRouter<S>,
+        rename: impl FnMut(&str) -> String,
+    ) -> Result<(), MergeError> {
+        let (routes, disabled) = other.into_renamed(rename);
+        let mut names = std::collections::HashSet::new();
+        for route in &routes {
+            let name = route.name();
+            if self.map.contains_key(name) || !names.insert(name) {
+                return Err(MergeError::Collision(name.to_owned()));
+            }
+        }
+        self.disabled.extend(disabled);
+        for route in routes {
+            self.add_route(route);
         }
+        Ok(())
     }
 
+    /// The routes and disabled names of `self`, renamed.
+    fn into_renamed(
+        self,
+        mut rename: impl FnMut(&str) -> String,
+    ) -> (Vec<PromptRoute<S>>, Vec<Cow<'static, str>>) {
+        let disabled = self
+            .disabled
+            .iter()
+            .map(|name| Cow::Owned(rename(name)))
+            .collect();
+        let routes = self
+            .map
+            .into_values()
+            .map(|mut route| {
+                route.attr.name = rename(&route.attr.name);
+                route
+            })
+            .collect();
+        (routes, disabled)
+    }
+
+    /// Remove a prompt route from the router.
+    ///
+    /// The disabled state is **preserved**, see [`ToolRouter::remove_route`].
+    ///
+    /// [`ToolRouter::remove_route`]: super::tool::ToolRouter::remove_route
     pub fn remove_route(&mut self, name: &str) {
         self.map.remove(name);
     }
 
+    /// Returns `true` if the prompt is registered **and** not currently
+    /// disabled.
     pub fn has_route(&self, name: &str) -> bool {
-        self.map.contains_key(name)
+        self.map.contains_key(name) && !self.disabled.contains(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Disable a prompt by name. Hidden from `list_all`, rejected by
+    /// `get_prompt`. Re-enable with [`enable_route`](Self::enable_route).
+    ///
+    /// Returns `true` if the name was newly added to the disabled set.
+    /// The name is recorded even if no matching route exists yet, so routes
+    /// added later will inherit the disabled state.
+    pub fn disable_route(&mut self, name: impl Into<Cow<'static, str>>) -> bool {
+        let name = name.into();
+        let was_visible = self.map.contains_key(&name) && !self.disabled.contains(&name);
+        if was_visible {
+            self.notify_if_visible(&name);
+        }
+        self.disabled.insert(name)
+    }
+
+    /// Re-enable a previously disabled prompt. Returns `true` if the name
+    /// was in the disabled set.
+    pub fn enable_route(&mut self, name: &str) -> bool {
+        let removed = self.disabled.remove(name);
+        if removed {
+            self.notify_if_visible(name);
+        }
+        removed
+    }
+
+    /// Returns `true` if the prompt exists in the router **and** is currently
+    /// disabled.
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.map.contains_key(name) && self.disabled.contains(name)
+    }
+
+    /// Builder-style variant of [`disable_route`](Self::disable_route).
+    pub fn with_disabled(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.disabled.insert(name.into());
+        self
+    }
+
+    /// List at most `page_size` prompts per page.
+    ///
+    /// See [`pagination`](crate::handler::server::pagination).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.set_page_size(Some(page_size));
+        self
+    }
+
+    /// Set or clear the page size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is `Some(0)`.
+    pub fn set_page_size(&mut self, page_size: Option<usize>) {
+        assert!(page_size != Some(0), "page size must be at least 1");
+        self.page_size = page_size;
+    }
+
+    pub fn page_size(&self) -> Option<usize> {
+        self.page_size
+    }
+
+    /// Install a callback invoked when the visible prompt list changes.
+    pub fn set_notifier(&mut self, f: impl Fn() + Send + Sync + 'static) {
+        self.notifier = Some(Arc::new(f));
+    }
+
+    pub fn clear_notifier(&mut self) {
+        self.notifier = None;
+    }
+
+    /// Install a notifier that sends `notifications/prompts/list_changed`
+    /// via the given peer.
+    pub fn bind_peer_notifier(&mut self, peer: &crate::service::Peer<crate::RoleServer>) {
+        let peer = peer.clone();
+        self.set_notifier(move || {
+            let peer = peer.clone();
+            tokio::spawn(async move {
+                if let Err(e) = peer.notify_prompt_list_changed().await {
+                    tracing::warn!("failed to send prompts/list_changed notification: {e}");
+                }
+            });
+        });
+    }
+
+    /// Deferred notifier sharing `peer_slot`: no-op until the slot is filled.
+    pub(crate) fn deferred_peer_notifier(
+        peer_slot: Arc<std::sync::OnceLock<crate::service::Peer<crate::RoleServer>>>,
+    ) -> impl Fn() + Send + Sync + 'static {
+        move || {
+            if let Some(peer) = peer_slot.get() {
+                let peer = peer.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = peer.notify_prompt_list_changed().await {
+                        tracing::warn!("failed to send prompts/list_changed notification: {e}");
+                    }
+                });
+            }
+        }
+    }
+
+    fn notify_if_visible(&self, name: &str) {
+        if self.map.contains_key(name) {
+            if let Some(notifier) = &self.notifier {
+                notifier();
+            }
+        }
     }
 
     pub async fn get_prompt(
         &self,
         context: PromptContext<'_, S>,
     ) -> Result<GetPromptResult, crate::ErrorData> {
-        let item = self.map.get(context.name.as_str()).ok_or_else(|| {
+        let name = context.name.as_str();
+        let item = self.map.get(name).filter(|_| !self.disabled.contains(name));
+        let item = item.ok_or_else(|| {
             crate::ErrorData::invalid_params(
                 format!("prompt '{}' not found", context.name),
                 Some(serde_json::json!({
@@ -184,14 +474,38 @@ where
                 })),
             )
         })?;
+        item.authorize(&context.context.extensions)?;
         (item.get)(context).await
     }
 
     pub fn list_all(&self) -> Vec<crate::model::Prompt> {
-        let mut prompts: Vec<_> = self.map.values().map(|item| item.attr.clone()).collect();
+        let mut prompts: Vec<_> = self
+            .map
+            .values()
+            .filter(|item| !self.disabled.contains(item.attr.name.as_str()))
+            .map(|item| item.attr.clone())
+            .collect();
         prompts.sort_by(|a, b| a.name.cmp(&b.name));
         prompts
     }
+
+    /// The page of [`list_all`](Self::list_all) that follows `cursor`.
+    pub fn list_page(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<crate::model::ListPromptsResult, crate::ErrorData> {
+        let (prompts, next_cursor) = crate::handler::server::pagination::paginate(
+            self.list_all(),
+            |prompt| &prompt.name,
+            self.page_size,
+            cursor,
+        )?;
+        Ok(crate::model::ListPromptsResult {
+            meta: None,
+            next_cursor,
+            prompts,
+        })
+    }
 }
 
 impl<S> std::ops::Add<PromptRouter<S>> for PromptRouter<S>
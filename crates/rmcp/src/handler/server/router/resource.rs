@@ -0,0 +1,572 @@
+use std::{borrow::Cow, sync::Arc};
+
+use crate::{
+    handler::server::resource::{
+        DynReadResourceHandler, ReadResourceHandler, ResourceContext, TemplateVariables,
+    },
+    model::{
+        CompleteRequestParams, ReadResourceResult, Reference, Resource, ResourceTemplate,
+        UriTemplate,
+    },
+    service::{MaybeBoxFuture, MaybeSend},
+};
+
+#[non_exhaustive]
+pub struct ResourceRoute<S> {
+    #[allow(clippy::type_complexity)]
+    pub read: Arc<DynReadResourceHandler<S>>,
+    pub attr: Resource,
+    scopes: Vec<Cow<'static, str>>,
+}
+
+impl<S> std::fmt::Debug for ResourceRoute<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceRoute")
+            .field("uri", &self.attr.uri)
+            .field("name", &self.attr.name)
+            .field("mime_type", &self.attr.mime_type)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+impl<S> Clone for ResourceRoute<S> {
+    fn clone(&self) -> Self {
+        Self {
+            read: self.read.clone(),
+            attr: self.attr.clone(),
+            scopes: self.scopes.clone(),
+        }
+    }
+}
+
+impl<S: MaybeSend + 'static> ResourceRoute<S> {
+    pub fn new<H, A: 'static>(attr: impl Into<Resource>, handler: H) -> Self
+    where
+        H: ReadResourceHandler<S, A> + MaybeSend + Clone + 'static,
+    {
+        Self {
+            read: Arc::new(move |context: ResourceContext<S>| {
+                let handler = handler.clone();
+                handler.handle(context)
+            }),
+            attr: attr.into(),
+            scopes: Vec::new(),
+        }
+    }
+
+    pub fn new_dyn<H>(attr: impl Into<Resource>, handler: H) -> Self
+    where
+        H: for<'a> Fn(
+                ResourceContext<'a, S>,
+            )
+                -> MaybeBoxFuture<'a, Result<ReadResourceResult, crate::ErrorData>>
+            + MaybeSend
+            + 'static,
+    {
+        Self {
+            read: Arc::new(handler),
+            attr: attr.into(),
+            scopes: Vec::new(),
+        }
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.attr.uri
+    }
+
+    /// Require the caller to hold every one of `scopes`.
+    ///
+    /// See [`scopes`](crate::handler::server::scopes).
+    pub fn with_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn scopes(&self) -> &[Cow<'static, str>] {
+        &self.scopes
+    }
+
+    /// Check that the caller behind `extensions` may read this resource.
+    pub fn authorize(
+        &self,
+        extensions: &crate::model::Extensions,
+    ) -> Result<(), crate::handler::server::scopes::InsufficientScope> {
+        crate::handler::server::scopes::check_scopes(&self.scopes, extensions)
+    }
+}
+
+#[non_exhaustive]
+pub struct ResourceTemplateRoute<S> {
+    #[allow(clippy::type_complexity)]
+    pub read: Arc<DynReadResourceHandler<S>>,
+    pub attr: ResourceTemplate,
+    template: Arc<UriTemplate>,
+    scopes: Vec<Cow<'static, str>>,
+}
+
+impl<S> std::fmt::Debug for ResourceTemplateRoute<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceTemplateRoute")
+            .field("uri_template", &self.attr.uri_template)
+            .field("name", &self.attr.name)
+            .field("mime_type", &self.attr.mime_type)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+impl<S> Clone for ResourceTemplateRoute<S> {
+    fn clone(&self) -> Self {
+        Self {
+            read: self.read.clone(),
+            attr: self.attr.clone(),
+            template: self.template.clone(),
+            scopes: self.scopes.clone(),
+        }
+    }
+}
+
+/// Parse the URI template of a route once, when it is built.
+fn parse_template(attr: &ResourceTemplate) -> Arc<UriTemplate> {
+    match UriTemplate::parse(&attr.uri_template) {
+        Ok(template) => Arc::new(template),
+        Err(error) => panic!(
+            "resource template '{}' is not a valid RFC 6570 template: {error}",
+            attr.uri_template
+        ),
+    }
+}
+
+impl<S: MaybeSend + 'static> ResourceTemplateRoute<S> {
+    /// # Panics
+    ///
+    /// Panics if the `uri_template` of `attr` is not a valid RFC 6570 template.
+    pub fn new<H, A: 'static>(attr: impl Into<ResourceTemplate>, handler: H) -> Self
+    where
+        H: ReadResourceHandler<S, A> + MaybeSend + Clone + 'static,
+    {
+        let attr = attr.into();
+        Self {
+            read: Arc::new(move |context: ResourceContext<S>| {
+                let handler = handler.clone();
+                handler.handle(context)
+            }),
+            template: parse_template(&attr),
+            attr,
+            scopes: Vec::new(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the `uri_template` of `attr` is not a valid RFC 6570 template.
+    pub fn new_dyn<H>(attr: impl Into<ResourceTemplate>, handler: H) -> Self
+    where
+        H: for<'a> Fn(
+                ResourceContext<'a, S>,
+            )
+                -> MaybeBoxFuture<'a, Result<ReadResourceResult, crate::ErrorData>>
+            + MaybeSend
+            + 'static,
+    {
+        let attr = attr.into();
+        Self {
+            read: Arc::new(handler),
+            template: parse_template(&attr),
+            attr,
+            scopes: Vec::new(),
+        }
+    }
+
+    pub fn uri_template(&self) -> &str {
+        &self.attr.uri_template
+    }
+
+    /// The URI template, parsed when the route was built.
+    pub fn template(&self) -> &UriTemplate {
+        &self.template
+    }
+
+    /// Require the caller to hold every one of `scopes`.
+    ///
+    /// See [`scopes`](crate::handler::server::scopes).
+    pub fn with_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn scopes(&self) -> &[Cow<'static, str>] {
+        &self.scopes
+    }
+
+    /// Check that the caller behind `extensions` may read resources of this template.
+    pub fn authorize(
+        &self,
+        extensions: &crate::model::Extensions,
+    ) -> Result<(), crate::handler::server::scopes::InsufficientScope> {
+        crate::handler::server::scopes::check_scopes(&self.scopes, extensions)
+    }
+
+    /// Match a concrete URI against this RFC 6570 template, returning the extracted
+    /// variables.
+    pub fn match_uri(&self, uri: &str) -> Option<TemplateVariables> {
+        self.template.match_uri(uri).map(TemplateVariables::new)
+    }
+}
+
+pub trait IntoResourceRoute<S, A> {
+    fn into_resource_route(self) -> ResourceRoute<S>;
+}
+
+impl<S, H, A, R> IntoResourceRoute<S, A> for (R, H)
+where
+    S: MaybeSend + 'static,
+    A: 'static,
+    H: ReadResourceHandler<S, A> + MaybeSend + Clone + 'static,
+    R: Into<Resource>,
+{
+    fn into_resource_route(self) -> ResourceRoute<S> {
+        ResourceRoute::new(self.0.into(), self.1)
+    }
+}
+
+impl<S> IntoResourceRoute<S, ()> for ResourceRoute<S>
+where
+    S: MaybeSend + 'static,
+{
+    fn into_resource_route(self) -> ResourceRoute<S> {
+        self
+    }
+}
+
+pub trait IntoResourceTemplateRoute<S, A> {
+    fn into_resource_template_route(self) -> ResourceTemplateRoute<S>;
+}
+
+impl<S, H, A, T> IntoResourceTemplateRoute<S, A> for (T, H)
+where
+    S: MaybeSend + 'static,
+    A: 'static,
+    H: ReadResourceHandler<S, A> + MaybeSend + Clone + 'static,
+    T: Into<ResourceTemplate>,
+{
+    fn into_resource_template_route(self) -> ResourceTemplateRoute<S> {
+        ResourceTemplateRoute::new(self.0.into(), self.1)
+    }
+}
+
+impl<S> IntoResourceTemplateRoute<S, ()> for ResourceTemplateRoute<S>
+where
+    S: MaybeSend + 'static,
+{
+    fn into_resource_template_route(self) -> ResourceTemplateRoute<S> {
+        self
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ResourceRouter<S> {
+    #[allow(clippy::type_complexity)]
+    pub map: std::collections::HashMap<String, ResourceRoute<S>>,
+    /// Resource templates, matched in insertion order.
+    pub templates: Vec<ResourceTemplateRoute<S>>,
+    page_size: Option<usize>,
+}
+
+impl<S> Default for ResourceRouter<S> {
+    fn default() -> Self {
+        Self {
+            map: std::collections::HashMap::new(),
+            templates: Vec::new(),
+            page_size: None,
+        }
+    }
+}
+
+impl<S> Clone for ResourceRouter<S> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            templates: self.templates.clone(),
+            page_size: self.page_size,
+        }
+    }
+}
+
+impl<S> ResourceRouter<S>
+where
+    S: MaybeSend + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_route<R, A: 'static>(mut self, route: R) -> Self
+    where
+        R: IntoResourceRoute<S, A>,
+    {
+        self.add_route(route.into_resource_route());
+        self
+    }
+
+    pub fn with_template<R, A: 'static>(mut self, route: R) -> Self
+    where
+        R: IntoResourceTemplateRoute<S, A>,
+    {
+        self.add_template(route.into_resource_template_route());
+        self
+    }
+
+    /// List at most `page_size` resources or resource templates per page.
+    ///
+    /// See [`pagination`](crate::handler::server::pagination).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.set_page_size(Some(page_size));
+        self
+    }
+
+    /// Set or clear the page size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is `Some(0)`.
+    pub fn set_page_size(&mut self, page_size: Option<usize>) {
+        assert!(page_size != Some(0), "page size must be at least 1");
+        self.page_size = page_size;
+    }
+
+    pub fn page_size(&self) -> Option<usize> {
+        self.page_size
+    }
+
+    pub fn add_route(&mut self, item: ResourceRoute<S>) {
+        self.map.insert(item.attr.uri.clone(), item);
+    }
+
+    /// Add a resource template. A template with the same `uri_template` replaces
+    /// the existing one in place.
+    pub fn add_template(&mut self, item: ResourceTemplateRoute<S>) {
+        if let Some(existing) = self
+            .templates
+            .iter_mut()
+            .find(|t| t.attr.uri_template == item.attr.uri_template)
+        {
+            *existing = item;
+        } else {
+            self.templates.push(item);
+        }
+    }
+
+    pub fn merge(&mut self, other: ResourceRouter<S>) {
+        for item in other.map.into_values() {
+            self.add_route(item);
+        }
+        for item in other.templates {
+            self.add_template(item);
+        }
+    }
+
+    pub fn remove_route(&mut self, uri: &str) {
+        self.map.remove(uri);
+    }
+
+    pub fn remove_template(&mut self, uri_template: &str) {
+        self.templates
+            .retain(|t| t.attr.uri_template != uri_template);
+    }
+
+    pub fn has_route(&self, uri: &str) -> bool {
+        self.map.contains_key(uri)
+    }
+
+    /// Returns `true` if no resources or resource templates are registered.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty() && self.templates.is_empty()
+    }
+
+    pub fn has_template(&self, uri_template: &str) -> bool {
+        self.templates
+            .iter()
+            .any(|t| t.attr.uri_template == uri_template)
+    }
+
+    /// Returns `true` if `uri` is served by a static resource or matches one of
+    /// the registered templates.
+    pub fn can_read(&self, uri: &str) -> bool {
+        self.map.contains_key(uri) || self.templates.iter().any(|t| t.match_uri(uri).is_some())
+    }
+
+    /// Read a resource. Static resources take precedence over templates.
+    pub async fn read_resource(
+        &self,
+        context: ResourceContext<'_, S>,
+    ) -> Result<ReadResourceResult, crate::ErrorData> {
+        if let Some(item) = self.map.get(context.uri.as_str()) {
+            item.authorize(&context.context.extensions)?;
+            return (item.read)(context).await;
+        }
+        for template in &self.templates {
+            if let Some(variables) = template.match_uri(&context.uri) {
+                template.authorize(&context.context.extensions)?;
+                return (template.read)(context.with_variables(variables)).await;
+            }
+        }
+        Err(crate::ErrorData::resource_not_found(
+            format!("resource '{}' not found", context.uri),
+            Some(serde_json::json!({ "uri": context.uri })),
+        ))
+    }
+
+    /// Validate a completion request that references one of the registered resource
+    /// templates: the completed argument, and any argument already resolved in the
+    /// request context, must be variables of that template. Requests for other
+    /// references pass through unchanged.
+    pub fn validate_completion(
+        &self,
+        params: &CompleteRequestParams,
+    ) -> Result<(), crate::ErrorData> {
+        let Reference::Resource(reference) = &params.r#ref else {
+            return Ok(());
+        };
+        let Some(route) = self
+            .templates
+            .iter()
+            .find(|t| t.attr.uri_template == reference.uri)
+        else {
+            return Ok(());
+        };
+        route
+            .template()
+            .validate_completion_argument(&params.argument.name, params.context.as_ref())
+    }
+
+    pub fn list_all(&self) -> Vec<Resource> {
+        let mut resources: Vec<_> = self.map.values().map(|item| item.attr.clone()).collect();
+        resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+        resources
+    }
+
+    pub fn list_all_templates(&self) -> Vec<ResourceTemplate> {
+        self.templates
+            .iter()
+            .map(|item| item.attr.clone())
+            .collect()
+    }
+
+    /// The page of [`list_all`](Self::list_all) that follows `cursor`.
+    pub fn list_page(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<crate::model::ListResourcesResult, crate::ErrorData> {
+        let (resources, next_cursor) = crate::handler::server::pagination::paginate(
+            self.list_all(),
+            |resource| &resource.uri,
+            self.page_size,
+            cursor,
+        )?;
+        Ok(crate::model::ListResourcesResult {
+            meta: None,
+            next_cursor,
+            resources,
+        })
+    }
+
+    /// The page of [`list_all_templates`](Self::list_all_templates) that
+    /// follows `cursor`. Once a page size is set, templates are listed sorted
+    /// by URI template rather than in matching order.
+    pub fn list_templates_page(
+        &self,
+        cursor: Option<&str>,
+    ) -> Result<crate::model::ListResourceTemplatesResult, crate::ErrorData> {
+        let mut templates = self.list_all_templates();
+        if self.page_size.is_none() && cursor.is_none() {
+            return Ok(crate::model::ListResourceTemplatesResult::with_all_items(
+                templates,
+            ));
+        }
+        templates.sort_by(|a, b| a.uri_template.cmp(&b.uri_template));
+        let (resource_templates, next_cursor) = crate::handler::server::pagination::paginate(
+            templates,
+            |template| &template.uri_template,
+            self.page_size,
+            cursor,
+        )?;
+        Ok(crate::model::ListResourceTemplatesResult {
+            meta: None,
+            next_cursor,
+            resource_templates,
+        })
+    }
+}
+
+impl<S> std::ops::Add<ResourceRouter<S>> for ResourceRouter<S>
+where
+    S: MaybeSend + 'static,
+{
+    type Output = Self;
+
+    fn add(mut self, other: ResourceRouter<S>) -> Self::Output {
+        self.merge(other);
+        self
+    }
+}
+
+impl<S> std::ops::AddAssign<ResourceRouter<S>> for ResourceRouter<S>
+where
+    S: MaybeSend + 'static,
+{
+    fn add_assign(&mut self, other: ResourceRouter<S>) {
+        self.merge(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AnnotateAble, RawResourceTemplate};
+
+    fn template_route(uri_template: &str) -> ResourceTemplateRoute<()> {
+        ResourceTemplateRoute::new_dyn(
+            RawResourceTemplate::new(uri_template, "test").no_annotation(),
+            |_context: ResourceContext<'_, ()>| {
+                Box::pin(async { Ok(ReadResourceResult::new(vec![])) })
+            },
+        )
+    }
+
+    #[test]
+    fn test_template_route_match_uri() {
+        let route = template_route("file:///{dir}/{name}.txt");
+        let vars = route
+            .match_uri("file:///docs/readme.txt")
+            .expect("should match");
+        assert_eq!(vars.get("dir"), Some("docs"));
+        assert_eq!(vars.get("name"), Some("readme"));
+        assert!(route.match_uri("file:///a/b/c.txt").is_none());
+
+        let route = template_route("file:///{+path}{?rev}");
+        let vars = route
+            .match_uri("file:///src/lib.rs?rev=3")
+            .expect("should match");
+        assert_eq!(vars.get("path"), Some("src/lib.rs"));
+        assert_eq!(vars.get("rev"), Some("3"));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a valid RFC 6570 template")]
+    fn test_invalid_template_route_panics() {
+        template_route("file:///{path");
+    }
+}
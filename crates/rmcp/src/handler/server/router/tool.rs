@@ -126,17 +126,18 @@
 
 mod tool_traits;
 
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, sync::Arc, time::Duration};
 
 use schemars::JsonSchema;
 pub use tool_traits::{AsyncTool, SyncTool, ToolBase};
 
+use super::MergeError;
 use crate::{
     handler::server::{
         tool::{CallToolHandler, DynCallToolHandler, ToolCallContext, schema_for_type},
         tool_name_validation::validate_and_warn_tool_name,
     },
-    model::{CallToolResult, Tool, ToolAnnotations},
+    model::{CallToolResult, Content, Tool, ToolAnnotations},
     service::{MaybeBoxFuture, MaybeSend},
 };
 
@@ -145,6 +146,92 @@ pub struct ToolRoute<S> {
     #[allow(clippy::type_complexity)]
     pub call: Arc<DynCallToolHandler<S>>,
     pub attr: crate::model::Tool,
+    #[cfg(feature = "schema-validation")]
+    input_validator: crate::schema_validation::LazyValidator,
+    #[cfg(feature = "schema-validation")]
+    output_validator: crate::schema_validation::LazyValidator,
+    timeout: Option<Duration>,
+    concurrency: Option<ConcurrencyLimit>,
+    busy_policy: BusyPolicy,
+    scopes: Vec<Cow<'static, str>>,
+}
+
+/// What a tool with a [`max_concurrency`](ToolRoute::with_max_concurrency)
+/// limit does with calls that arrive while every slot is taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BusyPolicy {
+    /// Wait for a slot to free up.
+    #[default]
+    Queue,
+    /// Fail the call immediately with a tool error.
+    Reject,
+}
+
+/// A fixed number of slots for running tool calls.
+///
+/// Every route holding a clone of the same limit draws from the same slots.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimit {
+    max: usize,
+    semaphore: Arc<tokio::sync::Semaphore>,
+}
+
+impl ConcurrencyLimit {
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn new(max: usize) -> Self {
+        assert!(max > 0, "max_concurrency must be at least 1");
+        Self {
+            max,
+            semaphore: Arc::new(tokio::sync::Semaphore::new(max)),
+        }
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Number of slots not currently in use.
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+/// The [`ConcurrencyLimit`]s of one tool, one for each server type.
+///
+/// A static in a generic function is shared by every instantiation, so
+/// `#[tool(max_concurrency = ...)]` keeps its limits in one of these, keyed
+/// by the server type, for a generic server or a `#[tool_router]` trait to
+/// get separate slots for each type.
+#[derive(Debug)]
+pub struct ConcurrencyLimits {
+    max: usize,
+    limits: std::sync::Mutex<std::collections::HashMap<std::any::TypeId, ConcurrencyLimit>>,
+}
+
+impl ConcurrencyLimits {
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn new(max: usize) -> Self {
+        assert!(max > 0, "max_concurrency must be at least 1");
+        Self {
+            max,
+            limits: Default::default(),
+        }
+    }
+
+    /// The limit of the server type `S`, shared by every router built for it.
+    pub fn for_type<S: 'static>(&self) -> ConcurrencyLimit {
+        self.limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(std::any::TypeId::of::<S>())
+            .or_insert_with(|| ConcurrencyLimit::new(self.max))
+            .clone()
+    }
 }
 
 impl<S> std::fmt::Debug for ToolRoute<S> {
@@ -153,6 +240,12 @@ impl<S> std::fmt::Debug for ToolRoute<S> {
             .field("name", &self.attr.name)
             .field("description", &self.attr.description)
             .field("input_schema", &self.attr.input_schema)
+            .field("timeout", &self.timeout)
+            .field(
+                "max_concurrency",
+                &self.concurrency.as_ref().map(ConcurrencyLimit::max),
+            )
+            .field("scopes", &self.scopes)
             .finish()
     }
 }
@@ -162,10 +255,27 @@ impl<S> Clone for ToolRoute<S> {
         Self {
             call: self.call.clone(),
             attr: self.attr.clone(),
+            #[cfg(feature = "schema-validation")]
+            input_validator: self.input_validator.clone(),
+            #[cfg(feature = "schema-validation")]
+            output_validator: self.output_validator.clone(),
+            timeout: self.timeout,
+            concurrency: self.concurrency.clone(),
+            busy_policy: self.busy_policy,
+            scopes: self.scopes.clone(),
         }
     }
 }
 
+/// The checks a [`ToolRouter`] runs around every call of its tools.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CallChecks {
+    #[cfg(feature = "schema-validation")]
+    validate_input: bool,
+    #[cfg(feature = "schema-validation")]
+    validate_output: bool,
+}
+
 impl<S: MaybeSend + 'static> ToolRoute<S> {
     pub fn new<C, A>(attr: impl Into<Tool>, call: C) -> Self
     where
@@ -177,6 +287,14 @@ impl<S: MaybeSend + 'static> ToolRoute<S> {
                 context.invoke(call)
             }),
             attr: attr.into(),
+            #[cfg(feature = "schema-validation")]
+            input_validator: Default::default(),
+            #[cfg(feature = "schema-validation")]
+            output_validator: Default::default(),
+            timeout: None,
+            concurrency: None,
+            busy_policy: BusyPolicy::Queue,
+            scopes: Vec::new(),
         }
     }
     pub fn new_dyn<C>(attr: impl Into<Tool>, call: C) -> Self
@@ -190,11 +308,182 @@ impl<S: MaybeSend + 'static> ToolRoute<S> {
         Self {
             call: Arc::new(call),
             attr: attr.into(),
+            #[cfg(feature = "schema-validation")]
+            input_validator: Default::default(),
+            #[cfg(feature = "schema-validation")]
+            output_validator: Default::default(),
+            timeout: None,
+            concurrency: None,
+            busy_policy: BusyPolicy::Queue,
+            scopes: Vec::new(),
         }
     }
     pub fn name(&self) -> &str {
         &self.attr.name
     }
+
+    /// Abort the handler and return a tool error if it runs longer than
+    /// `timeout`. Time spent waiting for a concurrency slot is not counted.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run at most `max` calls of this tool at once. Further calls wait for a
+    /// free slot unless the [`BusyPolicy`] says otherwise.
+    ///
+    /// The limit is shared by every clone of this route.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn with_max_concurrency(self, max: usize) -> Self {
+        self.with_concurrency_limit(ConcurrencyLimit::new(max))
+    }
+
+    /// Draw call slots from `limit`, which may be shared with other routes.
+    pub fn with_concurrency_limit(mut self, limit: ConcurrencyLimit) -> Self {
+        self.concurrency = Some(limit);
+        self
+    }
+
+    /// Choose what happens to calls beyond the
+    /// [`max_concurrency`](Self::with_max_concurrency) limit.
+    pub fn with_busy_policy(mut self, policy: BusyPolicy) -> Self {
+        self.busy_policy = policy;
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn max_concurrency(&self) -> Option<usize> {
+        self.concurrency.as_ref().map(ConcurrencyLimit::max)
+    }
+
+    pub fn busy_policy(&self) -> BusyPolicy {
+        self.busy_policy
+    }
+
+    /// Require the caller to hold every one of `scopes`.
+    ///
+    /// See [`scopes`](crate::handler::server::scopes).
+    pub fn with_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = impl Into<Cow<'static, str>>>,
+    ) -> Self {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn scopes(&self) -> &[Cow<'static, str>] {
+        &self.scopes
+    }
+
+    /// Check that the caller behind `extensions` may call this tool.
+    pub fn authorize(
+        &self,
+        extensions: &crate::model::Extensions,
+    ) -> Result<(), crate::handler::server::scopes::InsufficientScope> {
+        crate::handler::server::scopes::check_scopes(&self.scopes, extensions)
+    }
+
+    /// Run the handler, enforcing the route's timeout and concurrency limit.
+    pub async fn invoke(
+        &self,
+        context: ToolCallContext<'_, S>,
+    ) -> Result<CallToolResult, crate::ErrorData> {
+        let _permit = match &self.concurrency {
+            None => None,
+            Some(limit) => match self.busy_policy {
+                BusyPolicy::Queue => limit.semaphore.clone().acquire_owned().await.ok(),
+                BusyPolicy::Reject => match limit.semaphore.clone().try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => {
+                        return Ok(CallToolResult::error(vec![Content::text(format!(
+                            "tool '{}' is busy: all {} slots are in use, try again later",
+                            self.attr.name, limit.max
+                        ))]));
+                    }
+                },
+            },
+        };
+        let call = (self.call)(context);
+        match self.timeout {
+            None => call.await,
+            Some(timeout) => match tokio::time::timeout(timeout, call).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!(tool = %self.attr.name, ?timeout, "tool call timed out");
+                    Ok(CallToolResult::error(vec![Content::text(format!(
+                        "tool '{}' timed out after {timeout:?}",
+                        self.attr.name
+                    ))]))
+                }
+            },
+        }
+    }
+
+    /// Authorize the caller, then run the handler between the `checks` of
+    /// the router the route came from.
+    pub(crate) async fn call_checked(
+        &self,
+        context: ToolCallContext<'_, S>,
+        checks: CallChecks,
+    ) -> Result<CallToolResult, crate::ErrorData> {
+        self.authorize(&context.request_context.extensions)?;
+
+        #[cfg(feature = "schema-validation")]
+        if checks.validate_input {
+            self.validate_arguments(context.arguments.as_ref())?;
+        }
+
+        let result = self.invoke(context).await?;
+
+        #[cfg(feature = "schema-validation")]
+        if checks.validate_output {
+            return Ok(self.validate_output(result));
+        }
+        #[cfg(not(feature = "schema-validation"))]
+        let _ = checks;
+
+        Ok(result)
+    }
+
+    /// Check `arguments` against the tool's `input_schema`. Missing arguments
+    /// are validated as an empty object.
+    #[cfg(feature = "schema-validation")]
+    pub fn validate_arguments(
+        &self,
+        arguments: Option<&crate::model::JsonObject>,
+    ) -> Result<(), crate::ErrorData> {
+        let arguments = serde_json::Value::Object(arguments.cloned().unwrap_or_default());
+        self.input_validator
+            .validate(&self.attr.input_schema, &arguments)
+            .map_err(|violations| {
+                crate::schema_validation::invalid_arguments_error(&self.attr.name, violations)
+            })
+    }
+
+    /// Check the structured content of `result` against the tool's
+    /// `output_schema`, replacing a non-conforming result with a tool error.
+    #[cfg(feature = "schema-validation")]
+    pub fn validate_output(&self, result: CallToolResult) -> CallToolResult {
+        match self
+            .output_validator
+            .validate_output(self.attr.output_schema.as_deref(), &result)
+        {
+            Ok(()) => result,
+            Err(violations) => {
+                tracing::warn!(
+                    tool = %self.attr.name,
+                    "tool result does not match its output schema"
+                );
+                crate::schema_validation::invalid_output_result(&self.attr.name, &violations)
+            }
+        }
+    }
 }
 
 pub trait IntoToolRoute<S, A> {
@@ -308,6 +597,16 @@ pub struct ToolRouter<S> {
     disabled: std::collections::HashSet<Cow<'static, str>>,
 
     notifier: Option<Arc<dyn Fn() + Send + Sync>>,
+
+    hide_unauthorized: bool,
+
+    page_size: Option<usize>,
+
+    #[cfg(feature = "schema-validation")]
+    validate_input: bool,
+
+    #[cfg(feature = "schema-validation")]
+    validate_output: bool,
 }
 
 impl<S> std::fmt::Debug for ToolRouter<S> {
@@ -320,6 +619,8 @@ impl<S> std::fmt::Debug for ToolRouter<S> {
             )
             .field("disabled", &self.disabled)
             .field("notifier", &self.notifier.as_ref().map(|_| "..."))
+            .field("hide_unauthorized", &self.hide_unauthorized)
+            .field("page_size", &self.page_size)
             .finish()
     }
 }
@@ -331,6 +632,12 @@ impl<S> Default for ToolRouter<S> {
             transparent_when_not_found: false,
             disabled: std::collections::HashSet::new(),
             notifier: None,
+            hide_unauthorized: false,
+            page_size: None,
+            #[cfg(feature = "schema-validation")]
+            validate_input: false,
+            #[cfg(feature = "schema-validation")]
+            validate_output: false,
         }
     }
 }
@@ -342,6 +649,12 @@ impl<S> Clone for ToolRouter<S> {
             transparent_when_not_found: self.transparent_when_not_found,
             disabled: self.disabled.clone(),
             notifier: self.notifier.clone(),
+            hide_unauthorized: self.hide_unauthorized,
+            page_size: self.page_size,
+            #[cfg(feature = "schema-validation")]
+            validate_input: self.validate_input,
+            #[cfg(feature = "schema-validation")]
+            validate_output: self.validate_output,
         }
     }
 }
@@ -416,11 +729,93 @@ where
         self.map.insert(new_name.clone(), item);
     }
 
+    /// Add every tool of `other`. A tool that shares a name with an existing
+    /// one replaces it; use [`try_merge`](Self::try_merge) to refuse that.
     pub fn merge(&mut self, other: ToolRouter<S>) {
-        self.disabled.extend(other.disabled);
-        for item in other.map.into_values() {
-            self.add_route(item);
+        self.merge_renamed(other, str::to_owned);
+    }
+
+    /// Like [`merge`](Self::merge), with `prefix` put in front of every name
+    /// of `other`, e.g. `merge_with_prefix("git_", git_tools)`.
+    pub fn merge_with_prefix(&mut self, prefix: &str, other: ToolRouter<S>) {
+        self.merge_renamed(other, |name| format!("{prefix}{name}"));
+    }
+
+    /// Like [`merge`](Self::merge), with every name of `other` replaced by
+    /// `rename(name)`.
+    pub fn merge_renamed(&mut self, other: ToolRouter<S>, rename: impl FnMut(&str) -> String) {
+        let (routes, disabled) = other.into_renamed(rename);
+        self.disabled.extend(disabled);
+        for route in routes {
+            if self.map.contains_key(route.name()) {
+                tracing::warn!(tool = route.name(), "merged tool replaces an existing tool");
+            }
+            self.add_route(route);
+        }
+    }
+
+    /// Add every tool of `other`, failing without changing `self` if a name
+    /// is already taken.
+    pub fn try_merge(&mut self, other: ToolRouter<S>) -> Result<(), MergeError> {
+        self.try_merge_renamed(other, str::to_owned)
+    }
+
+    /// Like [`try_merge`](Self::try_merge), with `prefix` put in front of
+    /// every name of `other`.
+    pub fn try_merge_with_prefix(
+        &mut self,
+        prefix: &str,
+        other: ToolRouter<S>,
+    ) -> Result<(), MergeError> {
+        self.try_merge_renamed(other, |name| format!("{prefix}{name}"))
+    }
+
+    /// Like [`try_merge`](Self::try_merge), with every name of `other`
+    /// replaced by `rename(name)`. Names that fail
+    /// [tool name validation](crate::handler::server::tool_name_validation)
+    /// are refused as well.
+    pub fn try_merge_renamed(
+        &mut self,
+        other: ToolRouter<S>,
+        rename: impl FnMut(&str) -> String,
+    ) -> Result<(), MergeError> {
+        let (routes, disabled) = other.into_renamed(rename);
+        let mut names = std::collections::HashSet::new();
+        for route in &routes {
+            let name = route.name();
+            if self.map.contains_key(name) || !names.insert(name) {
+                return Err(MergeError::Collision(name.to_owned()));
+            }
+            if !validate_and_warn_tool_name(name) {
+                return Err(MergeError::InvalidName(name.to_owned()));
+            }
         }
+        self.disabled.extend(disabled);
+        for route in routes {
+            self.map.insert(route.attr.name.clone(), route);
+        }
+        Ok(())
+    }
+
+    /// The routes and disabled names of `self`, renamed.
+    fn into_renamed(
+        self,
+        mut rename: impl FnMut(&str) -> String,
+    ) -> (Vec<ToolRoute<S>>, Vec<Cow<'static, str>>) {
+        let disabled = self
+            .disabled
+            .iter()
+            .map(|name| Cow::Owned(rename(name)))
+            .collect();
+        let routes = self
+            .map
+            .into_values()
+            .map(|mut route| {
+                route.attr.name = Cow::Owned(rename(&route.attr.name));
+                route
+            })
+            .collect();
+        (routes, disabled)
     }
 
     /// Remove a tool route from the router.
@@ -482,6 +877,88 @@ where
         self
     }
 
+    /// Leave tools the caller lacks the [scopes](ToolRoute::with_scopes) for
+    /// out of [`list_for`](Self::list_for).
+    pub fn with_unauthorized_hidden(mut self) -> Self {
+        self.hide_unauthorized = true;
+        self
+    }
+
+    pub fn set_unauthorized_hidden(&mut self, hidden: bool) {
+        self.hide_unauthorized = hidden;
+    }
+
+    pub fn hides_unauthorized(&self) -> bool {
+        self.hide_unauthorized
+    }
+
+    /// List at most `page_size` tools per page.
+    ///
+    /// See [`pagination`](crate::handler::server::pagination).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.set_page_size(Some(page_size));
+        self
+    }
+
+    /// Set or clear the page size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is `Some(0)`.
+    pub fn set_page_size(&mut self, page_size: Option<usize>) {
+        assert!(page_size != Some(0), "page size must be at least 1");
+        self.page_size = page_size;
+    }
+
+    pub fn page_size(&self) -> Option<usize> {
+        self.page_size
+    }
+
+    /// Validate call arguments against each tool's `input_schema` before
+    /// dispatching, rejecting mismatches with `-32602`.
+    ///
+    /// See [`schema_validation`](crate::schema_validation).
+    #[cfg(feature = "schema-validation")]
+    pub fn with_input_validation(mut self) -> Self {
+        self.validate_input = true;
+        self
+    }
+
+    #[cfg(feature = "schema-validation")]
+    pub fn set_input_validation(&mut self, enabled: bool) {
+        self.validate_input = enabled;
+    }
+
+    #[cfg(feature = "schema-validation")]
+    pub fn validates_input(&self) -> bool {
+        self.validate_input
+    }
+
+    /// Check each successful result against the tool's `output_schema`,
+    /// replacing mismatches with a tool error result (`is_error: true`) that
+    /// lists the offending paths.
+    ///
+    /// See [`schema_validation`](crate::schema_validation).
+    #[cfg(feature = "schema-validation")]
+    pub fn with_output_validation(mut self) -> Self {
+        self.validate_output = true;
+        self
+    }
+
+    #[cfg(feature = "schema-validation")]
+    pub fn set_output_validation(&mut self, enabled: bool) {
+        self.validate_output = enabled;
+    }
+
+    #[cfg(feature = "schema-validation")]
+    pub fn validates_output(&self) -> bool {
+        self.validate_output
+    }
+
     /// Install a callback invoked when the visible tool list changes.
     pub fn set_notifier(&mut self, f: impl Fn() + Send + Sync + 'static) {
         self.notifier = Some(Arc::new(f));
@@ -534,22 +1011,31 @@ where
         }
     }
 
-    pub async fn call(
+    /// The enabled route of `name` and the checks to call it with.
+    pub(crate) fn route_for_call(
         &self,
-        context: ToolCallContext<'_, S>,
-    ) -> Result<CallToolResult, crate::ErrorData> {
-        let name = context.name();
-        if self.disabled.contains(name) {
-            return Err(crate::ErrorData::invalid_params("tool not found", None));
-        }
-        let item = self
+        name: &str,
+    ) -> Result<(&ToolRoute<S>, CallChecks), crate::ErrorData> {
+        let route = self
             .map
             .get(name)
+            .filter(|_| !self.disabled.contains(name))
             .ok_or_else(|| crate::ErrorData::invalid_params("tool not found", None))?;
+        let checks = CallChecks {
+            #[cfg(feature = "schema-validation")]
+            validate_input: self.validate_input,
+            #[cfg(feature = "schema-validation")]
+            validate_output: self.validate_output,
+        };
+        Ok((route, checks))
+    }
 
-        let result = (item.call)(context).await?;
-
-        Ok(result)
+    pub async fn call(
+        &self,
+        context: ToolCallContext<'_, S>,
+    ) -> Result<CallToolResult, crate::ErrorData> {
+        let (route, checks) = self.route_for_call(context.name())?;
+        route.call_checked(context, checks).await
     }
 
     pub fn list_all(&self) -> Vec<crate::model::Tool> {
@@ -563,6 +1049,44 @@ where
         tools
     }
 
+    /// The tools to list for the caller behind `extensions`: all of them, or
+    /// only the ones it may call if [`with_unauthorized_hidden`] is set.
+    ///
+    /// [`with_unauthorized_hidden`]: Self::with_unauthorized_hidden
+    pub fn list_for(&self, extensions: &crate::model::Extensions) -> Vec<crate::model::Tool> {
+        if !self.hide_unauthorized {
+            return self.list_all();
+        }
+        let mut tools: Vec<_> = self
+            .map
+            .values()
+            .filter(|item| !self.disabled.contains(&item.attr.name))
+            .filter(|item| item.authorize(extensions).is_ok())
+            .map(|item| item.attr.clone())
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
+    }
+
+    /// The page of [`list_for`](Self::list_for) that follows `cursor`.
+    pub fn list_page(
+        &self,
+        cursor: Option<&str>,
+        extensions: &crate::model::Extensions,
+    ) -> Result<crate::model::ListToolsResult, crate::ErrorData> {
+        let (tools, next_cursor) = crate::handler::server::pagination::paginate(
+            self.list_for(extensions),
+            |tool| &tool.name,
+            self.page_size,
+            cursor,
+        )?;
+        Ok(crate::model::ListToolsResult {
+            meta: None,
+            next_cursor,
+            tools,
+        })
+    }
+
     /// Get a tool definition by name.
     ///
     /// Returns the tool if found and enabled, or `None` if the tool does not
@@ -610,6 +1134,18 @@ mod tests {
     struct DummyService;
     impl crate::handler::server::ServerHandler for DummyService {}
 
+    #[test]
+    fn test_concurrency_limits_are_per_type() {
+        let limits = ConcurrencyLimits::new(1);
+        let _slot = limits
+            .for_type::<u8>()
+            .semaphore
+            .try_acquire_owned()
+            .unwrap();
+        assert_eq!(limits.for_type::<u8>().available(), 0);
+        assert_eq!(limits.for_type::<u16>().available(), 1);
+    }
+
     #[tokio::test]
     async fn test_call_disabled_tool_returns_error() {
         let service = DummyService;
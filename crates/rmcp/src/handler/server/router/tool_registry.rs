@@ -0,0 +1,240 @@
+//! A tool registry shared by every session of a server.
+//!
+//! A [`ToolRouter`] is usually built once per session by the service factory,
+//! so tools registered after the server started never reach the sessions that
+//! are already running. [`SharedToolRegistry`] keeps a single router behind a
+//! lock instead: every session holds a clone of the registry, and adding,
+//! removing, enabling or disabling a tool is immediately visible to all of
+//! them. Every registered peer receives `notifications/tools/list_changed`.
+//!
+//! ```rust,ignore
+//! let registry = SharedToolRegistry::<MyServer>::new();
+//! let service = StreamableHttpService::new(
+//!     {
+//!         let registry = registry.clone();
+//!         move || Ok(Router::new(MyServer::default()).with_tool_registry(registry.clone()))
+//!     },
+//!     LocalSessionManager::default().into(),
+//!     Default::default(),
+//! );
+//!
+//! // later, e.g. from a plugin loader
+//! registry.add_route(ToolRoute::new(my_plugin_tool(), handler));
+//! ```
+
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use super::tool::{ToolRoute, ToolRouter};
+use crate::{
+    RoleServer,
+    handler::server::tool::ToolCallContext,
+    model::{CallToolResult, Tool},
+    service::{MaybeSend, Peer},
+};
+
+type PeerList = Arc<Mutex<Vec<Peer<RoleServer>>>>;
+
+/// A [`ToolRouter`] shared across sessions, with `tools/list_changed` fan-out.
+///
+/// Cloning is cheap and every clone shares the same tools and peers.
+pub struct SharedToolRegistry<S> {
+    router: Arc<RwLock<ToolRouter<S>>>,
+    peers: PeerList,
+}
+
+impl<S> Clone for SharedToolRegistry<S> {
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+            peers: self.peers.clone(),
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for SharedToolRegistry<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedToolRegistry")
+            .field("router", &self.router)
+            .field("peers", &lock_peers(&self.peers).len())
+            .finish()
+    }
+}
+
+impl<S> Default for SharedToolRegistry<S>
+where
+    S: MaybeSend + 'static,
+{
+    fn default() -> Self {
+        Self::from_router(ToolRouter::new())
+    }
+}
+
+fn lock_peers(peers: &PeerList) -> std::sync::MutexGuard<'_, Vec<Peer<RoleServer>>> {
+    peers
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn notify_peers(peers: &PeerList) {
+    let mut peers = lock_peers(peers);
+    peers.retain(|peer| !peer.is_transport_closed());
+    for peer in peers.iter() {
+        let peer = peer.clone();
+        tokio::spawn(async move {
+            if let Err(e) = peer.notify_tool_list_changed().await {
+                tracing::warn!("failed to send tools/list_changed notification: {e}");
+            }
+        });
+    }
+}
+
+impl<S> SharedToolRegistry<S>
+where
+    S: MaybeSend + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share the tools of `router`. Any notifier installed on it is replaced.
+    pub fn from_router(mut router: ToolRouter<S>) -> Self {
+        let peers = PeerList::default();
+        let notifier_peers = peers.clone();
+        router.set_notifier(move || notify_peers(&notifier_peers));
+        Self {
+            router: Arc::new(RwLock::new(router)),
+            peers,
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ToolRouter<S>> {
+        self.router
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ToolRouter<S>> {
+        self.router
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Send `tools/list_changed` to `peer` whenever the visible tools change.
+    ///
+    /// Peers are dropped once their connection is closed.
+    pub fn register_peer(&self, peer: &Peer<RoleServer>) {
+        let mut peers = lock_peers(&self.peers);
+        peers.retain(|p| !p.is_transport_closed());
+        if !peers.iter().any(|p| p.is_same_peer(peer)) {
+            peers.push(peer.clone());
+        }
+    }
+
+    /// Number of connected peers that get notified on changes.
+    pub fn peer_count(&self) -> usize {
+        let mut peers = lock_peers(&self.peers);
+        peers.retain(|p| !p.is_transport_closed());
+        peers.len()
+    }
+
+    /// Add or replace a tool.
+    pub fn add_route(&self, item: ToolRoute<S>) {
+        self.add_routes([item]);
+    }
+
+    /// Add or replace several tools, notifying peers once.
+    pub fn add_routes(&self, items: impl IntoIterator<Item = ToolRoute<S>>) {
+        let mut router = self.write();
+        let mut changed = false;
+        for item in items {
+            let name = item.attr.name.clone();
+            router.add_route(item);
+            changed |= router.has_route(&name);
+        }
+        drop(router);
+        if changed {
+            notify_peers(&self.peers);
+        }
+    }
+
+    /// Add every tool of `other`, keeping its disabled markers.
+    pub fn merge(&self, other: ToolRouter<S>) {
+        self.write().merge(other);
+        notify_peers(&self.peers);
+    }
+
+    /// Remove a tool. Returns `true` if a visible tool was removed.
+    pub fn remove_route(&self, name: &str) -> bool {
+        let mut router = self.write();
+        let was_visible = router.has_route(name);
+        router.remove_route(name);
+        drop(router);
+        if was_visible {
+            notify_peers(&self.peers);
+        }
+        was_visible
+    }
+
+    /// See [`ToolRouter::disable_route`].
+    pub fn disable_route(&self, name: impl Into<Cow<'static, str>>) -> bool {
+        self.write().disable_route(name)
+    }
+
+    /// See [`ToolRouter::enable_route`].
+    pub fn enable_route(&self, name: &str) -> bool {
+        self.write().enable_route(name)
+    }
+
+    pub fn has_route(&self, name: &str) -> bool {
+        self.read().has_route(name)
+    }
+
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.read().is_disabled(name)
+    }
+
+    pub fn list_all(&self) -> Vec<Tool> {
+        self.read().list_all()
+    }
+
+    /// See [`ToolRouter::list_for`].
+    pub fn list_for(&self, extensions: &crate::model::Extensions) -> Vec<Tool> {
+        self.read().list_for(extensions)
+    }
+
+    /// See [`ToolRouter::list_page`].
+    pub fn list_page(
+        &self,
+        cursor: Option<&str>,
+        extensions: &crate::model::Extensions,
+    ) -> Result<crate::model::ListToolsResult, crate::ErrorData> {
+        self.read().list_page(cursor, extensions)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Tool> {
+        self.read().get(name).cloned()
+    }
+
+    /// A copy of the current tools, detached from the registry.
+    pub fn snapshot(&self) -> ToolRouter<S> {
+        let mut router = self.read().clone();
+        router.clear_notifier();
+        router
+    }
+
+    pub async fn call(
+        &self,
+        context: ToolCallContext<'_, S>,
+    ) -> Result<CallToolResult, crate::ErrorData> {
+        // Clone the route out so the lock is not held while the tool runs.
+        let (route, checks) = {
+            let router = self.read();
+            let (route, checks) = router.route_for_call(context.name())?;
+            (route.clone(), checks)
+        };
+        route.call_checked(context, checks).await
+    }
+}
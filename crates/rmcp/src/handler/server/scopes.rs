@@ -0,0 +1,273 @@
+//! OAuth scope requirements for tools, prompts and resources.
+//!
+//! Routes declare the scopes a caller needs with `with_scopes`, or with
+//! `scopes = [...]` on `#[tool]`, `#[prompt]`, `#[resource]` and
+//! `#[resource_template]`. The caller's scopes come from a [`GrantedScopes`]
+//! value that authentication middleware puts into the request extensions. Over
+//! Streamable HTTP it is enough to insert it into the extensions of the
+//! incoming `http::Request`; it is found inside the forwarded
+//! [`http::request::Parts`].
+//!
+//! Routers reject a caller that lacks a required scope with
+//! [`INSUFFICIENT_SCOPE`]. A request without any [`GrantedScopes`] holds no
+//! scopes, so it can only use routes that require none.
+//!
+//! Over HTTP, a [`ScopeRequirements`] table given to
+//! `StreamableHttpServerConfig::with_scope_requirements` rejects such calls
+//! before they reach the session, answering `403 Forbidden` with
+//! `WWW-Authenticate: Bearer error="insufficient_scope", scope="..."` as the
+//! MCP authorization spec recommends.
+//!
+//! ```rust,ignore
+//! #[tool_router]
+//! impl Files {
+//!     #[tool(description = "Delete a file", scopes = ["files:write"])]
+//!     async fn delete(&self, Parameters(req): Parameters<DeleteRequest>) -> String { ... }
+//! }
+//!
+//! let requirements = ScopeRequirements::new().with_tool_router(&Files::tool_router());
+//! let config = StreamableHttpServerConfig::default().with_scope_requirements(requirements);
+//!
+//! // in the auth middleware, after the token has been verified
+//! request.extensions_mut().insert(GrantedScopes::from_space_separated(&claims.scope));
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+};
+
+use super::router::{prompt::PromptRouter, resource::ResourceRouter, tool::ToolRouter};
+use crate::{
+    error::ErrorData as McpError,
+    model::{ClientRequest, ErrorCode, Extensions, GetExtensions, UriTemplate},
+    service::MaybeSend,
+};
+
+/// Error code of requests rejected for missing scopes.
+///
+/// JSON-RPC reserves `-32000` to `-32099` for implementation-defined server errors.
+pub const INSUFFICIENT_SCOPE: ErrorCode = ErrorCode(-32003);
+
+/// The scopes granted to the caller of a request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GrantedScopes(BTreeSet<String>);
+
+impl GrantedScopes {
+    pub fn new(scopes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self(scopes.into_iter().map(Into::into).collect())
+    }
+
+    /// Parse an OAuth `scope` value such as `"files:read files:write"`.
+    pub fn from_space_separated(scopes: &str) -> Self {
+        Self::new(scopes.split_whitespace())
+    }
+
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.contains(scope)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Look up the granted scopes in request extensions, either directly or
+    /// inside the forwarded [`http::request::Parts`].
+    pub fn from_extensions(extensions: &Extensions) -> Option<&Self> {
+        extensions
+            .get::<Self>()
+            .or_else(|| from_http_parts(extensions))
+    }
+}
+
+#[cfg(feature = "server-side-http")]
+fn from_http_parts(extensions: &Extensions) -> Option<&GrantedScopes> {
+    extensions
+        .get::<http::request::Parts>()?
+        .extensions
+        .get::<GrantedScopes>()
+}
+
+#[cfg(not(feature = "server-side-http"))]
+fn from_http_parts(_extensions: &Extensions) -> Option<&GrantedScopes> {
+    None
+}
+
+/// Check that the caller behind `extensions` holds every scope in `required`.
+pub fn check_scopes(
+    required: &[Cow<'static, str>],
+    extensions: &Extensions,
+) -> Result<(), InsufficientScope> {
+    if required.is_empty() {
+        return Ok(());
+    }
+    let granted = GrantedScopes::from_extensions(extensions);
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|scope| !granted.is_some_and(|granted| granted.contains(scope)))
+        .map(|scope| scope.to_string())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(InsufficientScope {
+            required: required.iter().map(|scope| scope.to_string()).collect(),
+            missing,
+        })
+    }
+}
+
+/// A request whose caller lacks scopes the target requires.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("insufficient scope, missing: {}", missing.join(" "))]
+#[non_exhaustive]
+pub struct InsufficientScope {
+    /// Every scope the target requires.
+    pub required: Vec<String>,
+    /// The required scopes the caller does not hold.
+    pub missing: Vec<String>,
+}
+
+impl InsufficientScope {
+    /// The `WWW-Authenticate` challenge telling the client which scopes to
+    /// request.
+    pub fn www_authenticate(&self) -> String {
+        format!(
+            r#"Bearer error="insufficient_scope", scope="{}""#,
+            self.required.join(" ")
+        )
+    }
+}
+
+impl From<InsufficientScope> for McpError {
+    fn from(value: InsufficientScope) -> Self {
+        McpError::new(
+            INSUFFICIENT_SCOPE,
+            value.to_string(),
+            Some(serde_json::json!({
+                "error": "insufficient_scope",
+                "requiredScopes": value.required,
+                "missingScopes": value.missing,
+            })),
+        )
+    }
+}
+
+/// The scopes required by each tool, prompt and resource of a server,
+/// for checking requests before they are dispatched.
+///
+/// The table is a snapshot: routes added to a router afterwards are still
+/// checked by the router itself, but not by the table.
+#[derive(Debug, Clone, Default)]
+pub struct ScopeRequirements {
+    tools: HashMap<String, Vec<Cow<'static, str>>>,
+    prompts: HashMap<String, Vec<Cow<'static, str>>>,
+    resources: HashMap<String, Vec<Cow<'static, str>>>,
+    templates: Vec<(UriTemplate, Vec<Cow<'static, str>>)>,
+}
+
+impl ScopeRequirements {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tool_router<S: MaybeSend + 'static>(mut self, router: &ToolRouter<S>) -> Self {
+        for route in router.map.values() {
+            if !route.scopes().is_empty() {
+                self.tools
+                    .insert(route.attr.name.to_string(), route.scopes().to_vec());
+            }
+        }
+        self
+    }
+
+    pub fn with_prompt_router<S: MaybeSend + 'static>(mut self, router: &PromptRouter<S>) -> Self {
+        for route in router.map.values() {
+            if !route.scopes().is_empty() {
+                self.prompts
+                    .insert(route.attr.name.clone(), route.scopes().to_vec());
+            }
+        }
+        self
+    }
+
+    pub fn with_resource_router<S: MaybeSend + 'static>(
+        mut self,
+        router: &ResourceRouter<S>,
+    ) -> Self {
+        for route in router.map.values() {
+            if !route.scopes().is_empty() {
+                self.resources
+                    .insert(route.attr.uri.clone(), route.scopes().to_vec());
+            }
+        }
+        // every template is kept so a URI matches the same template the
+        // router would pick
+        for route in &router.templates {
+            self.templates
+                .push((route.template().clone(), route.scopes().to_vec()));
+        }
+        self
+    }
+
+    /// The scopes `request` needs. Methods other than `tools/call`,
+    /// `prompts/get` and `resources/read` need none.
+    pub fn required_for(&self, request: &ClientRequest) -> &[Cow<'static, str>] {
+        let required = match request {
+            ClientRequest::CallToolRequest(request) => self.tools.get(request.params.name.as_ref()),
+            ClientRequest::GetPromptRequest(request) => self.prompts.get(&request.params.name),
+            ClientRequest::ReadResourceRequest(request) => {
+                let uri = &request.params.uri;
+                self.resources.get(uri).or_else(|| {
+                    self.templates
+                        .iter()
+                        .find(|(template, _)| template.match_uri(uri).is_some())
+                        .map(|(_, scopes)| scopes)
+                })
+            }
+            _ => None,
+        };
+        required.map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Check `request` against the scopes granted in its extensions.
+    pub fn check(&self, request: &ClientRequest) -> Result<(), InsufficientScope> {
+        check_scopes(self.required_for(request), request.extensions())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_scopes_reports_missing() {
+        let required = [Cow::Borrowed("files:read"), Cow::Borrowed("files:write")];
+        let mut extensions = Extensions::new();
+        let err = check_scopes(&required, &extensions).unwrap_err();
+        assert_eq!(err.missing, ["files:read", "files:write"]);
+
+        extensions.insert(GrantedScopes::from_space_separated("files:read openid"));
+        let err = check_scopes(&required, &extensions).unwrap_err();
+        assert_eq!(err.missing, ["files:write"]);
+        assert_eq!(
+            err.www_authenticate(),
+            r#"Bearer error="insufficient_scope", scope="files:read files:write""#
+        );
+
+        let error = McpError::from(err);
+        assert_eq!(error.code, INSUFFICIENT_SCOPE);
+        assert_eq!(
+            error.data,
+            Some(serde_json::json!({
+                "error": "insufficient_scope",
+                "requiredScopes": ["files:read", "files:write"],
+                "missingScopes": ["files:write"],
+            }))
+        );
+
+        extensions.insert(GrantedScopes::new(["files:read", "files:write"]));
+        assert!(check_scopes(&required, &extensions).is_ok());
+        assert!(check_scopes(&[], &Extensions::new()).is_ok());
+    }
+}
@@ -0,0 +1,176 @@
+//! Resource subscription tracking for MCP servers
+//!
+//! [`SubscriptionManager`] remembers which connected peers subscribed to which
+//! resource URIs and fans `notifications/resources/updated` out to them. A
+//! single manager can be shared by every connection of a server, e.g. all
+//! sessions of a `StreamableHttpService` or all stdio connections, by cloning
+//! it into each service instance.
+//!
+//! Peers are forgotten as soon as their connection shuts down.
+//!
+//! ```rust,ignore
+//! impl ServerHandler for MyServer {
+//!     async fn subscribe(
+//!         &self,
+//!         request: SubscribeRequestParams,
+//!         context: RequestContext<RoleServer>,
+//!     ) -> Result<(), ErrorData> {
+//!         self.subscriptions.subscribe(request.uri, &context.peer);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! // later, from anywhere
+//! subscriptions.notify_updated("file:///README.md").await;
+//! ```
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+    RoleServer,
+    model::ResourceUpdatedNotificationParam,
+    service::{Peer, ServiceError},
+};
+
+struct Subscriber {
+    peer: Peer<RoleServer>,
+    uris: HashSet<String>,
+}
+
+/// Tracks resource subscriptions across all connections of a server.
+///
+/// Cloning is cheap and every clone shares the same subscriptions.
+#[derive(Clone, Default)]
+pub struct SubscriptionManager {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl std::fmt::Debug for SubscriptionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubscriptionManager")
+            .field("peers", &self.lock().len())
+            .finish()
+    }
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Subscriber>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Subscribe `peer` to updates of `uri`.
+    ///
+    /// The first subscription of a peer starts watching its connection so the
+    /// peer is dropped from the manager once it disconnects.
+    pub fn subscribe(&self, uri: impl Into<String>, peer: &Peer<RoleServer>) {
+        if peer.is_transport_closed() {
+            return;
+        }
+        let uri = uri.into();
+        let mut subscribers = self.lock();
+        if let Some(subscriber) = subscribers.iter_mut().find(|s| s.peer.is_same_peer(peer)) {
+            subscriber.uris.insert(uri);
+            return;
+        }
+        subscribers.push(Subscriber {
+            peer: peer.clone(),
+            uris: HashSet::from([uri]),
+        });
+        drop(subscribers);
+
+        let manager = Arc::downgrade(&self.subscribers);
+        let peer = peer.clone();
+        tokio::spawn(async move {
+            peer.closed().await;
+            if let Some(subscribers) = manager.upgrade() {
+                Self { subscribers }.remove_peer(&peer);
+            }
+        });
+    }
+
+    /// Remove the subscription of `peer` to `uri`, if any.
+    pub fn unsubscribe(&self, uri: &str, peer: &Peer<RoleServer>) {
+        let mut subscribers = self.lock();
+        if let Some(subscriber) = subscribers.iter_mut().find(|s| s.peer.is_same_peer(peer)) {
+            subscriber.uris.remove(uri);
+        }
+    }
+
+    /// Forget every subscription of `peer`.
+    pub fn remove_peer(&self, peer: &Peer<RoleServer>) {
+        self.lock().retain(|s| !s.peer.is_same_peer(peer));
+    }
+
+    pub fn is_subscribed(&self, uri: &str, peer: &Peer<RoleServer>) -> bool {
+        self.lock()
+            .iter()
+            .any(|s| s.peer.is_same_peer(peer) && s.uris.contains(uri))
+    }
+
+    /// Number of connected peers subscribed to `uri`.
+    pub fn subscriber_count(&self, uri: &str) -> usize {
+        self.lock()
+            .iter()
+            .filter(|s| s.uris.contains(uri) && !s.peer.is_transport_closed())
+            .count()
+    }
+
+    /// Number of peers holding at least one subscription.
+    pub fn peer_count(&self) -> usize {
+        // a peer that unsubscribed from everything keeps its entry, and the
+        // task watching its connection, until it disconnects
+        self.lock().iter().filter(|s| !s.uris.is_empty()).count()
+    }
+
+    /// All URIs with at least one subscriber, sorted.
+    pub fn subscribed_uris(&self) -> Vec<String> {
+        let mut uris: Vec<_> = self
+            .lock()
+            .iter()
+            .flat_map(|s| s.uris.iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        uris.sort();
+        uris
+    }
+
+    /// Send `notifications/resources/updated` for `uri` to every subscribed peer.
+    ///
+    /// Returns the number of peers the notification was delivered to. Peers
+    /// whose connection turns out to be closed are dropped.
+    pub async fn notify_updated(&self, uri: &str) -> usize {
+        let peers: Vec<_> = self
+            .lock()
+            .iter()
+            .filter(|s| s.uris.contains(uri))
+            .map(|s| s.peer.clone())
+            .collect();
+        let results =
+            futures::future::join_all(peers.iter().map(|peer| {
+                peer.notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+            }))
+            .await;
+
+        let mut delivered = 0;
+        for (peer, result) in peers.iter().zip(results) {
+            match result {
+                Ok(()) => delivered += 1,
+                Err(ServiceError::TransportClosed) => self.remove_peer(peer),
+                Err(e) => {
+                    tracing::warn!("failed to send resources/updated notification for {uri}: {e}")
+                }
+            }
+        }
+        delivered
+    }
+}
@@ -24,6 +24,8 @@ pub use service::{RoleClient, serve_client};
 pub use service::{RoleServer, serve_server};
 
 pub mod handler;
+#[cfg(feature = "schema-validation")]
+pub mod schema_validation;
 #[cfg(feature = "server")]
 pub mod task_manager;
 #[cfg(any(feature = "client", feature = "server"))]
@@ -10,6 +10,7 @@ mod resource;
 mod serde_impl;
 mod task;
 mod tool;
+mod uri_template;
 pub use annotated::*;
 pub use capabilities::*;
 pub use content::*;
@@ -22,6 +23,7 @@ use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
 pub use task::*;
 pub use tool::*;
+pub use uri_template::*;
 
 /// A JSON object type alias for convenient handling of JSON data.
 ///
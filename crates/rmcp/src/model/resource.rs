@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{Annotated, Icon, Meta};
+use super::{Annotated, Icon, Meta, UriTemplate, UriTemplateError};
 
 /// Represents a resource in the extension with metadata
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -210,6 +210,11 @@ impl RawResourceTemplate {
         self.icons = Some(icons);
         self
     }
+
+    /// Parse [`uri_template`](Self::uri_template) as an RFC 6570 URI template.
+    pub fn parse_uri_template(&self) -> Result<UriTemplate, UriTemplateError> {
+        UriTemplate::parse(&self.uri_template)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,1065 @@
+//! [RFC 6570](https://www.rfc-editor.org/rfc/rfc6570) URI templates.
+//!
+//! [`UriTemplate`] supports every expression type up to level 4 of the RFC: simple
+//! and reserved expansion, fragment, label, path segment, path-style parameter,
+//! form-style query and query continuation expansion, along with the prefix (`:N`)
+//! and explode (`*`) modifiers.
+//!
+//! Templates can be expanded into concrete URIs (typically on the client side) and
+//! concrete URIs can be matched back against a template to recover the variables
+//! (typically on the server side, when serving a resource template).
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use rmcp::model::{TemplateValue, UriTemplate};
+//!
+//! let template = UriTemplate::parse("file:///{+path}{?version}").unwrap();
+//!
+//! let mut variables = HashMap::new();
+//! variables.insert("path".to_string(), TemplateValue::from("src/main.rs"));
+//! variables.insert("version".to_string(), TemplateValue::from("2"));
+//! let uri = template.expand(&variables);
+//! assert_eq!(uri, "file:///src/main.rs?version=2");
+//!
+//! let matched = template.match_uri(&uri).unwrap();
+//! assert_eq!(matched["path"], "src/main.rs");
+//! assert_eq!(matched["version"], "2");
+//! ```
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use super::{CompletionContext, ErrorData};
+
+/// Error returned when a URI template is not valid RFC 6570 syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum UriTemplateError {
+    #[error("unclosed expression starting at byte {0}")]
+    UnclosedExpression(usize),
+    #[error("unexpected '}}' at byte {0}")]
+    UnexpectedClosingBrace(usize),
+    #[error("empty expression at byte {0}")]
+    EmptyExpression(usize),
+    #[error("operator '{operator}' at byte {position} is reserved for future extensions")]
+    ReservedOperator { operator: char, position: usize },
+    #[error("invalid variable name '{name}' at byte {position}")]
+    InvalidVariableName { name: String, position: usize },
+    #[error("invalid prefix length '{prefix}' at byte {position}, expected 1..=9999")]
+    InvalidPrefix { prefix: String, position: usize },
+}
+
+/// A value that can be substituted into a URI template expression.
+///
+/// Lists and associative arrays are only meaningful for level 4 templates; an empty
+/// list or associative array is treated as undefined, as required by the RFC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[expect(clippy::exhaustive_enums, reason = "intentionally exhaustive")]
+pub enum TemplateValue {
+    String(String),
+    List(Vec<String>),
+    AssocList(Vec<(String, String)>),
+}
+
+impl TemplateValue {
+    fn is_defined(&self) -> bool {
+        match self {
+            TemplateValue::String(_) => true,
+            TemplateValue::List(items) => !items.is_empty(),
+            TemplateValue::AssocList(pairs) => !pairs.is_empty(),
+        }
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        TemplateValue::String(value)
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        TemplateValue::String(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for TemplateValue {
+    fn from(value: Vec<String>) -> Self {
+        TemplateValue::List(value)
+    }
+}
+
+impl From<Vec<&str>> for TemplateValue {
+    fn from(value: Vec<&str>) -> Self {
+        TemplateValue::List(value.into_iter().map(String::from).collect())
+    }
+}
+
+impl From<Vec<(String, String)>> for TemplateValue {
+    fn from(value: Vec<(String, String)>) -> Self {
+        TemplateValue::AssocList(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Simple,
+    Reserved,
+    Fragment,
+    Label,
+    PathSegment,
+    PathParameter,
+    Query,
+    QueryContinuation,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '+' => Operator::Reserved,
+            '#' => Operator::Fragment,
+            '.' => Operator::Label,
+            '/' => Operator::PathSegment,
+            ';' => Operator::PathParameter,
+            '?' => Operator::Query,
+            '&' => Operator::QueryContinuation,
+            _ => return None,
+        })
+    }
+
+    fn first(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved => "",
+            Operator::Fragment => "#",
+            Operator::Label => ".",
+            Operator::PathSegment => "/",
+            Operator::PathParameter => ";",
+            Operator::Query => "?",
+            Operator::QueryContinuation => "&",
+        }
+    }
+
+    fn separator(self) -> char {
+        match self {
+            Operator::Simple | Operator::Reserved | Operator::Fragment => ',',
+            Operator::Label => '.',
+            Operator::PathSegment => '/',
+            Operator::PathParameter => ';',
+            Operator::Query | Operator::QueryContinuation => '&',
+        }
+    }
+
+    fn named(self) -> bool {
+        matches!(
+            self,
+            Operator::PathParameter | Operator::Query | Operator::QueryContinuation
+        )
+    }
+
+    fn if_empty(self) -> &'static str {
+        match self {
+            Operator::Query | Operator::QueryContinuation => "=",
+            _ => "",
+        }
+    }
+
+    fn allow_reserved(self) -> bool {
+        matches!(self, Operator::Reserved | Operator::Fragment)
+    }
+
+    /// Whether `c` may appear in the expansion of an expression with this operator.
+    fn allows_char(self, c: char) -> bool {
+        if is_unreserved(c) || c == '%' || c == ',' {
+            return true;
+        }
+        if self.allow_reserved() {
+            return is_reserved(c);
+        }
+        match self {
+            Operator::PathSegment => c == '/',
+            Operator::PathParameter => c == ';' || c == '=',
+            Operator::Query | Operator::QueryContinuation => c == '?' || c == '&' || c == '=',
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VarSpec {
+    name: String,
+    prefix: Option<usize>,
+    explode: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Expression {
+        operator: Operator,
+        variables: Vec<VarSpec>,
+    },
+}
+
+/// A parsed RFC 6570 URI template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriTemplate {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl UriTemplate {
+    /// Parse a URI template.
+    pub fn parse(template: &str) -> Result<Self, UriTemplateError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.char_indices();
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' => {
+                    let rest = &template[position + 1..];
+                    let end = rest
+                        .find('}')
+                        .ok_or(UriTemplateError::UnclosedExpression(position))?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_expression(&rest[..end], position)?);
+                    // skip the expression body and the closing brace
+                    for _ in 0..rest[..=end].chars().count() {
+                        chars.next();
+                    }
+                }
+                '}' => return Err(UriTemplateError::UnexpectedClosingBrace(position)),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self {
+            source: template.to_string(),
+            parts,
+        })
+    }
+
+    /// The template string this was parsed from.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The names of the variables used by this template, in order of first appearance.
+    pub fn variable_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for part in &self.parts {
+            if let Part::Expression { variables, .. } = part {
+                for var in variables {
+                    if !names.contains(&var.name.as_str()) {
+                        names.push(&var.name);
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    /// Returns `true` if the template declares a variable called `name`.
+    pub fn has_variable(&self, name: &str) -> bool {
+        self.variable_names().contains(&name)
+    }
+
+    /// Expand the template. Variables missing from `variables` are undefined and are
+    /// omitted from the result.
+    pub fn expand(&self, variables: &HashMap<String, TemplateValue>) -> String {
+        let mut uri = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => uri.push_str(literal),
+                Part::Expression {
+                    operator,
+                    variables: specs,
+                } => expand_expression(&mut uri, *operator, specs, variables),
+            }
+        }
+        uri
+    }
+
+    /// Check that `argument`, and every argument already resolved in `context`, is a
+    /// variable of this template.
+    ///
+    /// This is the validation applied to `completion/complete` requests that reference
+    /// a resource template, on both the client and the server side.
+    pub fn validate_completion_argument(
+        &self,
+        argument: &str,
+        context: Option<&CompletionContext>,
+    ) -> Result<(), ErrorData> {
+        let resolved = context
+            .and_then(|context| context.arguments.as_ref())
+            .into_iter()
+            .flat_map(|arguments| arguments.keys().map(String::as_str));
+        for name in std::iter::once(argument).chain(resolved) {
+            if !self.has_variable(name) {
+                return Err(ErrorData::invalid_params(
+                    format!(
+                        "'{name}' is not a variable of uri template '{}'",
+                        self.source
+                    ),
+                    Some(serde_json::json!({
+                        "uriTemplate": self.source,
+                        "argument": name,
+                    })),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Match a concrete URI against this template, returning the decoded variables.
+    ///
+    /// Undefined variables are absent from the result. List and associative array
+    /// values are returned in their unexploded form, with items joined by `,`.
+    ///
+    /// Matching tries a bounded number of ways to split the URI between
+    /// expressions, and fails once they are used up.
+    pub fn match_uri(&self, uri: &str) -> Option<HashMap<String, String>> {
+        let mut variables = HashMap::new();
+        Matcher::new()
+            .match_parts(&self.parts, uri, &mut variables)
+            .then_some(variables)
+    }
+}
+
+impl FromStr for UriTemplate {
+    type Err = UriTemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for UriTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_expression(body: &str, position: usize) -> Result<Part, UriTemplateError> {
+    let Some(first) = body.chars().next() else {
+        return Err(UriTemplateError::EmptyExpression(position));
+    };
+    if matches!(first, '=' | ',' | '!' | '@' | '|') {
+        return Err(UriTemplateError::ReservedOperator {
+            operator: first,
+            position,
+        });
+    }
+    let (operator, list) = match Operator::from_char(first) {
+        Some(operator) => (operator, &body[first.len_utf8()..]),
+        None => (Operator::Simple, body),
+    };
+    let variables = list
+        .split(',')
+        .map(|spec| parse_varspec(spec, position))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Part::Expression {
+        operator,
+        variables,
+    })
+}
+
+fn parse_varspec(spec: &str, position: usize) -> Result<VarSpec, UriTemplateError> {
+    let (name, prefix, explode) = if let Some(name) = spec.strip_suffix('*') {
+        (name, None, true)
+    } else if let Some((name, prefix)) = spec.split_once(':') {
+        let length = prefix
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=9999).contains(n) && !prefix.starts_with('0'))
+            .ok_or_else(|| UriTemplateError::InvalidPrefix {
+                prefix: prefix.to_string(),
+                position,
+            })?;
+        (name, Some(length), false)
+    } else {
+        (spec, None, false)
+    };
+    if !is_valid_varname(name) {
+        return Err(UriTemplateError::InvalidVariableName {
+            name: name.to_string(),
+            position,
+        });
+    }
+    Ok(VarSpec {
+        name: name.to_string(),
+        prefix,
+        explode,
+    })
+}
+
+fn is_valid_varname(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() || bytes[0] == b'.' || bytes[bytes.len() - 1] == b'.' {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                if i + 2 >= bytes.len()
+                    || !bytes[i + 1].is_ascii_hexdigit()
+                    || !bytes[i + 2].is_ascii_hexdigit()
+                {
+                    return false;
+                }
+                i += 3;
+            }
+            b'.' if bytes[i - 1] == b'.' => return false,
+            b if b.is_ascii_alphanumeric() || b == b'_' || b == b'.' => i += 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn is_reserved(c: char) -> bool {
+    matches!(
+        c,
+        ':' | '/'
+            | '?'
+            | '#'
+            | '['
+            | ']'
+            | '@'
+            | '!'
+            | '$'
+            | '&'
+            | '\''
+            | '('
+            | ')'
+            | '*'
+            | '+'
+            | ','
+            | ';'
+            | '='
+    )
+}
+
+fn encode(out: &mut String, value: &str, allow_reserved: bool) {
+    let bytes = value.as_bytes();
+    let mut i = 0;
+    for c in value.chars() {
+        let len = c.len_utf8();
+        let keep = is_unreserved(c)
+            || (allow_reserved
+                && (is_reserved(c)
+                    || (c == '%'
+                        && bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+                        && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit))));
+        if keep {
+            out.push(c);
+        } else {
+            for b in &bytes[i..i + len] {
+                out.push_str(&format!("%{b:02X}"));
+            }
+        }
+        i += len;
+    }
+}
+
+fn decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn expand_expression(
+    out: &mut String,
+    operator: Operator,
+    specs: &[VarSpec],
+    variables: &HashMap<String, TemplateValue>,
+) {
+    let allow_reserved = operator.allow_reserved();
+    let separator = operator.separator();
+    let mut first = true;
+    for spec in specs {
+        let Some(value) = variables.get(&spec.name).filter(|v| v.is_defined()) else {
+            continue;
+        };
+        if first {
+            out.push_str(operator.first());
+            first = false;
+        } else {
+            out.push(separator);
+        }
+        match value {
+            TemplateValue::String(value) => {
+                let value = match spec.prefix {
+                    Some(length) => value.chars().take(length).collect(),
+                    None => value.clone(),
+                };
+                if operator.named() {
+                    out.push_str(&spec.name);
+                    if value.is_empty() {
+                        out.push_str(operator.if_empty());
+                        continue;
+                    }
+                    out.push('=');
+                }
+                encode(out, &value, allow_reserved);
+            }
+            TemplateValue::List(items) if spec.explode => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(separator);
+                    }
+                    if operator.named() {
+                        out.push_str(&spec.name);
+                        if item.is_empty() {
+                            out.push_str(operator.if_empty());
+                            continue;
+                        }
+                        out.push('=');
+                    }
+                    encode(out, item, allow_reserved);
+                }
+            }
+            TemplateValue::AssocList(pairs) if spec.explode => {
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        out.push(separator);
+                    }
+                    encode(out, key, allow_reserved);
+                    if operator.named() && value.is_empty() {
+                        out.push_str(operator.if_empty());
+                        continue;
+                    }
+                    out.push('=');
+                    encode(out, value, allow_reserved);
+                }
+            }
+            TemplateValue::List(items) => {
+                if operator.named() {
+                    out.push_str(&spec.name);
+                    out.push('=');
+                }
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    encode(out, item, allow_reserved);
+                }
+            }
+            TemplateValue::AssocList(pairs) => {
+                if operator.named() {
+                    out.push_str(&spec.name);
+                    out.push('=');
+                }
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    encode(out, key, allow_reserved);
+                    out.push(',');
+                    encode(out, value, allow_reserved);
+                }
+            }
+        }
+    }
+}
+
+/// How many ways of ending an expression [`Matcher`] tries before giving up.
+/// Templates whose expressions are separated by literals need a handful; the
+/// limit keeps a URI that fails to match a template with adjacent expressions,
+/// such as `x://{a}{b}{c}/z`, from taking time exponential in its length.
+const MATCH_ATTEMPTS: usize = 1024;
+
+/// Matches a URI against the parts of a template, backtracking over the extent
+/// of each expression.
+///
+/// Expressions are tried greedily, so `{+path}` in `file:///{+path}` takes as much
+/// of the URI as the rest of the template allows.
+struct Matcher {
+    attempts: usize,
+    /// `(parts left, bytes left)` of the states known not to match.
+    failed: HashSet<(usize, usize)>,
+}
+
+impl Matcher {
+    fn new() -> Self {
+        Self {
+            attempts: MATCH_ATTEMPTS,
+            failed: HashSet::new(),
+        }
+    }
+
+    fn match_parts(
+        &mut self,
+        parts: &[Part],
+        uri: &str,
+        variables: &mut HashMap<String, String>,
+    ) -> bool {
+        let Some((part, rest)) = parts.split_first() else {
+            return uri.is_empty();
+        };
+        let state = (parts.len(), uri.len());
+        if self.failed.contains(&state) {
+            return false;
+        }
+        let matched = match part {
+            Part::Literal(literal) => uri
+                .strip_prefix(literal.as_str())
+                .is_some_and(|uri| self.match_parts(rest, uri, variables)),
+            Part::Expression {
+                operator,
+                variables: specs,
+            } => self.match_expression(*operator, specs, rest, uri, variables),
+        };
+        if !matched {
+            self.failed.insert(state);
+        }
+        matched
+    }
+
+    fn match_expression(
+        &mut self,
+        operator: Operator,
+        specs: &[VarSpec],
+        rest: &[Part],
+        uri: &str,
+        variables: &mut HashMap<String, String>,
+    ) -> bool {
+        // every character an expansion can produce is ASCII, so any byte offset
+        // up to `extent` is a char boundary
+        let mut extent = uri
+            .find(|c: char| !operator.allows_char(c))
+            .unwrap_or(uri.len());
+        // a reserved expansion may contain `?` and `#` literally, but when the
+        // next expression is a query or fragment, that is where it starts
+        if let Some(Part::Expression { operator: next, .. }) = rest.first() {
+            if matches!(next, Operator::Query | Operator::Fragment) {
+                if let Some(delimiter) = next.first().chars().next() {
+                    extent = uri[..extent].find(delimiter).unwrap_or(extent);
+                }
+            }
+        }
+        for end in (0..=extent).rev() {
+            let tail = &uri[end..];
+            // the rest of the template has to be able to start where this
+            // expression ends
+            let fits = match rest.first() {
+                None => tail.is_empty(),
+                Some(Part::Literal(literal)) => tail.starts_with(literal.as_str()),
+                Some(Part::Expression { .. }) => true,
+            };
+            if !fits || self.failed.contains(&(rest.len(), tail.len())) {
+                continue;
+            }
+            if self.attempts == 0 {
+                return false;
+            }
+            self.attempts -= 1;
+            let Some(matched) = match_expression(operator, specs, &uri[..end]) else {
+                continue;
+            };
+            let mut previous = Vec::with_capacity(matched.len());
+            for (name, value) in matched {
+                let old = variables.insert(name.clone(), value);
+                previous.push((name, old));
+            }
+            if self.match_parts(rest, tail, variables) {
+                return true;
+            }
+            for (name, old) in previous {
+                match old {
+                    Some(value) => variables.insert(name, value),
+                    None => variables.remove(&name),
+                };
+            }
+        }
+        false
+    }
+}
+
+fn match_expression(
+    operator: Operator,
+    specs: &[VarSpec],
+    text: &str,
+) -> Option<HashMap<String, String>> {
+    let mut matched = HashMap::new();
+    if text.is_empty() {
+        return Some(matched);
+    }
+    let body = text.strip_prefix(operator.first())?;
+    let pieces: Vec<&str> = body.split(operator.separator()).collect();
+    if operator.named() {
+        let mut extra: Vec<String> = Vec::new();
+        for piece in pieces {
+            let (name, value) = piece.split_once('=').unwrap_or((piece, ""));
+            let name = decode(name)?;
+            let value = decode(value)?;
+            match specs.iter().find(|spec| spec.name == name) {
+                Some(spec) => {
+                    if !spec.explode && matched.contains_key(&spec.name) {
+                        return None;
+                    }
+                    append_value(&mut matched, &spec.name, value);
+                }
+                None => {
+                    extra.push(name);
+                    extra.push(value);
+                }
+            }
+        }
+        if !extra.is_empty() {
+            // unknown names can only come from an exploded associative array
+            let spec = specs.iter().find(|spec| spec.explode)?;
+            append_value(&mut matched, &spec.name, extra.join(","));
+        }
+    } else {
+        let mut pieces = pieces.into_iter().peekable();
+        for (i, spec) in specs.iter().enumerate() {
+            if pieces.peek().is_none() {
+                break;
+            }
+            // an exploded variable, or a trailing list joined with `,`, takes
+            // every remaining piece
+            let value = if spec.explode || (i + 1 == specs.len() && operator.separator() == ',') {
+                pieces
+                    .by_ref()
+                    .map(|piece| {
+                        if spec.explode {
+                            decode(&piece.replacen('=', ",", 1))
+                        } else {
+                            decode(piece)
+                        }
+                    })
+                    .collect::<Option<Vec<_>>>()?
+                    .join(",")
+            } else {
+                decode(pieces.next()?)?
+            };
+            matched.insert(spec.name.clone(), value);
+        }
+        if pieces.next().is_some() {
+            return None;
+        }
+    }
+    for spec in specs {
+        if let Some(value) = matched.get(&spec.name) {
+            check_prefix(spec, value)?;
+        }
+    }
+    Some(matched)
+}
+
+fn check_prefix(spec: &VarSpec, value: &str) -> Option<()> {
+    match spec.prefix {
+        Some(length) if value.chars().count() > length => None,
+        _ => Some(()),
+    }
+}
+
+fn append_value(matched: &mut HashMap<String, String>, name: &str, value: String) {
+    matched
+        .entry(name.to_string())
+        .and_modify(|existing| {
+            existing.push(',');
+            existing.push_str(&value);
+        })
+        .or_insert(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The variable set used by the examples in RFC 6570 section 3.2.
+    fn rfc_variables() -> HashMap<String, TemplateValue> {
+        let pairs = |items: &[(&str, &str)]| {
+            TemplateValue::AssocList(
+                items
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+        };
+        [
+            ("count", TemplateValue::from(vec!["one", "two", "three"])),
+            ("dom", TemplateValue::from(vec!["example", "com"])),
+            ("dub", TemplateValue::from("me/too")),
+            ("hello", TemplateValue::from("Hello World!")),
+            ("half", TemplateValue::from("50%")),
+            ("var", TemplateValue::from("value")),
+            ("who", TemplateValue::from("fred")),
+            ("base", TemplateValue::from("http://example.com/home/")),
+            ("path", TemplateValue::from("/foo/bar")),
+            ("list", TemplateValue::from(vec!["red", "green", "blue"])),
+            (
+                "keys",
+                pairs(&[("semi", ";"), ("dot", "."), ("comma", ",")]),
+            ),
+            ("v", TemplateValue::from("6")),
+            ("x", TemplateValue::from("1024")),
+            ("y", TemplateValue::from("768")),
+            ("empty", TemplateValue::from("")),
+            ("empty_keys", TemplateValue::AssocList(vec![])),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+
+    fn assert_expansions(cases: &[(&str, &str)]) {
+        let variables = rfc_variables();
+        for (template, expected) in cases {
+            let template = UriTemplate::parse(template).expect("template should parse");
+            assert_eq!(
+                template.expand(&variables),
+                *expected,
+                "expanding {template}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_simple_and_reserved() {
+        assert_expansions(&[
+            ("{var}", "value"),
+            ("{hello}", "Hello%20World%21"),
+            ("{half}", "50%25"),
+            ("O{empty}X", "OX"),
+            ("O{undef}X", "OX"),
+            ("{x,y}", "1024,768"),
+            ("{x,hello,y}", "1024,Hello%20World%21,768"),
+            ("?{x,empty}", "?1024,"),
+            ("?{x,undef}", "?1024"),
+            ("{var:3}", "val"),
+            ("{var:30}", "value"),
+            ("{list}", "red,green,blue"),
+            ("{list*}", "red,green,blue"),
+            ("{keys}", "semi,%3B,dot,.,comma,%2C"),
+            ("{keys*}", "semi=%3B,dot=.,comma=%2C"),
+            ("{+var}", "value"),
+            ("{+hello}", "Hello%20World!"),
+            ("{+half}", "50%25"),
+            ("{base}index", "http%3A%2F%2Fexample.com%2Fhome%2Findex"),
+            ("{+base}index", "http://example.com/home/index"),
+            ("{+path:6}/here", "/foo/b/here"),
+            ("{+list}", "red,green,blue"),
+            ("{+keys*}", "semi=;,dot=.,comma=,"),
+        ]);
+    }
+
+    #[test]
+    fn test_expand_operators() {
+        assert_expansions(&[
+            ("{#var}", "#value"),
+            ("{#hello}", "#Hello%20World!"),
+            ("{#path:6}/here", "#/foo/b/here"),
+            ("{#keys*}", "#semi=;,dot=.,comma=,"),
+            ("X{.var}", "X.value"),
+            ("X{.x,y}", "X.1024.768"),
+            ("X{.list*}", "X.red.green.blue"),
+            ("X{.keys}", "X.semi,%3B,dot,.,comma,%2C"),
+            ("X{.empty_keys}", "X"),
+            ("{/var}", "/value"),
+            ("{/var,x}/here", "/value/1024/here"),
+            ("{/list*,path:4}", "/red/green/blue/%2Ffoo"),
+            ("{/keys*}", "/semi=%3B/dot=./comma=%2C"),
+            ("{;x,y}", ";x=1024;y=768"),
+            ("{;x,y,empty}", ";x=1024;y=768;empty"),
+            ("{;hello:5}", ";hello=Hello"),
+            ("{;list*}", ";list=red;list=green;list=blue"),
+            ("{;keys*}", ";semi=%3B;dot=.;comma=%2C"),
+            ("{?x,y}", "?x=1024&y=768"),
+            ("{?x,y,empty}", "?x=1024&y=768&empty="),
+            ("{?var:3}", "?var=val"),
+            ("{?list}", "?list=red,green,blue"),
+            ("{?list*}", "?list=red&list=green&list=blue"),
+            ("{?keys}", "?keys=semi,%3B,dot,.,comma,%2C"),
+            ("{?keys*}", "?semi=%3B&dot=.&comma=%2C"),
+            ("{&who}", "&who=fred"),
+            ("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+            ("{&list*}", "&list=red&list=green&list=blue"),
+        ]);
+    }
+
+    fn matched(template: &str, uri: &str) -> Option<HashMap<String, String>> {
+        UriTemplate::parse(template)
+            .expect("template should parse")
+            .match_uri(uri)
+    }
+
+    #[test]
+    fn test_match_uri() {
+        let vars = matched("users://{id}/profile", "users://42/profile").unwrap();
+        assert_eq!(vars["id"], "42");
+
+        let vars = matched("file:///{dir}/{name}.txt", "file:///docs/readme.txt").unwrap();
+        assert_eq!(vars["dir"], "docs");
+        assert_eq!(vars["name"], "readme");
+
+        let vars = matched("file:///{+path}", "file:///src/lib.rs").unwrap();
+        assert_eq!(vars["path"], "src/lib.rs");
+
+        let vars = matched("search{?q,limit}", "search?limit=10&q=rust%20mcp").unwrap();
+        assert_eq!(vars["q"], "rust mcp");
+        assert_eq!(vars["limit"], "10");
+
+        let vars = matched("search{?q,limit}", "search").unwrap();
+        assert!(vars.is_empty());
+
+        let vars = matched("colors{/list*}", "colors/red/green/blue").unwrap();
+        assert_eq!(vars["list"], "red,green,blue");
+
+        let vars = matched("{hello}", "Hello%20World%21").unwrap();
+        assert_eq!(vars["hello"], "Hello World!");
+
+        assert!(matched("users://{id}/profile", "users://a/b/profile").is_none());
+        assert!(matched("users://{id}/profile", "posts://1/profile").is_none());
+        assert!(matched("{var:3}", "value").is_none());
+        assert!(matched("search{?q}", "search?other=1").is_none());
+    }
+
+    #[test]
+    fn test_match_uri_backtracks() {
+        let vars = matched("x://{+a}/{+b}/z", "x://p/q/r/z").unwrap();
+        assert_eq!(vars["a"], "p/q");
+        assert_eq!(vars["b"], "r");
+
+        // `a` first takes "p/q/r", binding `b` to "s" before `c` fails
+        let vars = matched("x://{+a}/{b}/{c}/end", "x://p/q/r/s/end").unwrap();
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars["a"], "p/q");
+        assert_eq!(vars["b"], "r");
+        assert_eq!(vars["c"], "s");
+
+        // a variable bound by an abandoned split does not leak into the result
+        let vars = matched("x://{+a}/{b}/{c}{d}/end", "x://p/q/r/end").unwrap();
+        assert_eq!(vars["a"], "p");
+        assert_eq!(vars["b"], "q");
+        assert_eq!(vars["c"], "r");
+        assert!(!vars.contains_key("d"));
+    }
+
+    #[test]
+    fn test_long_uri_fails_to_match_quickly() {
+        let uri = format!("x://{}/y", "a".repeat(10_000));
+        let start = std::time::Instant::now();
+        assert!(matched("x://{a}{b}{c}/z", &uri).is_none());
+        assert!(matched("x://{+a}{+b}{+c}{+d}/z", &uri).is_none());
+        let reserved = format!("x://{}", "/".repeat(10_000));
+        assert!(matched("x://{+a}/{+b}/{+c}/z", &reserved).is_none());
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_expand_then_match_round_trips() {
+        let variables = rfc_variables();
+        for template in [
+            "file:///{+path}{?var,x}",
+            "{/var,x}/here",
+            "X{.var}{#who}",
+            "{;x,y}",
+        ] {
+            let template = UriTemplate::parse(template).unwrap();
+            let uri = template.expand(&variables);
+            let matched = template.match_uri(&uri).expect("expansion should match");
+            for name in template.variable_names() {
+                let TemplateValue::String(expected) = &variables[name] else {
+                    unreachable!()
+                };
+                assert_eq!(&matched[name], expected, "{name} in {template}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |template: &str| UriTemplate::parse(template).unwrap_err();
+        assert_eq!(err("users/{id"), UriTemplateError::UnclosedExpression(6));
+        assert_eq!(
+            err("users/id}"),
+            UriTemplateError::UnexpectedClosingBrace(8)
+        );
+        assert_eq!(err("{}"), UriTemplateError::EmptyExpression(0));
+        assert!(matches!(
+            err("{=x}"),
+            UriTemplateError::ReservedOperator { operator: '=', .. }
+        ));
+        assert!(matches!(
+            err("{x:0}"),
+            UriTemplateError::InvalidPrefix { .. }
+        ));
+        assert!(matches!(
+            err("{x:10000}"),
+            UriTemplateError::InvalidPrefix { .. }
+        ));
+        assert!(matches!(
+            err("{a b}"),
+            UriTemplateError::InvalidVariableName { .. }
+        ));
+        assert!(matches!(
+            err("{x,}"),
+            UriTemplateError::InvalidVariableName { .. }
+        ));
+    }
+
+    #[test]
+    fn test_validate_completion_argument() {
+        let template = UriTemplate::parse("repos://{owner}/{repo}").unwrap();
+        assert!(template.validate_completion_argument("repo", None).is_ok());
+
+        let context =
+            CompletionContext::with_arguments([("owner".to_string(), "rust".to_string())].into());
+        assert!(
+            template
+                .validate_completion_argument("repo", Some(&context))
+                .is_ok()
+        );
+
+        let err = template
+            .validate_completion_argument("branch", None)
+            .unwrap_err();
+        assert_eq!(err.code, crate::model::ErrorCode::INVALID_PARAMS);
+
+        let context =
+            CompletionContext::with_arguments([("user".to_string(), "rust".to_string())].into());
+        assert!(
+            template
+                .validate_completion_argument("repo", Some(&context))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_variable_names() {
+        let template = UriTemplate::parse("{scheme}://{+host}{/segments*}{?q,lang:2}{&q}").unwrap();
+        assert_eq!(
+            template.variable_names(),
+            ["scheme", "host", "segments", "q", "lang"]
+        );
+        assert!(template.has_variable("lang"));
+        assert!(!template.has_variable("missing"));
+    }
+}
@@ -0,0 +1,290 @@
+//! JSON Schema (draft 2020-12) validation of tool arguments and structured results.
+//!
+//! Servers enable input validation per router with
+//! [`ToolRouter::with_input_validation`](crate::handler::server::router::tool::ToolRouter::with_input_validation).
+//! Arguments that do not match [`Tool::input_schema`](crate::model::Tool::input_schema) are
+//! rejected with `-32602` before the handler runs, and the error data lists every
+//! [`SchemaViolation`]:
+//!
+//! ```json
+//! {
+//!   "violations": [
+//!     { "pointer": "/count", "keyword": "type", "message": "\"3\" is not of type \"integer\"" }
+//!   ]
+//! }
+//! ```
+//!
+//! [`ToolRouter::with_output_validation`](crate::handler::server::router::tool::ToolRouter::with_output_validation)
+//! checks `structured_content` against [`Tool::output_schema`](crate::model::Tool::output_schema)
+//! after the handler returns and turns a mismatch into a tool error result. Clients run the
+//! same check with [`Peer::call_tool_validated`](crate::service::Peer::call_tool_validated).
+//!
+//! The module only depends on [`model`](crate::model), so the `schema-validation` feature
+//! works with either of the `client` and `server` features.
+
+use std::sync::{Arc, OnceLock};
+
+use serde::Serialize;
+
+use crate::model::{CallToolResult, Content, JsonObject, Tool};
+
+/// A single way in which a value does not match its schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value, empty for the value itself.
+    pub pointer: String,
+    /// The schema keyword that failed, e.g. `required` or `type`.
+    pub keyword: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {} ({})", self.message, self.keyword)
+    }
+}
+
+/// A schema compiled on first use and shared by clones.
+#[derive(Clone, Default)]
+pub(crate) struct LazyValidator {
+    validator: Arc<OnceLock<Option<jsonschema::Validator>>>,
+}
+
+impl std::fmt::Debug for LazyValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyValidator")
+            .field("compiled", &self.validator.get().is_some())
+            .finish()
+    }
+}
+
+impl LazyValidator {
+    /// Validate `value` against `schema`, compiling it on the first call.
+    ///
+    /// A schema that fails to compile is reported once and then treated as
+    /// accepting everything.
+    pub(crate) fn validate(
+        &self,
+        schema: &JsonObject,
+        value: &serde_json::Value,
+    ) -> Result<(), Vec<SchemaViolation>> {
+        let validator = self.validator.get_or_init(|| {
+            jsonschema::draft202012::new(&serde_json::Value::Object(schema.clone()))
+                .inspect_err(|e| tracing::warn!("ignoring invalid JSON schema: {e}"))
+                .ok()
+        });
+        match validator {
+            Some(validator) => check(validator, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Validate the structured content of `result` against `output_schema`.
+    ///
+    /// Error results and tools without an output schema always pass.
+    pub(crate) fn validate_output(
+        &self,
+        output_schema: Option<&JsonObject>,
+        result: &CallToolResult,
+    ) -> Result<(), Vec<SchemaViolation>> {
+        let Some(schema) = output_schema else {
+            return Ok(());
+        };
+        if result.is_error == Some(true) {
+            return Ok(());
+        }
+        match &result.structured_content {
+            Some(structured) => self.validate(schema, structured),
+            None => Err(vec![SchemaViolation {
+                pointer: String::new(),
+                keyword: "required".to_string(),
+                message: "tool declares an output schema but returned no structured content"
+                    .to_string(),
+            }]),
+        }
+    }
+}
+
+/// Output schemas compiled for the tools of one server, by tool name.
+#[cfg(feature = "client")]
+#[derive(Debug, Default)]
+pub(crate) struct ValidatorCache {
+    validators:
+        std::sync::Mutex<std::collections::HashMap<String, (Arc<JsonObject>, LazyValidator)>>,
+}
+
+#[cfg(feature = "client")]
+impl ValidatorCache {
+    /// Validate the structured content of `result` against the output schema
+    /// of `tool`, compiling the schema only when the tool is new or its schema
+    /// changed.
+    pub(crate) fn validate_structured_content(
+        &self,
+        tool: &Tool,
+        result: &CallToolResult,
+    ) -> Result<(), Vec<SchemaViolation>> {
+        let Some(schema) = &tool.output_schema else {
+            return Ok(());
+        };
+        let validator = {
+            let mut validators = self
+                .validators
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            match validators.get(tool.name.as_ref()) {
+                Some((cached, validator)) if Arc::ptr_eq(cached, schema) || cached == schema => {
+                    validator.clone()
+                }
+                _ => {
+                    let validator = LazyValidator::default();
+                    validators.insert(tool.name.to_string(), (schema.clone(), validator.clone()));
+                    validator
+                }
+            }
+        };
+        validator.validate_output(Some(schema), result)
+    }
+}
+
+fn check(
+    validator: &jsonschema::Validator,
+    value: &serde_json::Value,
+) -> Result<(), Vec<SchemaViolation>> {
+    let violations: Vec<_> = validator
+        .iter_errors(value)
+        .map(|error| SchemaViolation {
+            pointer: error.instance_path().to_string(),
+            keyword: error.kind().keyword().to_string(),
+            message: error.to_string(),
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Validate `value` against `schema` once, without caching the compiled schema.
+pub fn validate(
+    schema: &JsonObject,
+    value: &serde_json::Value,
+) -> Result<(), Vec<SchemaViolation>> {
+    LazyValidator::default().validate(schema, value)
+}
+
+/// Check the structured content of `result` against the output schema of `tool`.
+///
+/// A successful result of a tool that declares an output schema must carry
+/// matching `structured_content`; error results are not checked. The schema is
+/// compiled on every call; [`Peer::call_tool_validated`](crate::service::Peer::call_tool_validated)
+/// keeps it compiled.
+pub fn validate_structured_content(
+    tool: &Tool,
+    result: &CallToolResult,
+) -> Result<(), Vec<SchemaViolation>> {
+    LazyValidator::default().validate_output(tool.output_schema.as_deref(), result)
+}
+
+/// Build the tool error result returned in place of a non-conforming result.
+pub fn invalid_output_result(tool: &str, violations: &[SchemaViolation]) -> CallToolResult {
+    let mut message =
+        format!("structured content of tool '{tool}' does not match its output schema:");
+    for violation in violations {
+        message.push_str("\n  ");
+        message.push_str(&violation.to_string());
+    }
+    CallToolResult::error(vec![Content::text(message)])
+}
+
+/// Build the `invalid_params` error returned for arguments that do not match.
+pub fn invalid_arguments_error(tool: &str, violations: Vec<SchemaViolation>) -> crate::ErrorData {
+    crate::ErrorData::invalid_params(
+        format!("arguments for tool '{tool}' do not match its input schema"),
+        Some(serde_json::json!({ "violations": violations })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> JsonObject {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "count": { "type": "integer", "minimum": 1 }
+            },
+            "required": ["name"]
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_validate_reports_every_violation() {
+        let violations = validate(&schema(), &json!({ "count": 0 })).unwrap_err();
+        let mut found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.pointer.as_str(), v.keyword.as_str()))
+            .collect();
+        found.sort();
+        assert_eq!(found, [("", "required"), ("/count", "minimum")]);
+
+        assert!(validate(&schema(), &json!({ "name": "x", "count": 2 })).is_ok());
+    }
+
+    #[test]
+    fn test_validate_structured_content() {
+        let tool = Tool::new("t", "", Arc::new(JsonObject::new()))
+            .with_raw_output_schema(Arc::new(schema()));
+
+        let ok = CallToolResult::structured(json!({ "name": "x" }));
+        assert!(validate_structured_content(&tool, &ok).is_ok());
+
+        let wrong = CallToolResult::structured(json!({ "name": 1 }));
+        let violations = validate_structured_content(&tool, &wrong).unwrap_err();
+        assert_eq!(violations[0].pointer, "/name");
+        assert_eq!(violations[0].keyword, "type");
+
+        let missing = CallToolResult::success(vec![Content::text("x")]);
+        assert!(validate_structured_content(&tool, &missing).is_err());
+
+        let error = CallToolResult::error(vec![Content::text("boom")]);
+        assert!(validate_structured_content(&tool, &error).is_ok());
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_validator_cache() {
+        let cache = ValidatorCache::default();
+        let tool = Tool::new("t", "", Arc::new(JsonObject::new()))
+            .with_raw_output_schema(Arc::new(schema()));
+        let wrong = CallToolResult::structured(json!({ "name": 1 }));
+        assert!(cache.validate_structured_content(&tool, &wrong).is_err());
+        assert!(cache.validate_structured_content(&tool, &wrong).is_err());
+        assert_eq!(cache.validators.lock().unwrap().len(), 1);
+
+        // a new schema for the same tool replaces the compiled one
+        let tool = tool.with_raw_output_schema(Arc::new(JsonObject::new()));
+        assert!(cache.validate_structured_content(&tool, &wrong).is_ok());
+        assert_eq!(cache.validators.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_schema_accepts_everything() {
+        let schema = json!({ "type": 12 }).as_object().unwrap().clone();
+        let validator = LazyValidator::default();
+        assert!(validator.validate(&schema, &json!("anything")).is_ok());
+    }
+}
@@ -61,6 +61,8 @@ pub use client::*;
 mod server;
 #[cfg(feature = "server")]
 pub use server::*;
+mod panic;
+pub use panic::*;
 #[cfg(feature = "tower")]
 mod tower;
 use tokio_util::sync::{CancellationToken, DropGuard};
@@ -82,6 +84,12 @@ pub enum ServiceError {
     Cancelled { reason: Option<String> },
     #[error("request timeout after {}", chrono::Duration::from_std(*timeout).unwrap_or_default())]
     Timeout { timeout: Duration },
+    #[cfg(feature = "schema-validation")]
+    #[error("structured content of tool '{tool}' does not match its output schema")]
+    OutputSchemaViolation {
+        tool: String,
+        violations: Vec<crate::schema_validation::SchemaViolation>,
+    },
 }
 
 trait TransferObject:
@@ -141,6 +149,10 @@ pub trait Service<R: ServiceRole>: Send + Sync + 'static {
         context: NotificationContext<R>,
     ) -> impl Future<Output = Result<(), McpError>> + MaybeSendFuture + '_;
     fn get_info(&self) -> R::Info;
+    /// How this service reacts when one of its request handlers panics.
+    fn panic_policy(&self) -> PanicPolicy {
+        PanicPolicy::default()
+    }
 }
 
 #[cfg(feature = "local")]
@@ -156,6 +168,10 @@ pub trait Service<R: ServiceRole>: 'static {
         context: NotificationContext<R>,
     ) -> impl Future<Output = Result<(), McpError>> + MaybeSendFuture + '_;
     fn get_info(&self) -> R::Info;
+    /// How this service reacts when one of its request handlers panics.
+    fn panic_policy(&self) -> PanicPolicy {
+        PanicPolicy::default()
+    }
 }
 
 pub trait ServiceExt<R: ServiceRole>: Service<R> + Sized {
@@ -207,6 +223,10 @@ impl<R: ServiceRole> Service<R> for Box<dyn DynService<R>> {
     fn get_info(&self) -> R::Info {
         DynService::get_info(self.as_ref())
     }
+
+    fn panic_policy(&self) -> PanicPolicy {
+        DynService::panic_policy(self.as_ref())
+    }
 }
 
 #[cfg(not(feature = "local"))]
@@ -222,6 +242,7 @@ pub trait DynService<R: ServiceRole>: Send + Sync {
         context: NotificationContext<R>,
     ) -> MaybeBoxFuture<'_, Result<(), McpError>>;
     fn get_info(&self) -> R::Info;
+    fn panic_policy(&self) -> PanicPolicy;
 }
 
 #[cfg(feature = "local")]
@@ -237,6 +258,7 @@ pub trait DynService<R: ServiceRole> {
         context: NotificationContext<R>,
     ) -> MaybeBoxFuture<'_, Result<(), McpError>>;
     fn get_info(&self) -> R::Info;
+    fn panic_policy(&self) -> PanicPolicy;
 }
 
 impl<R: ServiceRole, S: Service<R>> DynService<R> for S {
@@ -257,6 +279,9 @@ impl<R: ServiceRole, S: Service<R>> DynService<R> for S {
     fn get_info(&self) -> R::Info {
         self.get_info()
     }
+    fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy()
+    }
 }
 
 use std::{
@@ -385,6 +410,9 @@ pub struct Peer<R: ServiceRole> {
     request_id_provider: Arc<dyn RequestIdProvider>,
     progress_token_provider: Arc<dyn ProgressTokenProvider>,
     info: Arc<tokio::sync::OnceCell<R::PeerInfo>>,
+    /// Output schemas compiled by [`Peer::call_tool_validated`].
+    #[cfg(all(feature = "client", feature = "schema-validation"))]
+    output_validators: Arc<crate::schema_validation::ValidatorCache>,
 }
 
 impl<R: ServiceRole> std::fmt::Debug for Peer<R> {
@@ -424,6 +452,8 @@ impl<R: ServiceRole> Peer<R> {
                 request_id_provider,
                 progress_token_provider: Arc::new(AtomicU32ProgressTokenProvider::default()),
                 info: Arc::new(tokio::sync::OnceCell::new_with(peer_info)),
+                #[cfg(all(feature = "client", feature = "schema-validation"))]
+                output_validators: Default::default(),
             },
             rx,
         )
@@ -499,6 +529,51 @@ impl<R: ServiceRole> Peer<R> {
     pub fn is_transport_closed(&self) -> bool {
         self.tx.is_closed()
     }
+
+    /// Resolves once the service talking to this peer has stopped, e.g.
+    /// because the connection closed.
+    pub async fn closed(&self) {
+        self.tx.closed().await
+    }
+
+    /// Whether both handles talk to the same connection.
+    #[cfg(feature = "server")]
+    pub(crate) fn is_same_peer(&self, other: &Self) -> bool {
+        self.tx.same_channel(&other.tx)
+    }
+
+    /// A handle that sends through this one and calls `tap` with every
+    /// notification on the way, before it is sent.
+    #[cfg(feature = "server")]
+    pub(crate) fn tapped(&self, tap: impl Fn(&R::Not) + Send + Sync + 'static) -> Peer<R> {
+        let (tx, mut rx) = mpsc::channel(Self::CLIENT_CHANNEL_BUFFER_SIZE);
+        let sink = self.tx.clone();
+        spawn_service_task(async move {
+            loop {
+                let message = tokio::select! {
+                    message = rx.recv() => message,
+                    _ = sink.closed() => None,
+                };
+                let Some(message) = message else {
+                    break;
+                };
+                if let PeerSinkMessage::Notification { notification, .. } = &message {
+                    tap(notification);
+                }
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Peer {
+            tx,
+            request_id_provider: self.request_id_provider.clone(),
+            progress_token_provider: self.progress_token_provider.clone(),
+            info: self.info.clone(),
+            #[cfg(all(feature = "client", feature = "schema-validation"))]
+            output_validators: self.output_validators.clone(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -646,13 +721,16 @@ pub enum QuitReason {
     Cancelled,
     Closed,
     JoinError(tokio::task::JoinError),
+    /// A request handler panicked and the [`PanicPolicy`] asked to shut down.
+    Panicked,
 }
 
 /// Request execution context
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct RequestContext<R: ServiceRole> {
-    /// this token will be cancelled when the [`CancelledNotification`] is received.
+    /// this token will be cancelled when the [`CancelledNotification`] is received,
+    /// when the service stops, and once the request has been answered.
     pub ct: CancellationToken,
     pub id: RequestId,
     pub meta: Meta,
@@ -771,6 +849,9 @@ where
     // let message_sink = tokio::sync::
     // let mut stream = std::pin::pin!(stream);
     let serve_loop_ct = ct.child_token();
+    let panic_policy = service.panic_policy();
+    // cancelled by a request handler that panicked, when the policy says to stop
+    let panicked_ct = CancellationToken::new();
     let peer_return: Peer<R> = peer.clone();
     let current_span = tracing::Span::current();
     let handle = spawn_service_task(async move {
@@ -845,6 +926,10 @@ where
                         tracing::info!("task cancelled");
                         break QuitReason::Cancelled
                     }
+                    _ = panicked_ct.cancelled() => {
+                        tracing::warn!("shutting down after a request handler panicked");
+                        break QuitReason::Panicked
+                    }
                 }
             };
 
@@ -959,22 +1044,36 @@ where
                             meta,
                             extensions,
                         };
+                        let panicked_ct = panicked_ct.clone();
+                        let panic_policy = panic_policy.clone();
                         let current_span = tracing::Span::current();
                         spawn_service_task(async move {
-                            let result = service
-                                .handle_request(request, context)
-                                .await;
+                            let result = std::panic::AssertUnwindSafe(
+                                service.handle_request(request, context),
+                            )
+                            .catch_unwind()
+                            .await;
+                            let mut shutdown = false;
                             let response = match result {
-                                Ok(result) => {
+                                Ok(Ok(result)) => {
                                     tracing::debug!(%id, ?result, "response message");
                                     JsonRpcMessage::response(result, id)
                                 }
-                                Err(error) => {
+                                Ok(Err(error)) => {
                                     tracing::warn!(%id, ?error, "response error");
                                     JsonRpcMessage::error(error, Some(id))
                                 }
+                                Err(payload) => {
+                                    panic_policy.report(&HandlerPanic::new(id.clone(), payload.as_ref()));
+                                    shutdown = panic_policy.shutdown();
+                                    let error = McpError::internal_error("request handler panicked", None);
+                                    JsonRpcMessage::error(error, Some(id))
+                                }
                             };
                             let _send_result = sink.send(response).await;
+                            if shutdown {
+                                panicked_ct.cancel();
+                            }
                         }.instrument(current_span));
                     }
                 }
@@ -1044,6 +1143,10 @@ where
             }
         };
 
+        // The connection is going away: let handlers that are still running
+        // wind down while their responses are drained.
+        serve_loop_ct.cancel();
+
         // Drain in-flight handler responses before closing the transport.
         // When stdin EOF or cancellation arrives, spawned handler tasks may still
         // be finishing. We need to:
@@ -1051,7 +1154,7 @@ where
         // 2. Drain any remaining handler responses from the channel
         let drain_timeout = match &quit_reason {
             QuitReason::Closed => Some(Duration::from_secs(5)),
-            QuitReason::Cancelled => Some(Duration::from_secs(2)),
+            QuitReason::Cancelled | QuitReason::Panicked => Some(Duration::from_secs(2)),
             _ => None,
         };
         if let Some(timeout_duration) = drain_timeout {
@@ -16,7 +16,7 @@ use crate::{
         ReadResourceRequest, ReadResourceRequestParams, ReadResourceResult, Reference, RequestId,
         RootsListChangedNotification, ServerInfo, ServerJsonRpcMessage, ServerNotification,
         ServerRequest, ServerResult, SetLevelRequest, SetLevelRequestParams, SubscribeRequest,
-        SubscribeRequestParams, UnsubscribeRequest, UnsubscribeRequestParams,
+        SubscribeRequestParams, UnsubscribeRequest, UnsubscribeRequestParams, UriTemplate,
     },
     transport::DynamicTransportError,
 };
@@ -352,6 +352,18 @@ macro_rules! method {
     };
 }
 
+/// The cursor of the next page. A server that hands back the cursor it was
+/// just given would be paged forever, so that is an error.
+fn next_page_cursor(
+    current: Option<String>,
+    next: Option<String>,
+) -> Result<Option<String>, ServiceError> {
+    if next.is_some() && next == current {
+        return Err(ServiceError::UnexpectedResponse);
+    }
+    Ok(next)
+}
+
 impl Peer<RoleClient> {
     method!(peer_req complete CompleteRequest(CompleteRequestParams) => CompleteResult);
     method!(peer_req set_level SetLevelRequest(SetLevelRequestParams));
@@ -372,6 +384,39 @@ impl Peer<RoleClient> {
 }
 
 impl Peer<RoleClient> {
+    /// Call `tool` and check the structured content of a successful result
+    /// against its `output_schema`, as listed by the server.
+    ///
+    /// The schema is compiled on the first call and reused for as long as the
+    /// tool keeps it. A mismatch is returned as
+    /// [`ServiceError::OutputSchemaViolation`]. `params` must name `tool`;
+    /// otherwise the call fails with `invalid_params` without reaching the
+    /// server.
+    #[cfg(feature = "schema-validation")]
+    pub async fn call_tool_validated(
+        &self,
+        tool: &crate::model::Tool,
+        params: CallToolRequestParams,
+    ) -> Result<CallToolResult, ServiceError> {
+        if tool.name != params.name {
+            return Err(ServiceError::McpError(crate::ErrorData::invalid_params(
+                format!(
+                    "called tool '{}' with the output schema of tool '{}'",
+                    params.name, tool.name
+                ),
+                None,
+            )));
+        }
+        let result = self.call_tool(params).await?;
+        self.output_validators
+            .validate_structured_content(tool, &result)
+            .map_err(|violations| ServiceError::OutputSchemaViolation {
+                tool: tool.name.to_string(),
+                violations,
+            })?;
+        Ok(result)
+    }
+
     /// A wrapper method for [`Peer<RoleClient>::list_tools`].
     ///
     /// This function will call [`Peer<RoleClient>::list_tools`] multiple times until all tools are listed.
@@ -380,10 +425,13 @@ impl Peer<RoleClient> {
         let mut cursor = None;
         loop {
             let result = self
-                .list_tools(Some(PaginatedRequestParams { meta: None, cursor }))
+                .list_tools(Some(PaginatedRequestParams {
+                    meta: None,
+                    cursor: cursor.clone(),
+                }))
                 .await?;
             tools.extend(result.tools);
-            cursor = result.next_cursor;
+            cursor = next_page_cursor(cursor, result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -399,10 +447,13 @@ impl Peer<RoleClient> {
         let mut cursor = None;
         loop {
             let result = self
-                .list_prompts(Some(PaginatedRequestParams { meta: None, cursor }))
+                .list_prompts(Some(PaginatedRequestParams {
+                    meta: None,
+                    cursor: cursor.clone(),
+                }))
                 .await?;
             prompts.extend(result.prompts);
-            cursor = result.next_cursor;
+            cursor = next_page_cursor(cursor, result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -418,10 +469,13 @@ impl Peer<RoleClient> {
         let mut cursor = None;
         loop {
             let result = self
-                .list_resources(Some(PaginatedRequestParams { meta: None, cursor }))
+                .list_resources(Some(PaginatedRequestParams {
+                    meta: None,
+                    cursor: cursor.clone(),
+                }))
                 .await?;
             resources.extend(result.resources);
-            cursor = result.next_cursor;
+            cursor = next_page_cursor(cursor, result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -439,10 +493,13 @@ impl Peer<RoleClient> {
         let mut cursor = None;
         loop {
             let result = self
-                .list_resource_templates(Some(PaginatedRequestParams { meta: None, cursor }))
+                .list_resource_templates(Some(PaginatedRequestParams {
+                    meta: None,
+                    cursor: cursor.clone(),
+                }))
                 .await?;
             resource_templates.extend(result.resource_templates);
-            cursor = result.next_cursor;
+            cursor = next_page_cursor(cursor, result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -483,6 +540,10 @@ impl Peer<RoleClient> {
 
     /// Convenient method to get completion suggestions for a resource URI argument
     ///
+    /// The template is parsed as RFC 6570 and the request is rejected locally with
+    /// `invalid_params` if `argument_name`, or an argument resolved in `context`, is
+    /// not one of its variables.
+    ///
     /// # Arguments
     /// * `uri_template` - URI template pattern being completed
     /// * `argument_name` - Name of the URI parameter being completed
@@ -498,11 +559,25 @@ impl Peer<RoleClient> {
         current_value: impl Into<String>,
         context: Option<CompletionContext>,
     ) -> Result<CompletionInfo, ServiceError> {
+        let uri_template = uri_template.into();
+        let argument_name = argument_name.into();
+        UriTemplate::parse(&uri_template)
+            .map_err(|error| {
+                ErrorData::invalid_params(
+                    format!("invalid uri template '{uri_template}': {error}"),
+                    None,
+                )
+            })
+            .and_then(|template| {
+                template.validate_completion_argument(&argument_name, context.as_ref())
+            })
+            .map_err(ServiceError::McpError)?;
+
         let request = CompleteRequestParams {
             meta: None,
             r#ref: Reference::for_resource(uri_template),
             argument: ArgumentInfo {
-                name: argument_name.into(),
+                name: argument_name,
                 value: current_value.into(),
             },
             context,
@@ -0,0 +1,123 @@
+//! What the service does when a request handler panics.
+//!
+//! A panic inside a request handler is caught by the service: the panic is
+//! logged, the request is answered with an `internal_error`, and the service
+//! keeps serving other requests. [`PanicPolicy`] adds a hook, e.g. to report
+//! the panic to a crash collector, and can make the service stop instead.
+//! Each handler picks its own policy:
+//!
+//! ```rust
+//! use rmcp::{ServerHandler, service::PanicPolicy};
+//!
+//! struct Server;
+//!
+//! impl ServerHandler for Server {
+//!     fn panic_policy(&self) -> PanicPolicy {
+//!         PanicPolicy::new()
+//!             .with_hook(|panic| eprintln!("request {} panicked: {}", panic.request_id, panic.message))
+//!             .with_shutdown(true)
+//!     }
+//! }
+//! ```
+//!
+//! The policy is read once, when the service starts serving. The default
+//! panic hook of the standard library still runs before the panic is caught.
+
+use std::{any::Any, sync::Arc};
+
+use crate::model::RequestId;
+
+/// A panic caught in a request handler.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct HandlerPanic {
+    /// The request that was being handled.
+    pub request_id: RequestId,
+    /// The panic message, if the payload was a string.
+    pub message: String,
+}
+
+impl HandlerPanic {
+    pub(crate) fn new(request_id: RequestId, payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            (*message).to_owned()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_owned()
+        };
+        Self {
+            request_id,
+            message,
+        }
+    }
+}
+
+type PanicHook = Arc<dyn Fn(&HandlerPanic) + Send + Sync>;
+
+/// How services react to a panicking request handler. See the
+/// [module docs](self).
+#[derive(Clone, Default)]
+pub struct PanicPolicy {
+    hook: Option<PanicHook>,
+    shutdown: bool,
+}
+
+impl std::fmt::Debug for PanicPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PanicPolicy")
+            .field("hook", &self.hook.is_some())
+            .field("shutdown", &self.shutdown)
+            .finish()
+    }
+}
+
+impl PanicPolicy {
+    pub const fn new() -> Self {
+        Self {
+            hook: None,
+            shutdown: false,
+        }
+    }
+
+    /// Call `hook` for every caught panic, after it has been logged.
+    pub fn with_hook(mut self, hook: impl Fn(&HandlerPanic) + Send + Sync + 'static) -> Self {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Whether the service stops after answering the request that panicked.
+    /// It then quits with [`QuitReason::Panicked`](super::QuitReason::Panicked).
+    /// Defaults to `false`.
+    pub fn with_shutdown(mut self, shutdown: bool) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn shutdown(&self) -> bool {
+        self.shutdown
+    }
+
+    pub(crate) fn report(&self, panic: &HandlerPanic) {
+        tracing::error!(id = %panic.request_id, message = %panic.message, "request handler panicked");
+        if let Some(hook) = &self.hook {
+            hook(panic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let id = RequestId::Number(1);
+        assert_eq!(HandlerPanic::new(id.clone(), &"static").message, "static");
+        assert_eq!(
+            HandlerPanic::new(id.clone(), &format!("formatted {}", 1)).message,
+            "formatted 1"
+        );
+        assert_eq!(HandlerPanic::new(id, &42).message, "Box<dyn Any>");
+    }
+}
@@ -5,6 +5,7 @@ use tokio::{
     sync::mpsc,
     time::{Duration, timeout},
 };
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     RoleServer,
@@ -26,6 +27,10 @@ pub struct OperationDescriptor {
     pub client_request: Option<ClientRequest>,
     pub context: Option<RequestContext<RoleServer>>,
     pub ttl: Option<u64>,
+    /// Cancelled when the task is cancelled, times out or its client goes
+    /// away. The context's own `ct` fires as soon as the request that created
+    /// the task is answered, so the task should watch this one instead.
+    pub ct: CancellationToken,
 }
 
 impl OperationDescriptor {
@@ -36,6 +41,7 @@ impl OperationDescriptor {
             client_request: None,
             context: None,
             ttl: None,
+            ct: CancellationToken::new(),
         }
     }
 
@@ -76,6 +82,8 @@ pub trait OperationResultTransport: Send + Sync + 'static {
 
 // ===== Operation Processor =====
 pub const DEFAULT_TASK_TIMEOUT_SECS: u64 = 300; // 5 minutes
+/// How long a cancelled task may keep running to clean up before it is dropped.
+pub const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(5);
 /// Operation processor that coordinates extractors and handlers
 pub struct OperationProcessor {
     /// Currently running tasks keyed by id
@@ -180,8 +188,31 @@ impl OperationProcessor {
             }
         };
 
+        let ct = descriptor.ct.clone();
+        let peer = descriptor
+            .context
+            .as_ref()
+            .map(|context| context.peer.clone());
+        let cancelled = async move {
+            let peer_closed = async {
+                match &peer {
+                    Some(peer) => peer.closed().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = ct.cancelled() => {}
+                _ = peer_closed => ct.cancel(),
+            }
+            // let the task observe its token and wind down on its own
+            tokio::time::sleep(CANCEL_GRACE_PERIOD).await;
+        };
+
         let handle = tokio::spawn(async move {
-            let result = timed_future.await;
+            let result = tokio::select! {
+                result = timed_future => result,
+                _ = cancelled => Err(Error::TaskError("Operation cancelled".to_string())),
+            };
             let task_result = TaskResult {
                 descriptor: descriptor_for_result,
                 result,
@@ -200,8 +231,14 @@ impl OperationProcessor {
     /// Collect completed results from running tasks and remove them from the running tasks map.
     fn collect_completed_results(&mut self) {
         while let Ok(result) = self.task_result_receiver.try_recv() {
-            self.running_tasks.remove(&result.descriptor.operation_id);
-            self.completed_results.push(result);
+            // cancelled tasks already have their terminal result
+            if self
+                .running_tasks
+                .remove(&result.descriptor.operation_id)
+                .is_some()
+            {
+                self.completed_results.push(result);
+            }
         }
     }
 
@@ -214,6 +251,7 @@ impl OperationProcessor {
         for (task_id, task) in &self.running_tasks {
             if let Some(timeout_duration) = task.timeout {
                 if now.duration_since(task.started_at).as_secs() > timeout_duration {
+                    task.descriptor.ct.cancel();
                     task.task_handle.abort();
                     timed_out_tasks.push(task_id.clone());
                 }
@@ -240,6 +278,7 @@ impl OperationProcessor {
     /// Cancel all running tasks.
     pub fn cancel_all_tasks(&mut self) {
         for (_, task) in self.running_tasks.drain() {
+            task.descriptor.ct.cancel();
             task.task_handle.abort();
         }
         while self.task_result_receiver.try_recv().is_ok() {}
@@ -271,10 +310,13 @@ impl OperationProcessor {
     }
 
     /// Attempt to cancel a running task.
+    ///
+    /// The task's [`ct`](OperationDescriptor::ct) fires at once; the task is
+    /// dropped if it is still running after [`CANCEL_GRACE_PERIOD`].
     pub fn cancel_task(&mut self, task_id: &str) -> bool {
         self.collect_completed_results();
         if let Some(task) = self.running_tasks.remove(task_id) {
-            task.task_handle.abort();
+            task.descriptor.ct.cancel();
             // Insert a cancelled result so callers can observe the terminal state.
             let cancel_result = TaskResult {
                 descriptor: task.descriptor,
@@ -13,6 +13,10 @@ use super::session::{
 };
 use crate::{
     RoleServer,
+    handler::server::{
+        rate_limit::RateLimiter,
+        scopes::{InsufficientScope, ScopeRequirements},
+    },
     model::{
         ClientJsonRpcMessage, ClientNotification, ClientRequest, GetExtensions, InitializeRequest,
         InitializedNotification, ProtocolVersion,
@@ -95,6 +99,19 @@ pub struct StreamableHttpServerConfig {
     /// };
     /// ```
     pub session_store: Option<Arc<dyn SessionStore>>,
+    /// Optional rate limiter applied to incoming JSON-RPC requests.
+    ///
+    /// Requests over the limit are answered with `429 Too Many Requests` and
+    /// a `Retry-After` header without reaching the session. See
+    /// [`rate_limit`](crate::handler::server::rate_limit).
+    pub rate_limiter: Option<RateLimiter>,
+    /// Optional scopes required by tools, prompts and resources.
+    ///
+    /// Calls whose caller lacks a required scope are answered with
+    /// `403 Forbidden` and a `WWW-Authenticate` header naming the scopes,
+    /// without reaching the session. See
+    /// [`scopes`](crate::handler::server::scopes).
+    pub scope_requirements: Option<ScopeRequirements>,
 }
 
 impl std::fmt::Debug for dyn SessionStore {
@@ -114,6 +131,8 @@ impl Default for StreamableHttpServerConfig {
             allowed_hosts: vec!["localhost".into(), "127.0.0.1".into(), "::1".into()],
             allowed_origins: vec![],
             session_store: None,
+            rate_limiter: None,
+            scope_requirements: None,
         }
     }
 }
@@ -167,6 +186,16 @@ impl StreamableHttpServerConfig {
         self.cancellation_token = token;
         self
     }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn with_scope_requirements(mut self, requirements: ScopeRequirements) -> Self {
+        self.scope_requirements = Some(requirements);
+        self
+    }
 }
 
 #[expect(
@@ -209,6 +238,27 @@ fn validate_protocol_version_header(headers: &http::HeaderMap) -> Result<(), Box
     Ok(())
 }
 
+fn too_many_requests_response(retry_after: Duration) -> BoxResponse {
+    // Retry-After only takes whole seconds, so round up.
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    Response::builder()
+        .status(http::StatusCode::TOO_MANY_REQUESTS)
+        .header(http::header::RETRY_AFTER, seconds)
+        .body(Full::new(Bytes::from("Too Many Requests: rate limit exceeded")).boxed())
+        .expect("valid response")
+}
+
+fn insufficient_scope_response(insufficient: &InsufficientScope) -> BoxResponse {
+    Response::builder()
+        .status(http::StatusCode::FORBIDDEN)
+        .header(
+            http::header::WWW_AUTHENTICATE,
+            insufficient.www_authenticate(),
+        )
+        .body(Full::new(Bytes::from(format!("Forbidden: {insufficient}"))).boxed())
+        .expect("valid response")
+}
+
 fn forbidden_response(message: impl Into<String>) -> BoxResponse {
     Response::builder()
         .status(http::StatusCode::FORBIDDEN)
@@ -599,6 +649,32 @@ where
         (self.service_factory)()
     }
 
+    #[expect(
+        clippy::result_large_err,
+        reason = "BoxResponse is intentionally large; matches other handlers in this file"
+    )]
+    fn check_rate_limit(&self, request: &ClientRequest) -> Result<(), BoxResponse> {
+        match &self.config.rate_limiter {
+            Some(limiter) => limiter
+                .check(request)
+                .map_err(|limited| too_many_requests_response(limited.retry_after)),
+            None => Ok(()),
+        }
+    }
+
+    #[expect(
+        clippy::result_large_err,
+        reason = "BoxResponse is intentionally large; matches other handlers in this file"
+    )]
+    fn check_scopes(&self, request: &ClientRequest) -> Result<(), BoxResponse> {
+        match &self.config.scope_requirements {
+            Some(requirements) => requirements
+                .check(request)
+                .map_err(|insufficient| insufficient_scope_response(&insufficient)),
+            None => Ok(()),
+        }
+    }
+
     /// Spawn a task that runs `serve_server` for the given session, waits for
     /// it to finish, and then calls `close_session`.
     ///
@@ -1037,6 +1113,8 @@ where
                 match &mut message {
                     ClientJsonRpcMessage::Request(req) => {
                         req.request.extensions_mut().insert(part);
+                        self.check_rate_limit(&req.request)?;
+                        self.check_scopes(&req.request)?;
                     }
                     ClientJsonRpcMessage::Notification(not) => {
                         not.notification.extensions_mut().insert(part);
@@ -1177,6 +1255,8 @@ where
             match message {
                 ClientJsonRpcMessage::Request(mut request) => {
                     request.request.extensions_mut().insert(part);
+                    self.check_rate_limit(&request.request)?;
+                    self.check_scopes(&request.request)?;
                     let (transport, mut receiver) =
                         OneshotTransport::<RoleServer>::new(ClientJsonRpcMessage::Request(request));
                     let service = serve_directly(service, transport, None);
@@ -0,0 +1,105 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_cancellation --features "client server macros"
+
+use std::{sync::Arc, time::Duration};
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::router::tool::ToolRouter,
+    model::{CallToolRequest, CallToolRequestParams, ClientInfo, ClientRequest},
+    service::{PeerRequestOptions, RunningService},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone)]
+struct SlowServer {
+    tool_router: ToolRouter<Self>,
+    started: Arc<Notify>,
+    cleaned_up: Arc<Notify>,
+}
+
+#[tool_router]
+impl SlowServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+            started: Default::default(),
+            cleaned_up: Default::default(),
+        }
+    }
+
+    #[tool(description = "Wait until cancelled")]
+    async fn wait(&self, ct: CancellationToken) -> String {
+        self.started.notify_one();
+        ct.cancelled().await;
+        self.cleaned_up.notify_one();
+        "cancelled".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for SlowServer {}
+
+#[derive(Debug, Clone, Default)]
+struct Client;
+
+impl ClientHandler for Client {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+async fn connect(server: SlowServer) -> anyhow::Result<RunningService<RoleClient, Client>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(Client.serve(client_transport).await?)
+}
+
+async fn call_wait(
+    client: &RunningService<RoleClient, Client>,
+    started: &Notify,
+) -> anyhow::Result<rmcp::service::RequestHandle<RoleClient>> {
+    let handle = client
+        .send_cancellable_request(
+            ClientRequest::CallToolRequest(CallToolRequest::new(CallToolRequestParams::new(
+                "wait",
+            ))),
+            PeerRequestOptions::no_options(),
+        )
+        .await?;
+    tokio::time::timeout(Duration::from_secs(5), started.notified()).await?;
+    Ok(handle)
+}
+
+#[tokio::test]
+async fn test_cancelled_notification_fires_the_token() -> anyhow::Result<()> {
+    let server = SlowServer::new();
+    let (started, cleaned_up) = (server.started.clone(), server.cleaned_up.clone());
+    let client = connect(server).await?;
+
+    let handle = call_wait(&client, &started).await?;
+    let notified = cleaned_up.notified();
+    handle.cancel(Some("no longer needed".to_string())).await?;
+    tokio::time::timeout(Duration::from_secs(5), notified).await?;
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_closed_transport_fires_the_token() -> anyhow::Result<()> {
+    let server = SlowServer::new();
+    let (started, cleaned_up) = (server.started.clone(), server.cleaned_up.clone());
+    let client = connect(server).await?;
+
+    let _handle = call_wait(&client, &started).await?;
+    let notified = cleaned_up.notified();
+    client.cancel().await?;
+    tokio::time::timeout(Duration::from_secs(5), notified).await?;
+    Ok(())
+}
@@ -0,0 +1,194 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_completion_macros --features "client server"
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use rmcp::{
+    ClientHandler, ServerHandler, ServiceExt, completion, completion_handler, completion_router,
+    handler::server::{
+        completion::CompletionValue,
+        router::{
+            Router,
+            completion::{CompletionRouter, CompletionTarget},
+        },
+    },
+    model::{ClientInfo, CompletionContext},
+};
+
+const LANGUAGES: &[&str] = &["go", "python", "ruby", "rust", "typescript"];
+
+#[derive(Debug, Clone)]
+pub struct CodeServer {
+    completion_router: CompletionRouter<Self>,
+}
+
+impl Default for CodeServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[completion_router]
+impl CodeServer {
+    pub fn new() -> Self {
+        Self {
+            completion_router: Self::completion_router(),
+        }
+    }
+
+    #[completion(prompt = "code_review", argument = "language")]
+    async fn complete_language(&self, CompletionValue(value): CompletionValue) -> Vec<String> {
+        LANGUAGES
+            .iter()
+            .filter(|lang| lang.starts_with(&value))
+            .map(|lang| lang.to_string())
+            .collect()
+    }
+
+    /// Frameworks depend on the language the client already picked.
+    #[completion(prompt = "code_review", argument = "framework")]
+    fn complete_framework(
+        &self,
+        CompletionValue(value): CompletionValue,
+        context: CompletionContext,
+    ) -> Vec<&'static str> {
+        let frameworks: &[&'static str] = match context.get_argument("language").map(String::as_str)
+        {
+            Some("rust") => &["actix", "axum", "rocket"],
+            Some("python") => &["django", "fastapi", "flask"],
+            _ => &[],
+        };
+        frameworks
+            .iter()
+            .copied()
+            .filter(|f| f.starts_with(&value))
+            .collect()
+    }
+
+    #[completion(resource = "repo://{owner}/{repo}", argument = "repo")]
+    fn complete_repo(&self, context: CompletionContext) -> Vec<String> {
+        let owner = context.get_argument("owner").cloned().unwrap_or_default();
+        vec![format!("{owner}-core"), format!("{owner}-docs")]
+    }
+}
+
+#[completion_handler(router = self.completion_router)]
+impl ServerHandler for CodeServer {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler {}
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+#[test]
+fn test_completion_router_contents() {
+    let router = CodeServer::completion_router();
+    assert!(router.has_route(&CompletionTarget::prompt("code_review", "language")));
+    assert!(router.has_route(&CompletionTarget::resource("repo://{owner}/{repo}", "repo")));
+    assert_eq!(router.list_all().len(), 3);
+}
+
+#[test]
+fn test_completion_handler_get_info() {
+    let info = CodeServer::new().get_info();
+    assert!(info.capabilities.completions.is_some());
+    assert!(info.capabilities.tools.is_none());
+}
+
+#[tokio::test]
+async fn test_completions_through_client() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+
+    let server_handle = tokio::spawn(async move {
+        CodeServer::new()
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+
+    let client = DummyClientHandler::default()
+        .serve(client_transport)
+        .await?;
+
+    let values = client
+        .complete_prompt_simple("code_review", "language", "r")
+        .await?;
+    assert_eq!(values, ["ruby", "rust"]);
+
+    let context = CompletionContext::with_arguments(HashMap::from([(
+        "language".to_string(),
+        "rust".to_string(),
+    )]));
+    let completion = client
+        .complete_prompt_argument("code_review", "framework", "a", Some(context))
+        .await?;
+    assert_eq!(completion.values, ["actix", "axum"]);
+
+    let values = client
+        .complete_prompt_simple("code_review", "framework", "")
+        .await?;
+    assert!(values.is_empty());
+
+    let context = CompletionContext::with_arguments(HashMap::from([(
+        "owner".to_string(),
+        "rmcp".to_string(),
+    )]));
+    let completion = client
+        .complete_resource_argument("repo://{owner}/{repo}", "repo", "", Some(context))
+        .await?;
+    assert_eq!(completion.values, ["rmcp-core", "rmcp-docs"]);
+
+    // Targets without a handler complete to nothing rather than failing.
+    let values = client
+        .complete_prompt_simple("unknown", "language", "r")
+        .await?;
+    assert!(values.is_empty());
+
+    client.cancel().await?;
+    server_handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_completion_through_router() -> anyhow::Result<()> {
+    #[derive(Debug, Clone, Default)]
+    struct EmptyServer;
+    impl ServerHandler for EmptyServer {}
+
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+
+    let router = Router::new(EmptyServer).with_completion((
+        CompletionTarget::prompt("greet", "name"),
+        |CompletionValue(value): CompletionValue| {
+            vec![format!("{value}lice"), format!("{value}my")]
+        },
+    ));
+    assert!(
+        rmcp::Service::get_info(&router)
+            .capabilities
+            .completions
+            .is_some()
+    );
+    let server_handle = tokio::spawn(async move {
+        router.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+
+    let client = DummyClientHandler::default()
+        .serve(client_transport)
+        .await?;
+
+    let values = client.complete_prompt_simple("greet", "name", "A").await?;
+    assert_eq!(values, ["Alice", "Amy"]);
+
+    client.cancel().await?;
+    server_handle.await??;
+    Ok(())
+}
@@ -0,0 +1,404 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_composite_server --features "client server macros"
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{composite::CompositeServer, router::tool::ToolRouter},
+    model::{
+        AnnotateAble, CallToolRequestParams, CallToolResult, ClientInfo, Content, ErrorCode,
+        GetPromptRequestParams, GetPromptResult, ListPromptsResult, ListResourcesResult,
+        ListToolsResult, LoggingLevel, LoggingMessageNotificationParam, PaginatedRequestParams,
+        PromptMessage, PromptMessageRole, RawResource, ReadResourceRequestParams,
+        ReadResourceResult, ResourceContents, ServerCapabilities, ServerInfo, Tool,
+    },
+    prompt, prompt_handler, prompt_router,
+    service::{NotificationContext, RequestContext, RunningService, ServiceError},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone)]
+struct MathServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MathServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Answer")]
+    async fn answer(&self) -> String {
+        "42".to_string()
+    }
+
+    #[tool(description = "Log a message to the client")]
+    async fn shout(&self, context: RequestContext<RoleServer>) -> Result<String, ErrorData> {
+        context
+            .peer
+            .notify_logging_message(LoggingMessageNotificationParam {
+                level: LoggingLevel::Info,
+                logger: None,
+                data: serde_json::json!("from math"),
+            })
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok("shouted".to_string())
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MathServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+            .with_instructions("math tools")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GitServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl GitServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Repository status")]
+    async fn status(&self) -> String {
+        "clean".to_string()
+    }
+
+    // same name as MathServer's tool, kept apart by the mount prefix
+    #[tool(description = "Another answer")]
+    async fn answer(&self) -> String {
+        "git".to_string()
+    }
+}
+
+#[prompt_router]
+impl GitServer {
+    #[prompt(description = "Commit message")]
+    async fn commit(&self) -> Vec<PromptMessage> {
+        vec![PromptMessage::new_text(PromptMessageRole::User, "write it")]
+    }
+}
+
+#[tool_handler]
+#[prompt_handler]
+impl ServerHandler for GitServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .build(),
+        )
+        .with_instructions("git tools")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DocsServer;
+
+impl ServerHandler for DocsServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .build(),
+        )
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        Ok(ListResourcesResult::with_all_items(vec![
+            RawResource::new("docs://readme", "readme").no_annotation(),
+        ]))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        Ok(ReadResourceResult::new(vec![ResourceContents::text(
+            "hello",
+            request.uri,
+        )]))
+    }
+}
+
+/// Serves the tools `count` and `grow`, counting how often it is listed.
+/// `grow` adds a tool and sends `list_changed`.
+#[derive(Debug, Clone, Default)]
+struct CountingServer {
+    lists: Arc<AtomicUsize>,
+    extra: Arc<Mutex<Vec<String>>>,
+}
+
+impl ServerHandler for CountingServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
+        )
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        self.lists.fetch_add(1, Ordering::SeqCst);
+        let object = Arc::new(serde_json::Map::new());
+        let mut names = vec!["count".to_owned(), "grow".to_owned()];
+        names.extend(self.extra.lock().unwrap().iter().cloned());
+        Ok(ListToolsResult::with_all_items(
+            names
+                .into_iter()
+                .map(|name| Tool::new(name, "A counting tool", object.clone()))
+                .collect(),
+        ))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if request.name == "grow" {
+            {
+                let mut extra = self.extra.lock().unwrap();
+                let name = format!("extra_{}", extra.len());
+                extra.push(name);
+            }
+            context
+                .peer
+                .notify_tool_list_changed()
+                .await
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            request.name.to_string(),
+        )]))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct LoggingClient {
+    messages: Arc<Mutex<Vec<serde_json::Value>>>,
+    received: Arc<Notify>,
+}
+
+impl ClientHandler for LoggingClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.messages.lock().unwrap().push(params.data);
+        self.received.notify_one();
+    }
+}
+
+async fn connect(
+    server: CompositeServer,
+    client: LoggingClient,
+) -> anyhow::Result<RunningService<RoleClient, LoggingClient>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(client.serve(client_transport).await?)
+}
+
+fn composite() -> CompositeServer {
+    CompositeServer::new()
+        .mount(MathServer::new())
+        .mount_with_prefix("git_", GitServer::new())
+        .mount(DocsServer)
+}
+
+async fn call(
+    client: &RunningService<RoleClient, LoggingClient>,
+    name: &'static str,
+) -> Result<String, ServiceError> {
+    let result = client.call_tool(CallToolRequestParams::new(name)).await?;
+    Ok(result.content[0].as_text().unwrap().text.clone())
+}
+
+#[tokio::test]
+async fn test_children_are_merged_and_routed() -> anyhow::Result<()> {
+    let client = connect(composite(), LoggingClient::default()).await?;
+
+    let info = client.peer_info().unwrap();
+    assert!(info.capabilities.tools.is_some());
+    assert!(info.capabilities.prompts.is_some());
+    assert_eq!(
+        info.capabilities.resources.as_ref().unwrap().subscribe,
+        Some(true)
+    );
+    assert_eq!(
+        info.instructions.as_deref(),
+        Some("math tools\n\ngit tools")
+    );
+
+    let mut names: Vec<_> = client
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["answer", "git_answer", "git_status", "shout"]);
+
+    assert_eq!(call(&client, "answer").await?, "42");
+    assert_eq!(call(&client, "git_answer").await?, "git");
+    assert_eq!(call(&client, "git_status").await?, "clean");
+    let err = call(&client, "status")
+        .await
+        .expect_err("unprefixed name of a prefixed child");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    let prompts = client.list_all_prompts().await?;
+    assert_eq!(prompts.len(), 1);
+    assert_eq!(prompts[0].name, "git_commit");
+    client
+        .get_prompt(GetPromptRequestParams::new("git_commit"))
+        .await?;
+
+    let resources = client.list_all_resources().await?;
+    assert_eq!(resources.len(), 1);
+    let read = client
+        .read_resource(ReadResourceRequestParams::new("docs://readme"))
+        .await?;
+    assert_eq!(read.contents.len(), 1);
+    let err = client
+        .read_resource(ReadResourceRequestParams::new("docs://missing"))
+        .await
+        .expect_err("no child lists this resource");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::RESOURCE_NOT_FOUND);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_child_notifications_reach_the_client() -> anyhow::Result<()> {
+    let logging = LoggingClient::default();
+    let client = connect(composite(), logging.clone()).await?;
+
+    let received = logging.received.notified();
+    assert_eq!(call(&client, "shout").await?, "shouted");
+    received.await;
+    assert_eq!(
+        logging.messages.lock().unwrap().as_slice(),
+        [serde_json::json!("from math")]
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_owners_are_indexed_until_a_list_changes() -> anyhow::Result<()> {
+    let counting = CountingServer::default();
+    let lists = counting.lists.clone();
+    let server = CompositeServer::new()
+        .mount(MathServer::new())
+        .mount_with_prefix("counting_", counting);
+    let client = connect(server, LoggingClient::default()).await?;
+
+    assert_eq!(client.list_all_tools().await?.len(), 4);
+    assert_eq!(lists.load(Ordering::SeqCst), 1);
+    for _ in 0..3 {
+        assert_eq!(call(&client, "counting_count").await?, "count");
+        assert_eq!(call(&client, "answer").await?, "42");
+    }
+    assert_eq!(lists.load(Ordering::SeqCst), 1);
+
+    // list_changed drops the index, so the next call lists again
+    assert_eq!(call(&client, "counting_grow").await?, "grow");
+    assert_eq!(call(&client, "counting_count").await?, "count");
+    assert_eq!(lists.load(Ordering::SeqCst), 2);
+    assert_eq!(call(&client, "counting_extra_0").await?, "extra_0");
+    assert_eq!(lists.load(Ordering::SeqCst), 2);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_merged_lists_are_paginated() -> anyhow::Result<()> {
+    let client = connect(composite().with_page_size(3), LoggingClient::default()).await?;
+
+    let first = client.list_tools(None).await?;
+    let names: Vec<_> = first.tools.iter().map(|tool| tool.name.as_ref()).collect();
+    assert_eq!(names, ["answer", "git_answer", "git_status"]);
+    let second = client
+        .list_tools(Some(
+            PaginatedRequestParams::default().with_cursor(first.next_cursor),
+        ))
+        .await?;
+    let names: Vec<_> = second.tools.iter().map(|tool| tool.name.as_ref()).collect();
+    assert_eq!(names, ["shout"]);
+    assert_eq!(second.next_cursor, None);
+
+    let err = client
+        .list_tools(Some(
+            PaginatedRequestParams::default().with_cursor(Some("bogus".to_owned())),
+        ))
+        .await
+        .expect_err("a cursor the composite did not produce");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_server_info_overrides() {
+    let server = CompositeServer::new()
+        .with_instructions("composite")
+        .mount(MathServer::new());
+    let info = rmcp::Service::<RoleServer>::get_info(&server);
+    assert_eq!(
+        info.instructions.as_deref(),
+        Some("composite\n\nmath tools")
+    );
+    assert!(info.capabilities.prompts.is_none());
+    assert_eq!(server.len(), 1);
+}
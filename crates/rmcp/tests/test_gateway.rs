@@ -0,0 +1,324 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_gateway --features "client server macros"
+
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{
+        gateway::Gateway,
+        layer::{McpLayer, McpLayerExt, Next},
+        router::tool::ToolRouter,
+    },
+    model::{
+        CallToolRequest, CallToolRequestParams, ClientInfo, ClientRequest,
+        CreateMessageRequestParams, CreateMessageResult, Meta, ProgressNotificationParam,
+        SamplingMessage, ServerCapabilities, ServerInfo, ServerResult, ToolsCapability,
+    },
+    service::{NotificationContext, Peer, PeerRequestOptions, RequestContext, RunningService},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone)]
+struct UpstreamServer {
+    tool_router: ToolRouter<Self>,
+    started: Arc<Notify>,
+    cancelled: Arc<Notify>,
+}
+
+#[tool_router]
+impl UpstreamServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+            started: Default::default(),
+            cancelled: Default::default(),
+        }
+    }
+
+    #[tool(description = "Answer")]
+    async fn answer(&self) -> String {
+        "42".to_string()
+    }
+
+    #[tool(description = "Ask the client's model")]
+    async fn ask(&self, peer: Peer<RoleServer>) -> Result<String, ErrorData> {
+        let result = peer
+            .create_message(CreateMessageRequestParams::new(
+                vec![SamplingMessage::user_text("which model?")],
+                16,
+            ))
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok(result.model)
+    }
+
+    #[tool(description = "Report progress")]
+    async fn work(&self, meta: Meta, peer: Peer<RoleServer>) -> Result<String, ErrorData> {
+        let progress_token = meta
+            .get_progress_token()
+            .ok_or_else(|| ErrorData::invalid_params("progress token required", None))?;
+        peer.notify_progress(ProgressNotificationParam {
+            progress_token,
+            progress: 1.0,
+            total: Some(1.0),
+            message: None,
+        })
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok("done".to_string())
+    }
+
+    #[tool(description = "Wait until cancelled")]
+    async fn hang(&self, context: RequestContext<RoleServer>) -> String {
+        self.started.notify_one();
+        context.ct.cancelled().await;
+        self.cancelled.notify_one();
+        "cancelled".to_string()
+    }
+
+    #[tool(description = "Announce a tool list change")]
+    async fn reload(&self, peer: Peer<RoleServer>) -> Result<String, ErrorData> {
+        peer.notify_tool_list_changed()
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok("reloaded".to_string())
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for UpstreamServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
+        )
+    }
+}
+
+/// Counts `tools/list` requests, and hides that the server announces changes
+/// to its tools.
+#[derive(Debug, Clone, Default)]
+struct CountingLayer {
+    lists: Arc<AtomicUsize>,
+}
+
+impl McpLayer for CountingLayer {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+        next: Next<'_>,
+    ) -> Result<ServerResult, ErrorData> {
+        if matches!(request, ClientRequest::ListToolsRequest(_)) {
+            self.lists.fetch_add(1, Ordering::SeqCst);
+        }
+        let mut result = next.run(request, context).await;
+        if let Ok(ServerResult::InitializeResult(result)) = &mut result {
+            result.capabilities.tools = Some(ToolsCapability::default());
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct DownstreamClient {
+    progress: Arc<Mutex<Vec<ProgressNotificationParam>>>,
+    received: Arc<Notify>,
+}
+
+impl ClientHandler for DownstreamClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+
+    async fn create_message(
+        &self,
+        _params: CreateMessageRequestParams,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        Ok(CreateMessageResult::new(
+            SamplingMessage::assistant_text("hello"),
+            "downstream-model".to_string(),
+        ))
+    }
+
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.progress.lock().unwrap().push(params);
+        self.received.notify_one();
+    }
+
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.received.notify_one();
+    }
+}
+
+async fn gateway(upstream: UpstreamServer) -> anyhow::Result<Gateway> {
+    let mut gateway = Gateway::new();
+    for (prefix, server) in [("a_", upstream.clone()), ("b_", upstream)] {
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            server.serve(server_transport).await?.waiting().await?;
+            anyhow::Ok(())
+        });
+        gateway = gateway.connect(prefix, client_transport).await?;
+    }
+    Ok(gateway)
+}
+
+async fn connect(
+    gateway: Gateway,
+    client: DownstreamClient,
+) -> anyhow::Result<RunningService<RoleClient, DownstreamClient>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        gateway.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(client.serve(client_transport).await?)
+}
+
+async fn call(
+    client: &RunningService<RoleClient, DownstreamClient>,
+    name: &'static str,
+) -> anyhow::Result<String> {
+    let result = client.call_tool(CallToolRequestParams::new(name)).await?;
+    Ok(result.content[0].as_text().unwrap().text.clone())
+}
+
+#[tokio::test]
+async fn test_upstreams_are_namespaced() -> anyhow::Result<()> {
+    let gateway = gateway(UpstreamServer::new()).await?;
+    assert_eq!(gateway.len(), 2);
+    let client = connect(gateway, DownstreamClient::default()).await?;
+
+    let info = client.peer_info().unwrap();
+    assert_eq!(
+        info.capabilities.tools.as_ref().unwrap().list_changed,
+        Some(true)
+    );
+
+    let mut names: Vec<_> = client
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "a_answer", "a_ask", "a_hang", "a_reload", "a_work", "b_answer", "b_ask", "b_hang",
+            "b_reload", "b_work"
+        ]
+    );
+    assert_eq!(call(&client, "b_answer").await?, "42");
+    assert!(call(&client, "answer").await.is_err());
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calls_do_not_relist_upstreams() -> anyhow::Result<()> {
+    let layer = CountingLayer::default();
+    let lists = layer.lists.clone();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        UpstreamServer::new()
+            .layer(layer)
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let gateway = Gateway::new().connect("a_", client_transport).await?;
+    let client = connect(gateway, DownstreamClient::default()).await?;
+
+    assert_eq!(client.list_all_tools().await?.len(), 5);
+    for _ in 0..3 {
+        assert_eq!(call(&client, "a_answer").await?, "42");
+    }
+    assert_eq!(lists.load(Ordering::SeqCst), 1);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sampling_is_relayed_downstream() -> anyhow::Result<()> {
+    let client = connect(
+        gateway(UpstreamServer::new()).await?,
+        DownstreamClient::default(),
+    )
+    .await?;
+
+    assert_eq!(call(&client, "a_ask").await?, "downstream-model");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_progress_and_list_changed_are_relayed() -> anyhow::Result<()> {
+    let downstream = DownstreamClient::default();
+    let client = connect(gateway(UpstreamServer::new()).await?, downstream.clone()).await?;
+
+    let received = downstream.received.notified();
+    let handle = client
+        .send_cancellable_request(
+            ClientRequest::CallToolRequest(CallToolRequest::new(CallToolRequestParams::new(
+                "a_work",
+            ))),
+            PeerRequestOptions::no_options(),
+        )
+        .await?;
+    let token = handle.progress_token.clone();
+    handle.await_response().await?;
+    received.await;
+    let progress = downstream.progress.lock().unwrap().clone();
+    assert_eq!(progress.len(), 1);
+    assert_eq!(progress[0].progress_token, token);
+
+    let received = downstream.received.notified();
+    assert_eq!(call(&client, "b_reload").await?, "reloaded");
+    received.await;
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancellation_is_relayed_upstream() -> anyhow::Result<()> {
+    let upstream = UpstreamServer::new();
+    let started = upstream.started.clone();
+    let cancelled = upstream.cancelled.clone();
+    let client = connect(gateway(upstream).await?, DownstreamClient::default()).await?;
+
+    let handle = client
+        .send_cancellable_request(
+            ClientRequest::CallToolRequest(CallToolRequest::new(CallToolRequestParams::new(
+                "a_hang",
+            ))),
+            PeerRequestOptions::no_options(),
+        )
+        .await?;
+    let upstream_cancelled = cancelled.notified();
+    tokio::time::timeout(std::time::Duration::from_secs(5), started.notified()).await?;
+    handle.cancel(None).await?;
+    tokio::time::timeout(std::time::Duration::from_secs(5), upstream_cancelled).await?;
+
+    client.cancel().await?;
+    Ok(())
+}
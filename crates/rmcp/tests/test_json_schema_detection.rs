@@ -57,8 +57,10 @@ impl TestServer {
 
     /// Tool with explicit output_schema attribute - should have output schema
     #[tool(name = "explicit-schema", output_schema = rmcp::handler::server::tool::schema_for_type::<TestData>())]
-    pub async fn explicit_schema(&self) -> Result<String, String> {
-        Ok("test".to_string())
+    pub async fn explicit_schema(&self) -> Result<Json<TestData>, String> {
+        Ok(Json(TestData {
+            value: "test".to_string(),
+        }))
     }
 }
 
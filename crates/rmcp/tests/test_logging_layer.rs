@@ -0,0 +1,152 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_logging_layer --features "client server macros server-logging"
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::{layer::McpLayerExt, logging::McpLogging, router::tool::ToolRouter},
+    model::{
+        CallToolRequestParams, ClientInfo, LoggingLevel, LoggingMessageNotificationParam,
+        ServerCapabilities, ServerInfo, SetLevelRequestParams,
+    },
+    service::{NotificationContext, RunningService},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::Notify;
+use tracing_subscriber::prelude::*;
+
+#[derive(Debug, Clone)]
+struct LoggingServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl LoggingServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Log at two levels")]
+    async fn work(&self) -> String {
+        tracing::debug!(step = 1, "starting");
+        tracing::info!(answer = 42, ok = true, "computed");
+        "done".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for LoggingServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_logging()
+                .enable_tools()
+                .build(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct LoggingClient {
+    messages: Arc<Mutex<Vec<LoggingMessageNotificationParam>>>,
+    received: Arc<Notify>,
+}
+
+impl LoggingClient {
+    async fn wait_for(&self, count: usize) -> Vec<LoggingMessageNotificationParam> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let received = self.received.notified();
+                if self.messages.lock().unwrap().len() >= count {
+                    return self.messages.lock().unwrap().clone();
+                }
+                received.await;
+            }
+        })
+        .await
+        .expect("log messages")
+    }
+}
+
+impl ClientHandler for LoggingClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.messages.lock().unwrap().push(params);
+        self.received.notify_waiters();
+    }
+}
+
+async fn connect(
+    logging: McpLogging,
+    client: LoggingClient,
+) -> anyhow::Result<RunningService<RoleClient, LoggingClient>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        LoggingServer::new()
+            .layer(logging)
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    Ok(client.serve(client_transport).await?)
+}
+
+#[tokio::test]
+async fn test_request_events_reach_the_client() -> anyhow::Result<()> {
+    let logging = McpLogging::new();
+    let _guard = tracing_subscriber::registry()
+        .with(logging.clone())
+        .set_default();
+    let downstream = LoggingClient::default();
+    let client = connect(logging, downstream.clone()).await?;
+
+    // events outside of a request are not forwarded
+    tracing::info!("not for the client");
+
+    client.call_tool(CallToolRequestParams::new("work")).await?;
+    let messages = downstream.wait_for(1).await;
+    assert_eq!(messages.len(), 1, "debug is below the default level");
+    assert_eq!(messages[0].level, LoggingLevel::Info);
+    assert_eq!(messages[0].logger.as_deref(), Some("test_logging_layer"));
+    assert_eq!(
+        messages[0].data,
+        serde_json::json!({ "message": "computed", "answer": 42, "ok": true })
+    );
+
+    client
+        .set_level(SetLevelRequestParams::new(LoggingLevel::Debug))
+        .await?;
+    client.call_tool(CallToolRequestParams::new("work")).await?;
+    let messages = downstream.wait_for(3).await;
+    assert_eq!(messages[1].level, LoggingLevel::Debug);
+    assert_eq!(
+        messages[1].data,
+        serde_json::json!({ "message": "starting", "step": 1 })
+    );
+    assert_eq!(messages[2].level, LoggingLevel::Info);
+
+    client
+        .set_level(SetLevelRequestParams::new(LoggingLevel::Error))
+        .await?;
+    client.call_tool(CallToolRequestParams::new("work")).await?;
+    client.list_all_tools().await?;
+    assert_eq!(downstream.messages.lock().unwrap().len(), 3);
+
+    client.cancel().await?;
+    Ok(())
+}
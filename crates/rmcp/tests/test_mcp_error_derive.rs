@@ -0,0 +1,136 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_mcp_error_derive --features "client server macros"
+
+use rmcp::{
+    ClientHandler, ErrorData, McpError, ServerHandler, ServiceExt,
+    handler::server::{tool::IntoCallToolResult, wrapper::Parameters},
+    model::{CallToolRequestParams, ClientInfo, ErrorCode},
+    schemars::JsonSchema,
+    service::ServiceError,
+    tool, tool_handler, tool_router,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, thiserror::Error, McpError)]
+enum UserError {
+    #[error("no user {0}")]
+    #[mcp(code = -32602)]
+    UnknownUser(String),
+    #[error("rate limited")]
+    #[mcp(tool_error, message = "rate limited, retry in {retry_after}s")]
+    RateLimited {
+        #[mcp(data)]
+        retry_after: u64,
+    },
+    #[error("database error: {0}")]
+    #[mcp(message = "database unavailable")]
+    Database(String),
+}
+
+#[test]
+fn test_into_error_data() {
+    let error = ErrorData::from(UserError::UnknownUser("ada".to_string()));
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(error.message, "no user ada");
+    assert_eq!(error.data, None);
+
+    let error: ErrorData = UserError::Database("connection refused".to_string()).into();
+    assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    assert_eq!(error.message, "database unavailable");
+
+    // outside of tools a tool error is still an error
+    let error: ErrorData = UserError::RateLimited { retry_after: 3 }.into();
+    assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    assert_eq!(error.message, "rate limited, retry in 3s");
+    assert_eq!(error.data, Some(json!(3)));
+}
+
+#[test]
+fn test_into_call_tool_result() {
+    let result = Err::<String, _>(UserError::RateLimited { retry_after: 3 })
+        .into_call_tool_result()
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.content[0].as_text().unwrap().text,
+        "rate limited, retry in 3s"
+    );
+    assert_eq!(result.content[1].as_text().unwrap().text, "3");
+    assert_eq!(result.structured_content, None);
+
+    let error = Err::<String, _>(UserError::UnknownUser("ada".to_string()))
+        .into_call_tool_result()
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct UserParams {
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+struct UserServer;
+
+#[tool_router]
+impl UserServer {
+    #[tool(description = "Look up a user")]
+    async fn user(
+        &self,
+        Parameters(UserParams { name }): Parameters<UserParams>,
+    ) -> Result<String, UserError> {
+        match name.as_str() {
+            "ada" => Ok("Ada Lovelace".to_string()),
+            "busy" => Err(UserError::RateLimited { retry_after: 1 }),
+            _ => Err(UserError::UnknownUser(name)),
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for UserServer {}
+
+#[derive(Debug, Clone, Default)]
+struct Client;
+
+impl ClientHandler for Client {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+fn user(name: &str) -> CallToolRequestParams {
+    CallToolRequestParams::new("user")
+        .with_arguments(json!({ "name": name }).as_object().unwrap().clone())
+}
+
+#[tokio::test]
+async fn test_tool_errors_take_the_right_shape() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        UserServer.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = Client.serve(client_transport).await?;
+
+    let result = client.call_tool(user("ada")).await?;
+    assert_eq!(result.is_error, Some(false));
+
+    let result = client.call_tool(user("busy")).await?;
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.content.len(), 2);
+    assert_eq!(result.content[1].as_text().unwrap().text, "1");
+    assert_eq!(result.structured_content, None);
+
+    match client.call_tool(user("bob")).await {
+        Err(ServiceError::McpError(error)) => {
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+            assert_eq!(error.message, "no user bob");
+        }
+        other => panic!("expected a JSON-RPC error, got {other:?}"),
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
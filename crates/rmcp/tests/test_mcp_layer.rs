@@ -0,0 +1,187 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_mcp_layer --features "client server"
+
+use std::sync::{Arc, Mutex};
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{
+        layer::{McpLayer, McpLayerExt, Next},
+        router::{Router, tool::ToolRoute},
+    },
+    model::{
+        CallToolRequestParams, CallToolResult, ClientInfo, ClientNotification, ClientRequest,
+        Content, ErrorCode, ServerResult, Tool,
+    },
+    service::{NotificationContext, RequestContext, ServiceError},
+};
+use serde_json::json;
+
+#[derive(Debug, Clone, Default)]
+struct EmptyServer;
+
+impl ServerHandler for EmptyServer {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// Records every request and notification it sees, tagged with its name.
+struct Recorder {
+    name: &'static str,
+    log: Log,
+}
+
+impl McpLayer for Recorder {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+        next: Next<'_>,
+    ) -> Result<ServerResult, ErrorData> {
+        let method = request.method().to_owned();
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} > {method}", self.name));
+        let result = next.run(request, context).await;
+        self.log.lock().unwrap().push(format!(
+            "{} < {method} {}",
+            self.name,
+            if result.is_ok() { "ok" } else { "err" }
+        ));
+        result
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+        next: Next<'_>,
+    ) -> Result<(), ErrorData> {
+        if let ClientNotification::InitializedNotification(_) = notification {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} initialized", self.name));
+        }
+        next.notify(notification, context).await
+    }
+}
+
+/// Rejects calls to `secret` and forces `shout` on every other tool call.
+struct Guard;
+
+impl McpLayer for Guard {
+    async fn handle_request(
+        &self,
+        mut request: ClientRequest,
+        context: RequestContext<RoleServer>,
+        next: Next<'_>,
+    ) -> Result<ServerResult, ErrorData> {
+        if let ClientRequest::CallToolRequest(call) = &mut request {
+            if call.params.name == "secret" {
+                return Err(ErrorData::invalid_request("forbidden", None));
+            }
+            call.params
+                .arguments
+                .get_or_insert_default()
+                .insert("shout".into(), json!(true));
+        }
+        next.run(request, context).await
+    }
+}
+
+fn echo_tool(name: &'static str) -> ToolRoute<EmptyServer> {
+    ToolRoute::new_dyn(
+        Tool::new(name, "echo arguments", Arc::new(Default::default())),
+        |ctx| {
+            Box::pin(async move {
+                let args = serde_json::Value::Object(ctx.arguments.unwrap_or_default());
+                Ok(CallToolResult::success(vec![Content::text(
+                    args.to_string(),
+                )]))
+            })
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_layers_wrap_requests_and_notifications() -> anyhow::Result<()> {
+    let log = Log::default();
+    let server = Router::new(EmptyServer)
+        .with_tool(echo_tool("echo"))
+        .with_tool(echo_tool("secret"))
+        .layer(Guard)
+        .layer(Recorder {
+            name: "inner",
+            log: log.clone(),
+        })
+        .layer(Recorder {
+            name: "outer",
+            log: log.clone(),
+        });
+
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler.serve(client_transport).await?;
+
+    let result = client
+        .call_tool(
+            CallToolRequestParams::new("echo")
+                .with_arguments(json!({ "text": "hi" }).as_object().unwrap().clone()),
+        )
+        .await?;
+    let echoed: serde_json::Value =
+        serde_json::from_str(&result.content[0].as_text().unwrap().text)?;
+    assert_eq!(echoed, json!({ "text": "hi", "shout": true }));
+
+    let err = client
+        .call_tool(CallToolRequestParams::new("secret"))
+        .await
+        .expect_err("guard should reject the call");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_REQUEST);
+    assert_eq!(err.message, "forbidden");
+
+    client.cancel().await?;
+
+    // Notifications are handled concurrently with requests, so check them apart.
+    let (notifications, requests): (Vec<_>, Vec<_>) = log
+        .lock()
+        .unwrap()
+        .clone()
+        .into_iter()
+        .partition(|entry| entry.ends_with("initialized"));
+    assert_eq!(notifications, ["outer initialized", "inner initialized"]);
+    assert_eq!(
+        requests,
+        [
+            "outer > initialize",
+            "inner > initialize",
+            "inner < initialize ok",
+            "outer < initialize ok",
+            "outer > tools/call",
+            "inner > tools/call",
+            "inner < tools/call ok",
+            "outer < tools/call ok",
+            "outer > tools/call",
+            "inner > tools/call",
+            "inner < tools/call err",
+            "outer < tools/call err",
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,169 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_pagination --features "client server macros"
+
+use std::sync::Arc;
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleServer, ServerHandler, ServiceExt,
+    handler::server::router::{
+        Router,
+        tool::{ToolRoute, ToolRouter},
+    },
+    model::{
+        CallToolResult, ClientInfo, ErrorCode, GetPromptRequestParams, GetPromptResult,
+        ListPromptsResult, ListToolsResult, PaginatedRequestParams, PromptMessage,
+        PromptMessageRole, ServerCapabilities, ServerInfo, Tool,
+    },
+    prompt, prompt_handler, prompt_router,
+    service::{RequestContext, RunningService, ServiceError},
+};
+
+#[derive(Debug, Clone, Default)]
+struct EmptyServer;
+
+impl ServerHandler for EmptyServer {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+fn tool(name: &str) -> ToolRoute<EmptyServer> {
+    ToolRoute::new_dyn(
+        Tool::new(name.to_string(), "", Arc::new(Default::default())),
+        |_ctx| Box::pin(async { Ok(CallToolResult::default()) }),
+    )
+}
+
+async fn connect<S: ServerHandler>(
+    server: S,
+) -> anyhow::Result<RunningService<rmcp::RoleClient, DummyClientHandler>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(DummyClientHandler.serve(client_transport).await?)
+}
+
+fn page(cursor: Option<String>) -> Option<PaginatedRequestParams> {
+    Some(PaginatedRequestParams::default().with_cursor(cursor))
+}
+
+#[tokio::test]
+async fn test_tools_are_listed_page_by_page() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let mut router = Router::new(EmptyServer);
+    router.tool_router = ["e", "a", "d", "c", "b"]
+        .into_iter()
+        .fold(ToolRouter::new(), |router, name| {
+            router.with_route(tool(name))
+        })
+        .with_page_size(2);
+    tokio::spawn(async move {
+        router.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler.serve(client_transport).await?;
+
+    let first = client.list_tools(None).await?;
+    let names: Vec<_> = first.tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, ["a", "b"]);
+    let second = client.list_tools(page(first.next_cursor.clone())).await?;
+    let names: Vec<_> = second.tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, ["c", "d"]);
+
+    let all = client.list_all_tools().await?;
+    let names: Vec<_> = all.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, ["a", "b", "c", "d", "e"]);
+
+    let err = client
+        .list_tools(page(Some("not-a-cursor".to_string())))
+        .await
+        .expect_err("forged cursor should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct PromptServer;
+
+#[prompt_router]
+impl PromptServer {
+    #[prompt(description = "The first prompt")]
+    async fn alpha(&self) -> Vec<PromptMessage> {
+        vec![PromptMessage::new_text(PromptMessageRole::User, "alpha")]
+    }
+
+    #[prompt(description = "The second prompt")]
+    async fn beta(&self) -> Vec<PromptMessage> {
+        vec![PromptMessage::new_text(PromptMessageRole::User, "beta")]
+    }
+
+    #[prompt(description = "The third prompt")]
+    async fn gamma(&self) -> Vec<PromptMessage> {
+        vec![PromptMessage::new_text(PromptMessageRole::User, "gamma")]
+    }
+}
+
+#[prompt_handler(router = Self::prompt_router().with_page_size(1))]
+impl ServerHandler for PromptServer {}
+
+#[tokio::test]
+async fn test_generated_list_prompts_follows_cursor() -> anyhow::Result<()> {
+    let client = connect(PromptServer).await?;
+
+    let first = client.list_prompts(None).await?;
+    assert_eq!(first.prompts.len(), 1);
+    assert!(first.next_cursor.is_some());
+
+    let all = client.list_all_prompts().await?;
+    let names: Vec<_> = all.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["alpha", "beta", "gamma"]);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+/// Hands out the same cursor forever.
+#[derive(Debug, Clone)]
+struct StuckServer;
+
+impl ServerHandler for StuckServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult {
+            meta: None,
+            next_cursor: Some("again".to_string()),
+            tools: vec![],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_list_all_stops_on_repeated_cursor() -> anyhow::Result<()> {
+    let client = connect(StuckServer).await?;
+    let err = client
+        .list_all_tools()
+        .await
+        .expect_err("a repeated cursor should not be followed");
+    assert!(matches!(err, ServiceError::UnexpectedResponse), "{err:?}");
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,131 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_panic_isolation --features "client server macros"
+
+use std::sync::{Arc, Mutex};
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::composite::CompositeServer,
+    model::{CallToolRequestParams, ClientInfo, ErrorCode},
+    service::{HandlerPanic, PanicPolicy, QuitReason, RunningService, ServiceError},
+    tool, tool_handler, tool_router,
+};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone)]
+struct PanickingServer {
+    policy: PanicPolicy,
+}
+
+#[tool_router]
+impl PanickingServer {
+    #[tool(description = "Panic")]
+    async fn boom(&self) -> String {
+        panic!("boom")
+    }
+
+    #[tool(description = "Answer")]
+    async fn answer(&self) -> String {
+        "42".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for PanickingServer {
+    fn panic_policy(&self) -> PanicPolicy {
+        self.policy.clone()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Client;
+
+impl ClientHandler for Client {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+async fn connect(
+    policy: PanicPolicy,
+) -> anyhow::Result<(
+    RunningService<RoleClient, Client>,
+    JoinHandle<anyhow::Result<QuitReason>>,
+)> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(async move {
+        Ok(PanickingServer { policy }
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?)
+    });
+    Ok((Client.serve(client_transport).await?, server))
+}
+
+fn assert_internal_error(result: Result<impl std::fmt::Debug, ServiceError>) {
+    match result {
+        Err(ServiceError::McpError(error)) => assert_eq!(error.code, ErrorCode::INTERNAL_ERROR),
+        other => panic!("expected an internal error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_panic_is_answered_and_reported() -> anyhow::Result<()> {
+    let panics = Arc::new(Mutex::new(Vec::<HandlerPanic>::new()));
+    let reported = panics.clone();
+    let (client, _server) = connect(
+        PanicPolicy::new().with_hook(move |panic| reported.lock().unwrap().push(panic.clone())),
+    )
+    .await?;
+
+    assert_internal_error(client.call_tool(CallToolRequestParams::new("boom")).await);
+    {
+        let panics = panics.lock().unwrap();
+        assert_eq!(panics.len(), 1);
+        assert_eq!(panics[0].message, "boom");
+    }
+
+    // the service keeps serving
+    let result = client
+        .call_tool(CallToolRequestParams::new("answer"))
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "42");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_panic_shuts_the_service_down() -> anyhow::Result<()> {
+    let (client, server) = connect(PanicPolicy::new().with_shutdown(true)).await?;
+
+    assert_internal_error(client.call_tool(CallToolRequestParams::new("boom")).await);
+    let quit_reason = tokio::time::timeout(std::time::Duration::from_secs(5), server).await???;
+    assert!(matches!(quit_reason, QuitReason::Panicked));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_composite_applies_its_own_policy() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    // the child's policy would keep serving
+    let composite = CompositeServer::new()
+        .mount(PanickingServer {
+            policy: PanicPolicy::new(),
+        })
+        .with_panic_policy(PanicPolicy::new().with_shutdown(true));
+    let server = tokio::spawn(async move {
+        anyhow::Ok(composite.serve(server_transport).await?.waiting().await?)
+    });
+    let client = Client.serve(client_transport).await?;
+
+    assert_internal_error(client.call_tool(CallToolRequestParams::new("boom")).await);
+    let quit_reason = tokio::time::timeout(std::time::Duration::from_secs(5), server).await???;
+    assert!(matches!(quit_reason, QuitReason::Panicked));
+
+    client.cancel().await?;
+    Ok(())
+}
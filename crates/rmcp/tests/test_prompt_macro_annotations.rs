@@ -38,6 +38,7 @@ struct ComplexArgs {
 }
 
 // Test basic prompt attribute generation
+#[cfg(not(feature = "macros-strict"))]
 #[prompt]
 async fn basic_prompt(_server: &TestServer) -> Vec<PromptMessage> {
     vec![PromptMessage::new_text(
@@ -47,6 +48,7 @@ async fn basic_prompt(_server: &TestServer) -> Vec<PromptMessage> {
 }
 
 // Test prompt with custom name
+#[cfg(not(feature = "macros-strict"))]
 #[prompt(name = "custom_name")]
 async fn named_prompt(_server: &TestServer) -> Vec<PromptMessage> {
     vec![PromptMessage::new_text(
@@ -95,7 +97,7 @@ async fn override_doc_prompt(_server: &TestServer) -> Vec<PromptMessage> {
 }
 
 // Test prompt with arguments
-#[prompt]
+#[prompt(description = "A prompt with arguments")]
 async fn args_prompt(_server: &TestServer, _args: Parameters<TestArgs>) -> Vec<PromptMessage> {
     vec![PromptMessage::new_text(
         PromptMessageRole::Assistant,
@@ -104,7 +106,7 @@ async fn args_prompt(_server: &TestServer, _args: Parameters<TestArgs>) -> Vec<P
 }
 
 // Test prompt with complex arguments
-#[prompt]
+#[prompt(description = "A prompt with complex arguments")]
 async fn complex_args_prompt(
     _server: &TestServer,
     _args: Parameters<ComplexArgs>,
@@ -117,7 +119,7 @@ async fn complex_args_prompt(
 }
 
 // Test sync prompt
-#[prompt]
+#[prompt(description = "A sync prompt")]
 fn sync_prompt(_server: &TestServer) -> Vec<PromptMessage> {
     vec![PromptMessage::new_text(
         PromptMessageRole::Assistant,
@@ -125,6 +127,8 @@ fn sync_prompt(_server: &TestServer) -> Vec<PromptMessage> {
     )]
 }
 
+// without a description, which `macros-strict` rejects
+#[cfg(not(feature = "macros-strict"))]
 #[test]
 fn test_basic_prompt_attr() {
     let attr = basic_prompt_prompt_attr();
@@ -133,6 +137,7 @@ fn test_basic_prompt_attr() {
     assert!(attr.arguments.is_none());
 }
 
+#[cfg(not(feature = "macros-strict"))]
 #[test]
 fn test_named_prompt_attr() {
     let attr = named_prompt_prompt_attr();
@@ -252,7 +257,9 @@ fn test_prompt_attr_function_type() {
     // Test that the generated function returns the correct type
     fn assert_prompt_attr_fn(_: impl Fn() -> Prompt) {}
 
+    #[cfg(not(feature = "macros-strict"))]
     assert_prompt_attr_fn(basic_prompt_prompt_attr);
+    #[cfg(not(feature = "macros-strict"))]
     assert_prompt_attr_fn(named_prompt_prompt_attr);
     assert_prompt_attr_fn(described_prompt_prompt_attr);
     assert_prompt_attr_fn(fully_custom_prompt_prompt_attr);
@@ -271,7 +278,7 @@ struct GenericServer<T: Send + Sync + 'static> {
 
 impl<T: Send + Sync + 'static> ServerHandler for GenericServer<T> {}
 
-#[prompt]
+#[prompt(description = "A generic prompt")]
 async fn generic_prompt<T: Send + Sync + 'static>(
     _server: &GenericServer<T>,
 ) -> Vec<PromptMessage> {
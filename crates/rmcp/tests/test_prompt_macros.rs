@@ -65,7 +65,11 @@ impl Server {
             ),
         ]
     }
+}
 
+// without a description, which `macros-strict` rejects
+#[cfg(not(feature = "macros-strict"))]
+impl Server {
     #[prompt]
     async fn empty_param(&self) -> Vec<PromptMessage> {
         vec![PromptMessage::new_text(
@@ -145,6 +149,7 @@ async fn test_prompt_macros() {
     assert_eq!(result[1].role, PromptMessageRole::Assistant);
 }
 
+#[cfg(not(feature = "macros-strict"))]
 #[tokio::test]
 async fn test_prompt_macros_with_empty_param() {
     let _attr = Server::empty_param_prompt_attr();
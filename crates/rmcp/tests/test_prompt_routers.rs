@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use futures::future::BoxFuture;
 use rmcp::{
     ServerHandler,
-    handler::server::wrapper::Parameters,
+    handler::server::{router::MergeError, wrapper::Parameters},
     model::{GetPromptResult, PromptMessage, PromptMessageRole},
 };
 
@@ -28,7 +28,7 @@ pub struct Sum {
 
 #[rmcp::prompt_router(router = "test_router")]
 impl<T> TestHandler<T> {
-    #[rmcp::prompt]
+    #[rmcp::prompt(description = "An async method")]
     async fn async_method(
         &self,
         Parameters(Request { fields }): Parameters<Request>,
@@ -40,7 +40,7 @@ impl<T> TestHandler<T> {
         )]
     }
 
-    #[rmcp::prompt]
+    #[rmcp::prompt(description = "A sync method")]
     fn sync_method(
         &self,
         Parameters(Request { fields }): Parameters<Request>,
@@ -53,7 +53,7 @@ impl<T> TestHandler<T> {
     }
 }
 
-#[rmcp::prompt]
+#[rmcp::prompt(description = "An async function")]
 async fn async_function(Parameters(Request { fields }): Parameters<Request>) -> Vec<PromptMessage> {
     drop(fields);
     vec![PromptMessage::new_text(
@@ -62,7 +62,7 @@ async fn async_function(Parameters(Request { fields }): Parameters<Request>) ->
     )]
 }
 
-#[rmcp::prompt]
+#[rmcp::prompt(description = "An async function returning a future")]
 fn async_function2<T>(_callee: &TestHandler<T>) -> BoxFuture<'_, GetPromptResult> {
     Box::pin(async move {
         GetPromptResult::new(vec![PromptMessage::new_text(
@@ -138,3 +138,19 @@ fn test_prompt_router_list_all_is_sorted() {
         "list_all() should return prompts sorted alphabetically by name"
     );
 }
+
+#[test]
+fn test_prompt_router_merge_with_prefix() {
+    let mut router = TestHandler::<()>::test_router();
+    router.merge_with_prefix("git_", TestHandler::<()>::test_router());
+    assert!(router.has_route("sync_method"));
+    assert!(router.has_route("git_sync_method"));
+    assert_eq!(router.list_all().len(), 4);
+
+    let err = router
+        .try_merge(TestHandler::<()>::test_router().with_disabled("async_method"))
+        .unwrap_err();
+    assert!(matches!(err, MergeError::Collision(_)), "{err:?}");
+    assert_eq!(router.list_all().len(), 4);
+    assert!(!router.is_disabled("async_method"));
+}
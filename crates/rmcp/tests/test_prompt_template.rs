@@ -0,0 +1,214 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_prompt_template --features "client server macros"
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{prompt::template::PromptTemplate, router::prompt::PromptRoute},
+    model::{
+        ClientInfo, ErrorCode, GetPromptRequestParams, GetPromptResult, ListPromptsResult,
+        PaginatedRequestParams, Prompt, PromptMessageContent, PromptMessageRole,
+        ReadResourceRequestParams, ReadResourceResult, ResourceContents, ServerCapabilities,
+        ServerInfo,
+    },
+    prompt, prompt_handler, prompt_router,
+    service::{RequestContext, RunningService, ServiceError},
+};
+use serde_json::json;
+
+const IMAGE: &str = "iVBORw0KGgo=";
+
+#[derive(Debug, Clone)]
+struct TemplateServer;
+
+#[prompt_router]
+impl TemplateServer {
+    /// A test prompt that accepts arguments
+    #[prompt(
+        name = "test_prompt_with_arguments",
+        template = "tests/test_prompt_template/greeting.md"
+    )]
+    fn greeting(&self) {}
+}
+
+fn review_route() -> PromptRoute<TemplateServer> {
+    let template = PromptTemplate::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test_prompt_template/review.md"
+    ))
+    .unwrap();
+    PromptRoute::from_template(
+        Prompt::new("review", Some("Review some code"), None),
+        template,
+    )
+}
+
+#[prompt_handler(router = Self::prompt_router().with_route(review_route()))]
+impl ServerHandler for TemplateServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_prompts()
+                .enable_resources()
+                .build(),
+        )
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let contents = match request.uri.as_str() {
+            "test://static-text" => {
+                ResourceContents::text("Resource content for prompt", "test://static-text")
+            }
+            "test://image" => ResourceContents::BlobResourceContents {
+                uri: "test://image".into(),
+                mime_type: Some("image/png".into()),
+                blob: IMAGE.into(),
+                meta: None,
+            },
+            uri => {
+                return Err(ErrorData::resource_not_found(
+                    format!("no resource {uri}"),
+                    None,
+                ));
+            }
+        };
+        Ok(ReadResourceResult::new(vec![contents]))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Client;
+
+impl ClientHandler for Client {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+async fn connect() -> anyhow::Result<RunningService<RoleClient, Client>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        TemplateServer
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    Ok(Client.serve(client_transport).await?)
+}
+
+fn params(name: &str, arguments: serde_json::Value) -> GetPromptRequestParams {
+    GetPromptRequestParams::new(name).with_arguments(arguments.as_object().unwrap().clone())
+}
+
+fn text(content: &PromptMessageContent) -> &str {
+    match content {
+        PromptMessageContent::Text { text } => text,
+        other => panic!("expected text, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_arguments_from_template() -> anyhow::Result<()> {
+    let client = connect().await?;
+    let prompts = client.list_all_prompts().await?;
+    let greeting = prompts
+        .iter()
+        .find(|prompt| prompt.name == "test_prompt_with_arguments")
+        .unwrap();
+    assert_eq!(
+        greeting.description.as_deref(),
+        Some("A test prompt that accepts arguments")
+    );
+    let arguments: Vec<_> = greeting
+        .arguments
+        .iter()
+        .flatten()
+        .map(|argument| (argument.name.as_str(), argument.required))
+        .collect();
+    assert_eq!(arguments, [("name", Some(true)), ("style", Some(false))]);
+
+    let result = client
+        .get_prompt(params(
+            "test_prompt_with_arguments",
+            json!({ "name": "Ada" }),
+        ))
+        .await?;
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.messages[0].role, PromptMessageRole::User);
+    assert_eq!(
+        text(&result.messages[0].content),
+        "Please greet Ada in a friendly style."
+    );
+
+    let result = client
+        .get_prompt(params(
+            "test_prompt_with_arguments",
+            json!({ "name": "Ada", "style": "formal" }),
+        ))
+        .await?;
+    assert_eq!(
+        text(&result.messages[0].content),
+        "Please greet Ada in a formal style."
+    );
+
+    let error = client
+        .get_prompt(params(
+            "test_prompt_with_arguments",
+            json!({ "style": "formal" }),
+        ))
+        .await
+        .unwrap_err();
+    let ServiceError::McpError(error) = error else {
+        panic!("expected an MCP error, got {error:?}");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(error.data, Some(json!({ "missing": ["name"] })));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_roles_and_references() -> anyhow::Result<()> {
+    let client = connect().await?;
+    let result = client
+        .get_prompt(params("review", json!({ "language": "Rust" })))
+        .await?;
+    assert_eq!(result.description.as_deref(), Some("Review some code"));
+    let messages = &result.messages;
+    assert_eq!(messages.len(), 5);
+    assert_eq!(text(&messages[0].content), "Review the Rust code below.");
+    match &messages[1].content {
+        PromptMessageContent::Resource { resource } => match &resource.resource {
+            ResourceContents::TextResourceContents { uri, text, .. } => {
+                assert_eq!(uri, "test://static-text");
+                assert_eq!(text, "Resource content for prompt");
+            }
+            other => panic!("expected text contents, got {other:?}"),
+        },
+        other => panic!("expected a resource, got {other:?}"),
+    }
+    assert_eq!(text(&messages[2].content), "Here is the diagram:");
+    match &messages[3].content {
+        PromptMessageContent::Image { image } => {
+            assert_eq!(image.data, IMAGE);
+            assert_eq!(image.mime_type, "image/png");
+        }
+        other => panic!("expected an image, got {other:?}"),
+    }
+    assert!(
+        messages[..4]
+            .iter()
+            .all(|message| message.role == PromptMessageRole::User)
+    );
+    assert_eq!(messages[4].role, PromptMessageRole::Assistant);
+    assert_eq!(text(&messages[4].content), "I will review the Rust code.");
+
+    client.cancel().await?;
+    Ok(())
+}
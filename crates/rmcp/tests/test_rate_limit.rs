@@ -0,0 +1,153 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_rate_limit --features "client server macros transport-streamable-http-server reqwest"
+
+use std::time::Duration;
+
+use rmcp::{
+    ClientHandler, ServiceExt,
+    handler::server::{
+        layer::McpLayerExt,
+        rate_limit::{RATE_LIMITED, RateLimit, RateLimitKey, RateLimiter},
+    },
+    model::{CallToolRequestParams, ClientInfo},
+    service::ServiceError,
+    tool, tool_router,
+    transport::streamable_http_server::{
+        StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, Default)]
+struct Calculator;
+
+#[tool_router(server_handler)]
+impl Calculator {
+    #[tool(description = "Add")]
+    fn sum(&self) -> String {
+        "3".to_string()
+    }
+
+    #[tool(description = "Subtract")]
+    fn sub(&self) -> String {
+        "1".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+fn sum() -> CallToolRequestParams {
+    CallToolRequestParams::new("sum")
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_layer_rejects_with_retry_hint() -> anyhow::Result<()> {
+    let limiter = RateLimiter::new(RateLimit::per_second(2), RateLimitKey::Tool);
+    let server = Calculator.layer(limiter);
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler.serve(client_transport).await?;
+
+    // only tools/call is keyed by tool, listing is never limited
+    for _ in 0..5 {
+        client.list_all_tools().await?;
+    }
+    client.call_tool(sum()).await?;
+    client.call_tool(sum()).await?;
+    let err = client
+        .call_tool(sum())
+        .await
+        .expect_err("third call should be limited");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, RATE_LIMITED);
+    let retry_after_ms = err.data.unwrap()["retryAfterMs"].as_u64().unwrap();
+    assert!(
+        retry_after_ms > 0 && retry_after_ms <= 500,
+        "{retry_after_ms}"
+    );
+
+    // each tool has its own bucket
+    client.call_tool(CallToolRequestParams::new("sub")).await?;
+
+    tokio::time::sleep(Duration::from_millis(retry_after_ms)).await;
+    client.call_tool(sum()).await?;
+
+    client.cancel().await?;
+    Ok(())
+}
+
+const INIT_BODY: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"1.0"}}}"#;
+const CALL_BODY: &str = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"sum"}}"#;
+
+#[tokio::test]
+async fn test_http_rejects_with_429() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let limiter = RateLimiter::new(RateLimit::per_minute(2), RateLimitKey::Session)
+        .with_methods(["tools/call"]);
+    let config = StreamableHttpServerConfig::default()
+        .with_stateful_mode(false)
+        .with_json_response(true)
+        .with_sse_keep_alive(None)
+        .with_cancellation_token(ct.child_token())
+        .with_rate_limiter(limiter);
+    let service: StreamableHttpService<Calculator, LocalSessionManager> =
+        StreamableHttpService::new(|| Ok(Calculator), Default::default(), config);
+
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = tcp_listener.local_addr()?;
+    tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let post = |body: &'static str| {
+        client
+            .post(format!("http://{addr}/mcp"))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .body(body)
+            .send()
+    };
+
+    // initialize is not in the limited methods
+    for _ in 0..3 {
+        assert_eq!(post(INIT_BODY).await?.status(), 200);
+    }
+    for _ in 0..2 {
+        let response = post(CALL_BODY).await?;
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await?;
+        assert_eq!(body["result"]["content"][0]["text"], "3");
+    }
+
+    let response = post(CALL_BODY).await?;
+    assert_eq!(response.status(), 429);
+    let retry_after: u64 = response
+        .headers()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .expect("Retry-After header");
+    assert!((1..=30).contains(&retry_after), "{retry_after}");
+
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,256 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_resource_macros --features "client server"
+#![allow(dead_code)]
+
+use rmcp::{
+    ClientHandler, ErrorData, ServerHandler, ServiceExt,
+    handler::server::{
+        resource::{ResourceUri, TemplateVariables},
+        router::{Router, resource::ResourceRouter},
+    },
+    model::{
+        AnnotateAble, ArgumentInfo, ClientInfo, CompleteRequestParams, CompletionContext,
+        ErrorCode, ReadResourceRequestParams, Reference, ResourceContents,
+    },
+    resource, resource_handler, resource_router, resource_template,
+    service::ServiceError,
+};
+
+#[derive(Debug, Clone)]
+pub struct FileServer {
+    resource_router: ResourceRouter<Self>,
+}
+
+impl Default for FileServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[resource_router]
+impl FileServer {
+    pub fn new() -> Self {
+        Self {
+            resource_router: Self::resource_router(),
+        }
+    }
+
+    /// The project readme
+    #[resource(uri = "file:///README.md", mime_type = "text/markdown")]
+    async fn readme(&self) -> String {
+        "# Hello".to_string()
+    }
+
+    #[resource(uri = "mem://config", name = "config")]
+    fn config(&self, ResourceUri(uri): ResourceUri) -> Result<ResourceContents, ErrorData> {
+        Ok(ResourceContents::text("debug = true", uri))
+    }
+
+    #[resource_template(uri_template = "users://{id}/profile", title = "User profile")]
+    async fn user_profile(&self, id: u64) -> Result<String, ErrorData> {
+        if id == 0 {
+            return Err(ErrorData::invalid_params("user 0 is reserved", None));
+        }
+        Ok(format!("user {id}"))
+    }
+
+    #[resource_template(uri_template = "files://{dir}/{name}")]
+    fn file(&self, dir: String, variables: TemplateVariables) -> String {
+        format!("{dir}:{}", variables.get("name").unwrap_or_default())
+    }
+}
+
+#[resource_handler(router = self.resource_router)]
+impl ServerHandler for FileServer {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler {}
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+fn text_of(contents: &ResourceContents) -> &str {
+    match contents {
+        ResourceContents::TextResourceContents { text, .. } => text,
+        _ => panic!("expected text contents"),
+    }
+}
+
+#[test]
+fn test_resource_attributes() {
+    let readme = FileServer::readme_resource_attr();
+    assert_eq!(readme.uri, "file:///README.md");
+    assert_eq!(readme.name, "readme");
+    assert_eq!(readme.description.as_deref(), Some("The project readme"));
+    assert_eq!(readme.mime_type.as_deref(), Some("text/markdown"));
+
+    let template = FileServer::user_profile_resource_template_attr();
+    assert_eq!(template.uri_template, "users://{id}/profile");
+    assert_eq!(template.title.as_deref(), Some("User profile"));
+}
+
+#[test]
+fn test_resource_router_contents() {
+    let router = FileServer::resource_router();
+    assert!(router.has_route("file:///README.md"));
+    assert!(router.has_route("mem://config"));
+    assert!(router.has_template("users://{id}/profile"));
+    assert!(router.can_read("users://42/profile"));
+    assert!(!router.can_read("users://42"));
+}
+
+#[test]
+fn test_resource_handler_get_info() {
+    let info = FileServer::new().get_info();
+    assert!(info.capabilities.resources.is_some());
+    assert!(info.capabilities.tools.is_none());
+}
+
+#[tokio::test]
+async fn test_read_resources_through_client() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+
+    let server_handle = tokio::spawn(async move {
+        FileServer::new()
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+
+    let client = DummyClientHandler::default()
+        .serve(client_transport)
+        .await?;
+
+    let resources = client.list_all_resources().await?;
+    let uris: Vec<_> = resources.iter().map(|r| r.uri.as_str()).collect();
+    assert_eq!(uris, ["file:///README.md", "mem://config"]);
+
+    let templates = client.list_all_resource_templates().await?;
+    assert_eq!(templates.len(), 2);
+    assert_eq!(templates[0].uri_template, "users://{id}/profile");
+
+    let result = client
+        .read_resource(ReadResourceRequestParams::new("file:///README.md"))
+        .await?;
+    assert_eq!(text_of(&result.contents[0]), "# Hello");
+
+    let result = client
+        .read_resource(ReadResourceRequestParams::new("mem://config"))
+        .await?;
+    assert_eq!(text_of(&result.contents[0]), "debug = true");
+
+    let result = client
+        .read_resource(ReadResourceRequestParams::new("users://42/profile"))
+        .await?;
+    assert_eq!(text_of(&result.contents[0]), "user 42");
+
+    let result = client
+        .read_resource(ReadResourceRequestParams::new("files://docs/guide.md"))
+        .await?;
+    assert_eq!(text_of(&result.contents[0]), "docs:guide.md");
+
+    let err = client
+        .read_resource(ReadResourceRequestParams::new("users://abc/profile"))
+        .await
+        .expect_err("non-numeric id should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    let err = client
+        .read_resource(ReadResourceRequestParams::new("unknown://thing"))
+        .await
+        .expect_err("unknown resource should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::RESOURCE_NOT_FOUND);
+
+    client.cancel().await?;
+    server_handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rfc6570_template_through_router() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+
+    let router = Router::new(FileServer::new()).with_resource_template((
+        rmcp::model::RawResourceTemplate::new("repo://{owner}/{+path}{?rev}", "repo_file")
+            .no_annotation(),
+        |variables: TemplateVariables| {
+            format!(
+                "{}:{}@{}",
+                variables.get("owner").unwrap_or_default(),
+                variables.get("path").unwrap_or_default(),
+                variables.get("rev").unwrap_or("HEAD"),
+            )
+        },
+    ));
+    let server_handle = tokio::spawn(async move {
+        router.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+
+    let client = DummyClientHandler::default()
+        .serve(client_transport)
+        .await?;
+
+    let result = client
+        .read_resource(ReadResourceRequestParams::new(
+            "repo://rust-lang/src/lib.rs?rev=v1",
+        ))
+        .await?;
+    assert_eq!(text_of(&result.contents[0]), "rust-lang:src/lib.rs@v1");
+
+    let result = client
+        .read_resource(ReadResourceRequestParams::new("repo://rust-lang/README.md"))
+        .await?;
+    assert_eq!(text_of(&result.contents[0]), "rust-lang:README.md@HEAD");
+
+    // the client rejects arguments that are not template variables before sending
+    let err = client
+        .complete_resource_argument("repo://{owner}/{+path}{?rev}", "branch", "ma", None)
+        .await
+        .expect_err("unknown variable should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    // and so does the server, for templates registered on the router
+    let err = client
+        .complete(
+            CompleteRequestParams::new(
+                Reference::for_resource("repo://{owner}/{+path}{?rev}"),
+                ArgumentInfo {
+                    name: "path".to_string(),
+                    value: "src/".to_string(),
+                },
+            )
+            .with_context(CompletionContext::with_arguments(
+                [("user".to_string(), "rust-lang".to_string())].into(),
+            )),
+        )
+        .await
+        .expect_err("unknown context argument should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    let completion = client
+        .complete_resource_argument("repo://{owner}/{+path}{?rev}", "rev", "v", None)
+        .await?;
+    assert!(completion.values.is_empty());
+
+    client.cancel().await?;
+    server_handle.await??;
+    Ok(())
+}
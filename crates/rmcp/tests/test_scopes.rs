@@ -0,0 +1,231 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_scopes --features "client server macros transport-streamable-http-server reqwest"
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{
+        layer::{McpLayer, McpLayerExt, Next},
+        scopes::{GrantedScopes, INSUFFICIENT_SCOPE, ScopeRequirements},
+    },
+    model::{
+        CallToolRequestParams, ClientInfo, ClientRequest, GetPromptRequestParams, GetPromptResult,
+        ListPromptsResult, PaginatedRequestParams, PromptMessage, PromptMessageRole, ServerResult,
+    },
+    prompt, prompt_handler, prompt_router,
+    service::{RequestContext, ServiceError},
+    tool, tool_handler, tool_router,
+    transport::streamable_http_server::{
+        StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, Default)]
+struct Files;
+
+#[tool_router]
+impl Files {
+    #[tool(description = "Read the files", scopes = ["files:read"])]
+    fn read(&self) -> String {
+        "contents".to_string()
+    }
+
+    #[tool(description = "Write the files", scopes = ["files:read", "files:write"])]
+    fn write(&self) -> String {
+        "written".to_string()
+    }
+
+    #[tool(description = "The status of the files")]
+    fn status(&self) -> String {
+        "ok".to_string()
+    }
+}
+
+#[prompt_router]
+impl Files {
+    #[prompt(description = "Summarize the files", scopes = ["files:read"])]
+    async fn summary(&self) -> Vec<PromptMessage> {
+        vec![PromptMessage::new_text(
+            PromptMessageRole::User,
+            "summarize the files",
+        )]
+    }
+}
+
+#[tool_handler(router = Self::tool_router().with_unauthorized_hidden())]
+#[prompt_handler]
+impl ServerHandler for Files {}
+
+/// Grants fixed scopes to every request, standing in for an auth layer.
+struct Grant(&'static str);
+
+impl McpLayer for Grant {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        mut context: RequestContext<RoleServer>,
+        next: Next<'_>,
+    ) -> Result<ServerResult, ErrorData> {
+        context
+            .extensions
+            .insert(GrantedScopes::from_space_separated(self.0));
+        next.run(request, context).await
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+#[tokio::test]
+async fn test_router_enforces_scopes_and_hides_tools() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        Files
+            .layer(Grant("files:read"))
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler.serve(client_transport).await?;
+
+    let tools = client.list_all_tools().await?;
+    let names: Vec<_> = tools.iter().map(|tool| tool.name.as_ref()).collect();
+    assert_eq!(names, ["read", "status"]);
+
+    let result = client.call_tool(CallToolRequestParams::new("read")).await?;
+    assert_eq!(
+        result.content[0].as_text().map(|t| t.text.as_str()),
+        Some("contents")
+    );
+    client
+        .get_prompt(GetPromptRequestParams::new("summary"))
+        .await?;
+
+    let err = client
+        .call_tool(CallToolRequestParams::new("write"))
+        .await
+        .expect_err("write needs files:write");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, INSUFFICIENT_SCOPE);
+    let data = err.data.unwrap();
+    assert_eq!(data["error"], "insufficient_scope");
+    assert_eq!(
+        data["requiredScopes"],
+        serde_json::json!(["files:read", "files:write"])
+    );
+    assert_eq!(data["missingScopes"], serde_json::json!(["files:write"]));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_grant_holds_no_scopes() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        Files.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler.serve(client_transport).await?;
+
+    let tools = client.list_all_tools().await?;
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "status");
+    client
+        .call_tool(CallToolRequestParams::new("status"))
+        .await?;
+
+    let err = client
+        .get_prompt(GetPromptRequestParams::new("summary"))
+        .await
+        .expect_err("summary needs files:read");
+    assert!(
+        matches!(&err, ServiceError::McpError(err) if err.code == INSUFFICIENT_SCOPE),
+        "{err:?}"
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
+
+const WRITE_BODY: &str =
+    r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"write"}}"#;
+
+#[tokio::test]
+async fn test_http_rejects_with_403() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let requirements = ScopeRequirements::new()
+        .with_tool_router(&Files::tool_router())
+        .with_prompt_router(&Files::prompt_router());
+    let config = StreamableHttpServerConfig::default()
+        .with_stateful_mode(false)
+        .with_json_response(true)
+        .with_sse_keep_alive(None)
+        .with_cancellation_token(ct.child_token())
+        .with_scope_requirements(requirements);
+    let service: StreamableHttpService<Files, LocalSessionManager> =
+        StreamableHttpService::new(|| Ok(Files), Default::default(), config);
+
+    // stands in for token verification: the scopes come from a header
+    let router =
+        axum::Router::new()
+            .nest_service("/mcp", service)
+            .layer(axum::middleware::from_fn(
+                |mut request: axum::extract::Request, next: axum::middleware::Next| async move {
+                    let scopes = request
+                        .headers()
+                        .get("x-scopes")
+                        .and_then(|v| v.to_str().ok())
+                        .map(GrantedScopes::from_space_separated)
+                        .unwrap_or_default();
+                    request.extensions_mut().insert(scopes);
+                    next.run(request).await
+                },
+            ));
+    let tcp_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = tcp_listener.local_addr()?;
+    tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let post = |scopes: &'static str| {
+        client
+            .post(format!("http://{addr}/mcp"))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .header("x-scopes", scopes)
+            .body(WRITE_BODY)
+            .send()
+    };
+
+    let response = post("files:read").await?;
+    assert_eq!(response.status(), 403);
+    assert_eq!(
+        response.headers()["www-authenticate"],
+        r#"Bearer error="insufficient_scope", scope="files:read files:write""#
+    );
+
+    let response = post("files:read files:write").await?;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await?;
+    assert_eq!(body["result"]["content"][0]["text"], "written");
+
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,155 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_shared_tool_registry --features "client server"
+
+use std::sync::Arc;
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::router::{Router, tool::ToolRoute, tool_registry::SharedToolRegistry},
+    model::{CallToolRequestParams, CallToolResult, ClientInfo, Content, ErrorCode, Tool},
+    service::{NotificationContext, RunningService, ServiceError},
+};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Default)]
+struct EmptyServer;
+
+impl ServerHandler for EmptyServer {}
+
+#[derive(Debug, Clone)]
+struct ListChangedRecorder {
+    tx: mpsc::UnboundedSender<()>,
+}
+
+impl ClientHandler for ListChangedRecorder {
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        let _ = self.tx.send(());
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+fn plugin_tool(name: &'static str) -> ToolRoute<EmptyServer> {
+    ToolRoute::new_dyn(
+        Tool::new(name, "a plugin tool", Arc::new(Default::default())),
+        move |_ctx| Box::pin(async move { Ok(CallToolResult::success(vec![Content::text(name)])) }),
+    )
+}
+
+async fn connect(
+    registry: &SharedToolRegistry<EmptyServer>,
+) -> anyhow::Result<(
+    RunningService<RoleClient, ListChangedRecorder>,
+    mpsc::UnboundedReceiver<()>,
+)> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let router = Router::new(EmptyServer).with_tool_registry(registry.clone());
+    tokio::spawn(async move {
+        router.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let (tx, rx) = mpsc::unbounded_channel();
+    let client = ListChangedRecorder { tx }.serve(client_transport).await?;
+    Ok((client, rx))
+}
+
+async fn wait_for_peers(registry: &SharedToolRegistry<EmptyServer>, count: usize) {
+    tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        while registry.peer_count() != count {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("peers should register");
+}
+
+#[tokio::test]
+async fn test_tools_added_at_runtime_reach_every_session() -> anyhow::Result<()> {
+    let registry = SharedToolRegistry::new();
+    let (alice, mut alice_rx) = connect(&registry).await?;
+    let (bob, mut bob_rx) = connect(&registry).await?;
+    wait_for_peers(&registry, 2).await;
+
+    assert!(alice.list_all_tools().await?.is_empty());
+
+    registry.add_route(plugin_tool("plugin_a"));
+    alice_rx.recv().await;
+    bob_rx.recv().await;
+
+    for client in [&alice, &bob] {
+        let tools = client.list_all_tools().await?;
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "plugin_a");
+        let result = client
+            .call_tool(CallToolRequestParams::new("plugin_a"))
+            .await?;
+        assert_eq!(
+            result.content[0].as_text().map(|t| t.text.as_str()),
+            Some("plugin_a")
+        );
+    }
+
+    registry.disable_route("plugin_a");
+    alice_rx.recv().await;
+    bob_rx.recv().await;
+    assert!(bob.list_all_tools().await?.is_empty());
+
+    registry.enable_route("plugin_a");
+    alice_rx.recv().await;
+    bob_rx.recv().await;
+
+    assert!(registry.remove_route("plugin_a"));
+    alice_rx.recv().await;
+    bob_rx.recv().await;
+    let err = bob
+        .call_tool(CallToolRequestParams::new("plugin_a"))
+        .await
+        .expect_err("removed tool should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    alice.cancel().await?;
+    bob.cancel().await?;
+    wait_for_peers(&registry, 0).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_router_tools_win_over_registry() -> anyhow::Result<()> {
+    let registry = SharedToolRegistry::new();
+    registry.add_routes([plugin_tool("shared"), plugin_tool("only_registry")]);
+
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let router = Router::new(EmptyServer)
+        .with_tool(ToolRoute::new_dyn(
+            Tool::new("shared", "local override", Arc::new(Default::default())),
+            |_ctx| Box::pin(async { Ok(CallToolResult::success(vec![Content::text("local")])) }),
+        ))
+        .with_tool_registry(registry.clone());
+    tokio::spawn(async move {
+        router.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let client = ListChangedRecorder { tx }.serve(client_transport).await?;
+
+    let tools = client.list_all_tools().await?;
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, ["only_registry", "shared"]);
+    assert_eq!(tools[1].description.as_deref(), Some("local override"));
+
+    let result = client
+        .call_tool(CallToolRequestParams::new("shared"))
+        .await?;
+    assert_eq!(
+        result.content[0].as_text().map(|t| t.text.as_str()),
+        Some("local")
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_subscription_manager --features "client server"
+
+use std::time::Duration;
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::{router::Router, subscription::SubscriptionManager},
+    model::{
+        ClientInfo, ResourceUpdatedNotificationParam, SubscribeRequestParams,
+        UnsubscribeRequestParams,
+    },
+    service::{NotificationContext, RunningService},
+};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Default)]
+struct EmptyServer;
+
+impl ServerHandler for EmptyServer {}
+
+#[derive(Debug, Clone)]
+struct UpdateRecorder {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl ClientHandler for UpdateRecorder {
+    async fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let _ = self.tx.send(params.uri);
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+async fn connect(
+    subscriptions: &SubscriptionManager,
+) -> anyhow::Result<(
+    RunningService<RoleClient, UpdateRecorder>,
+    mpsc::UnboundedReceiver<String>,
+)> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let router = Router::new(EmptyServer).with_subscriptions(subscriptions.clone());
+    tokio::spawn(async move {
+        router.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let (tx, rx) = mpsc::unbounded_channel();
+    let client = UpdateRecorder { tx }.serve(client_transport).await?;
+    Ok((client, rx))
+}
+
+#[tokio::test]
+async fn test_notify_updated_fans_out_to_subscribers() -> anyhow::Result<()> {
+    let subscriptions = SubscriptionManager::new();
+    let (alice, mut alice_rx) = connect(&subscriptions).await?;
+    let (bob, mut bob_rx) = connect(&subscriptions).await?;
+
+    let capabilities = alice.peer_info().unwrap().capabilities.resources.clone();
+    assert_eq!(capabilities.unwrap().subscribe, Some(true));
+
+    alice
+        .subscribe(SubscribeRequestParams::new("file:///a.txt"))
+        .await?;
+    bob.subscribe(SubscribeRequestParams::new("file:///a.txt"))
+        .await?;
+    bob.subscribe(SubscribeRequestParams::new("file:///b.txt"))
+        .await?;
+    assert_eq!(subscriptions.subscriber_count("file:///a.txt"), 2);
+    assert_eq!(
+        subscriptions.subscribed_uris(),
+        ["file:///a.txt", "file:///b.txt"]
+    );
+
+    assert_eq!(subscriptions.notify_updated("file:///a.txt").await, 2);
+    assert_eq!(alice_rx.recv().await.as_deref(), Some("file:///a.txt"));
+    assert_eq!(bob_rx.recv().await.as_deref(), Some("file:///a.txt"));
+
+    assert_eq!(subscriptions.notify_updated("file:///b.txt").await, 1);
+    assert_eq!(bob_rx.recv().await.as_deref(), Some("file:///b.txt"));
+    assert!(alice_rx.try_recv().is_err());
+
+    assert_eq!(subscriptions.notify_updated("file:///c.txt").await, 0);
+
+    bob.unsubscribe(UnsubscribeRequestParams::new("file:///a.txt"))
+        .await?;
+    assert_eq!(subscriptions.notify_updated("file:///a.txt").await, 1);
+    assert_eq!(alice_rx.recv().await.as_deref(), Some("file:///a.txt"));
+    assert_eq!(subscriptions.peer_count(), 2);
+
+    bob.unsubscribe(UnsubscribeRequestParams::new("file:///b.txt"))
+        .await?;
+    assert_eq!(subscriptions.peer_count(), 1);
+
+    alice.cancel().await?;
+    bob.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_disconnected_peers_are_dropped() -> anyhow::Result<()> {
+    let subscriptions = SubscriptionManager::new();
+    let (alice, _alice_rx) = connect(&subscriptions).await?;
+    let (bob, mut bob_rx) = connect(&subscriptions).await?;
+
+    alice
+        .subscribe(SubscribeRequestParams::new("file:///a.txt"))
+        .await?;
+    bob.subscribe(SubscribeRequestParams::new("file:///a.txt"))
+        .await?;
+    assert_eq!(subscriptions.subscriber_count("file:///a.txt"), 2);
+
+    alice.cancel().await?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while subscriptions.peer_count() != 1 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await?;
+
+    assert_eq!(subscriptions.notify_updated("file:///a.txt").await, 1);
+    assert_eq!(bob_rx.recv().await.as_deref(), Some("file:///a.txt"));
+
+    bob.cancel().await?;
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while subscriptions.peer_count() != 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await?;
+    Ok(())
+}
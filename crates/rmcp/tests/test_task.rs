@@ -75,3 +75,31 @@ async fn rejects_duplicate_operation_ids() {
         .expect_err("duplicate should fail");
     assert!(format!("{err}").contains("already running"));
 }
+
+#[tokio::test]
+async fn cancel_task_fires_the_task_token() {
+    let mut processor = OperationProcessor::new();
+    let descriptor = OperationDescriptor::new("slow", "dummy");
+    let ct = descriptor.ct.clone();
+    let future = Box::pin(async move {
+        // winds down on its own once cancelled
+        ct.cancelled().await;
+        Ok(Box::new(DummyTransport {
+            id: "slow".to_string(),
+            value: 0,
+        }) as Box<dyn OperationResultTransport>)
+    });
+    let ct = descriptor.ct.clone();
+    processor
+        .submit_operation(OperationMessage::new(descriptor, future))
+        .expect("submit operation");
+
+    assert!(processor.cancel_task("slow"));
+    assert!(ct.is_cancelled());
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    let results = processor.peek_completed();
+    assert_eq!(results.len(), 1, "only the cancellation is reported");
+    assert!(results[0].result.is_err());
+    assert_eq!(processor.running_task_count(), 0);
+}
@@ -0,0 +1,129 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_tool_flat_args --features "client server macros"
+
+use rmcp::{
+    ClientHandler, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    model::{CallToolRequestParams, ClientInfo, ErrorCode},
+    service::{Peer, RequestContext, RunningService, ServiceError},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+struct Calculator;
+
+#[tool_router]
+impl Calculator {
+    #[tool(description = "Add two numbers")]
+    async fn add(&self, #[arg(description = "The first number")] a: i64, b: Option<i64>) -> String {
+        (a + b.unwrap_or_default()).to_string()
+    }
+
+    #[tool(description = "Scale a list")]
+    fn scale(&self, mut values: Vec<f64>, #[arg(default)] factor: f64) -> String {
+        values.iter_mut().for_each(|value| *value *= factor);
+        format!("{values:?}")
+    }
+
+    #[tool(description = "Greet the caller")]
+    async fn greet(
+        &self,
+        peer: Peer<RoleServer>,
+        name: String,
+        context: RequestContext<RoleServer>,
+    ) -> String {
+        assert!(peer.peer_info().is_some());
+        format!("hello {name}, this is request {}", context.id)
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for Calculator {}
+
+#[derive(Debug, Clone, Default)]
+struct Client;
+
+impl ClientHandler for Client {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+#[test]
+fn test_flat_arguments_schema() {
+    let schema = serde_json::Value::Object((*Calculator::add_tool_attr().input_schema).clone());
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], json!(["a"]));
+    assert_eq!(schema["properties"]["a"]["description"], "The first number");
+    assert!(schema["properties"].get("b").is_some());
+
+    let schema = serde_json::Value::Object((*Calculator::scale_tool_attr().input_schema).clone());
+    assert_eq!(schema["required"], json!(["values"]));
+
+    let schema = serde_json::Value::Object((*Calculator::greet_tool_attr().input_schema).clone());
+    assert_eq!(schema["required"], json!(["name"]));
+    assert_eq!(schema["properties"].as_object().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_tools_keep_their_signature() {
+    assert_eq!(Calculator.add(1, Some(2)).await, "3");
+    assert_eq!(Calculator.scale(vec![1.0, 2.0], 3.0), "[3.0, 6.0]");
+}
+
+async fn connect() -> anyhow::Result<RunningService<RoleClient, Client>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = Calculator.serve(server_transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(Client.serve(client_transport).await?)
+}
+
+async fn call(
+    client: &RunningService<RoleClient, Client>,
+    name: &'static str,
+    arguments: serde_json::Value,
+) -> Result<String, ServiceError> {
+    let result = client
+        .call_tool(
+            CallToolRequestParams::new(name).with_arguments(arguments.as_object().unwrap().clone()),
+        )
+        .await?;
+    Ok(result.content[0].as_text().unwrap().text.clone())
+}
+
+#[tokio::test]
+async fn test_flat_arguments_are_deserialized() -> anyhow::Result<()> {
+    let client = connect().await?;
+
+    assert_eq!(call(&client, "add", json!({ "a": 1, "b": 2 })).await?, "3");
+    assert_eq!(call(&client, "add", json!({ "a": 1 })).await?, "1");
+    assert_eq!(
+        call(
+            &client,
+            "scale",
+            json!({ "values": [1.0, 2.0], "factor": 2.0 })
+        )
+        .await?,
+        "[2.0, 4.0]"
+    );
+    assert_eq!(
+        call(&client, "scale", json!({ "values": [1.0] })).await?,
+        "[0.0]"
+    );
+    assert!(
+        call(&client, "greet", json!({ "name": "ada" }))
+            .await?
+            .starts_with("hello ada, this is request")
+    );
+
+    match call(&client, "add", json!({ "b": 2 })).await {
+        Err(ServiceError::McpError(error)) => assert_eq!(error.code, ErrorCode::INVALID_PARAMS),
+        other => panic!("expected invalid params, got {other:?}"),
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
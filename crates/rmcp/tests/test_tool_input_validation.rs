@@ -0,0 +1,147 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_tool_input_validation --features "client server schema-validation"
+
+use std::sync::Arc;
+
+use rmcp::{
+    ClientHandler, ServerHandler, ServiceExt,
+    handler::server::router::{
+        Router,
+        tool::{ToolRoute, ToolRouter},
+    },
+    model::{CallToolRequestParams, CallToolResult, ClientInfo, Content, ErrorCode, Tool},
+    service::ServiceError,
+};
+use serde_json::json;
+
+#[derive(Debug, Clone, Default)]
+struct EmptyServer;
+
+impl ServerHandler for EmptyServer {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+fn repeat_tool() -> ToolRoute<EmptyServer> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "text": { "type": "string" },
+            "times": { "type": "integer", "minimum": 1 }
+        },
+        "required": ["text", "times"],
+        "additionalProperties": false
+    });
+    ToolRoute::new_dyn(
+        Tool::new(
+            "repeat",
+            "repeat a text",
+            Arc::new(schema.as_object().unwrap().clone()),
+        ),
+        |ctx| {
+            Box::pin(async move {
+                let args = ctx.arguments.unwrap_or_default();
+                let text = args["text"].as_str().unwrap_or_default();
+                let times = args["times"].as_u64().unwrap_or_default() as usize;
+                Ok(CallToolResult::success(vec![Content::text(
+                    text.repeat(times),
+                )]))
+            })
+        },
+    )
+}
+
+async fn call(
+    router: ToolRouter<EmptyServer>,
+    arguments: serde_json::Value,
+) -> anyhow::Result<Result<CallToolResult, ServiceError>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let mut server = Router::new(EmptyServer);
+    server.tool_router = router;
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler.serve(client_transport).await?;
+    let result = client
+        .call_tool(
+            CallToolRequestParams::new("repeat")
+                .with_arguments(arguments.as_object().unwrap().clone()),
+        )
+        .await;
+    client.cancel().await?;
+    Ok(result)
+}
+
+#[tokio::test]
+async fn test_invalid_arguments_are_rejected_with_violations() -> anyhow::Result<()> {
+    let router = ToolRouter::new()
+        .with_route(repeat_tool())
+        .with_input_validation();
+
+    let err = call(router, json!({ "times": 0, "extra": true }))
+        .await?
+        .expect_err("invalid arguments should be rejected");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    let violations = err.data.unwrap()["violations"].as_array().unwrap().clone();
+    let mut found: Vec<_> = violations
+        .iter()
+        .map(|v| {
+            (
+                v["pointer"].as_str().unwrap().to_string(),
+                v["keyword"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        [
+            ("".to_string(), "additionalProperties".to_string()),
+            ("".to_string(), "required".to_string()),
+            ("/times".to_string(), "minimum".to_string()),
+        ]
+    );
+    assert!(
+        violations
+            .iter()
+            .all(|v| !v["message"].as_str().unwrap().is_empty())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_valid_arguments_reach_the_handler() -> anyhow::Result<()> {
+    let router = ToolRouter::new()
+        .with_route(repeat_tool())
+        .with_input_validation();
+
+    let result = call(router, json!({ "text": "ab", "times": 2 })).await??;
+    assert_eq!(
+        result.content[0].as_text().map(|t| t.text.as_str()),
+        Some("abab")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_validation_is_opt_in() -> anyhow::Result<()> {
+    let router = ToolRouter::new().with_route(repeat_tool());
+
+    let result = call(router, json!({ "text": "ab", "times": "2" })).await??;
+    assert_eq!(
+        result.content[0].as_text().map(|t| t.text.as_str()),
+        Some("")
+    );
+    Ok(())
+}
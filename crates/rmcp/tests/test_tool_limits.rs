@@ -0,0 +1,225 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_tool_limits --features "client server macros"
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::router::tool::{BusyPolicy, ToolRoute},
+    model::{CallToolRequestParams, CallToolResult, ClientInfo, Tool},
+    service::RunningService,
+    tool, tool_handler, tool_router,
+};
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Default)]
+struct LimitedServer {
+    finished: Arc<AtomicBool>,
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+impl LimitedServer {
+    async fn occupy(&self, duration: Duration) {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(duration).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[tool_router(server_handler)]
+impl LimitedServer {
+    #[tool(description = "Outlive its timeout", timeout = "30s")]
+    async fn slow(&self) -> String {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        self.finished.store(true, Ordering::SeqCst);
+        "done".to_string()
+    }
+
+    #[tool(description = "Finish within its timeout", timeout = "30s")]
+    async fn quick(&self) -> String {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        "done".to_string()
+    }
+
+    #[tool(description = "Wait for a free slot", max_concurrency = 2)]
+    async fn queued(&self) -> String {
+        self.occupy(Duration::from_secs(1)).await;
+        "done".to_string()
+    }
+
+    #[tool(
+        description = "Reject calls while busy",
+        max_concurrency = 1,
+        on_busy = "reject"
+    )]
+    async fn exclusive(&self) -> String {
+        self.occupy(Duration::from_secs(1)).await;
+        "done".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct GenericServer<T>(std::marker::PhantomData<T>);
+
+#[tool_router]
+impl<T: Send + Sync + 'static> GenericServer<T> {
+    #[tool(
+        description = "Reject calls while busy",
+        max_concurrency = 1,
+        on_busy = "reject"
+    )]
+    async fn exclusive(&self) -> String {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        "done".to_string()
+    }
+}
+
+#[tool_handler]
+impl<T: Send + Sync + 'static> ServerHandler for GenericServer<T> {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+async fn connect(
+    server: impl ServerHandler,
+) -> anyhow::Result<RunningService<RoleClient, DummyClientHandler>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(DummyClientHandler.serve(client_transport).await?)
+}
+
+fn text(result: &CallToolResult) -> &str {
+    result.content[0]
+        .as_text()
+        .map(|t| t.text.as_str())
+        .unwrap_or_default()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timeout_aborts_the_handler() -> anyhow::Result<()> {
+    let server = LimitedServer::default();
+    let finished = server.finished.clone();
+    let client = connect(server).await?;
+
+    let start = Instant::now();
+    let result = client.call_tool(CallToolRequestParams::new("slow")).await?;
+    assert_eq!(start.elapsed().as_secs(), 30);
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(text(&result), "tool 'slow' timed out after 30s");
+
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert!(
+        !finished.load(Ordering::SeqCst),
+        "handler should be dropped"
+    );
+
+    let result = client
+        .call_tool(CallToolRequestParams::new("quick"))
+        .await?;
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(text(&result), "done");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_max_concurrency_queues_calls() -> anyhow::Result<()> {
+    let server = LimitedServer::default();
+    let peak = server.peak.clone();
+    let client = connect(server).await?;
+
+    let start = Instant::now();
+    let results = futures::future::try_join_all(
+        (0..5).map(|_| client.call_tool(CallToolRequestParams::new("queued"))),
+    )
+    .await?;
+    assert!(results.iter().all(|r| r.is_error == Some(false)));
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+    // five one-second calls, two at a time
+    assert_eq!(start.elapsed().as_secs(), 3);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_max_concurrency_rejects_when_busy() -> anyhow::Result<()> {
+    let client = connect(LimitedServer::default()).await?;
+
+    let (first, second) = tokio::join!(
+        client.call_tool(CallToolRequestParams::new("exclusive")),
+        client.call_tool(CallToolRequestParams::new("exclusive")),
+    );
+    let mut errors: Vec<_> = [first?, second?]
+        .into_iter()
+        .map(|r| r.is_error == Some(true))
+        .collect();
+    errors.sort();
+    assert_eq!(errors, [false, true]);
+
+    let result = client
+        .call_tool(CallToolRequestParams::new("exclusive"))
+        .await?;
+    assert_eq!(result.is_error, Some(false));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_max_concurrency_is_per_server_type() -> anyhow::Result<()> {
+    let bytes = connect(GenericServer::<u8>::default()).await?;
+    let words = connect(GenericServer::<u16>::default()).await?;
+
+    let (first, second) = tokio::join!(
+        bytes.call_tool(CallToolRequestParams::new("exclusive")),
+        words.call_tool(CallToolRequestParams::new("exclusive")),
+    );
+    assert_eq!(first?.is_error, Some(false));
+    assert_eq!(second?.is_error, Some(false));
+
+    bytes.cancel().await?;
+    words.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_route_builder_limits() {
+    let route = ToolRoute::<LimitedServer>::new_dyn(
+        Tool::new("dyn", "a dynamic tool", Arc::new(Default::default())),
+        |_ctx| Box::pin(async { Ok(CallToolResult::default()) }),
+    )
+    .with_timeout(Duration::from_secs(5))
+    .with_max_concurrency(3)
+    .with_busy_policy(BusyPolicy::Reject);
+    assert_eq!(route.timeout(), Some(Duration::from_secs(5)));
+    assert_eq!(route.max_concurrency(), Some(3));
+    assert_eq!(route.busy_policy(), BusyPolicy::Reject);
+
+    let tools = LimitedServer::tool_router();
+    let queued = tools.map.get("queued").unwrap();
+    assert_eq!(queued.max_concurrency(), Some(2));
+    assert_eq!(queued.busy_policy(), BusyPolicy::Queue);
+    assert_eq!(
+        tools.map.get("slow").unwrap().timeout(),
+        Some(Duration::from_secs(30))
+    );
+}
@@ -58,7 +58,11 @@ impl Server {
         drop(city);
         "rain".to_string()
     }
+}
 
+// without a description, which `macros-strict` rejects
+#[cfg(not(feature = "macros-strict"))]
+impl Server {
     #[tool]
     async fn empty_param(&self) {}
 }
@@ -118,6 +122,7 @@ async fn test_tool_macros() {
         .await;
 }
 
+#[cfg(not(feature = "macros-strict"))]
 #[tokio::test]
 async fn test_tool_macros_with_empty_param() {
     let _attr = Server::empty_param_tool_attr();
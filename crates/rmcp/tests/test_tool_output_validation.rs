@@ -0,0 +1,128 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_tool_output_validation --features "client server schema-validation"
+
+use std::sync::Arc;
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::router::{
+        Router,
+        tool::{ToolRoute, ToolRouter},
+    },
+    model::{CallToolRequestParams, CallToolResult, ClientInfo, ErrorCode, Tool},
+    service::{RunningService, ServiceError},
+};
+use serde_json::json;
+
+#[derive(Debug, Clone, Default)]
+struct EmptyServer;
+
+impl ServerHandler for EmptyServer {}
+
+#[derive(Debug, Clone, Default)]
+struct DummyClientHandler;
+
+impl ClientHandler for DummyClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+/// Echoes its arguments back as structured content, which lets each test pick
+/// whether the result matches the declared output schema.
+fn echo_tool() -> ToolRoute<EmptyServer> {
+    let output_schema = json!({
+        "type": "object",
+        "properties": {
+            "sum": { "type": "integer" }
+        },
+        "required": ["sum"]
+    });
+    ToolRoute::new_dyn(
+        Tool::new("echo", "echo arguments", Arc::new(Default::default()))
+            .with_raw_output_schema(Arc::new(output_schema.as_object().unwrap().clone())),
+        |ctx| {
+            Box::pin(async move {
+                let args = ctx.arguments.unwrap_or_default();
+                Ok(CallToolResult::structured(serde_json::Value::Object(args)))
+            })
+        },
+    )
+}
+
+async fn connect(
+    router: ToolRouter<EmptyServer>,
+) -> anyhow::Result<RunningService<RoleClient, DummyClientHandler>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let mut server = Router::new(EmptyServer);
+    server.tool_router = router;
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(DummyClientHandler.serve(client_transport).await?)
+}
+
+fn echo(arguments: serde_json::Value) -> CallToolRequestParams {
+    CallToolRequestParams::new("echo").with_arguments(arguments.as_object().unwrap().clone())
+}
+
+#[tokio::test]
+async fn test_server_replaces_mismatched_output_with_tool_error() -> anyhow::Result<()> {
+    let client = connect(
+        ToolRouter::new()
+            .with_route(echo_tool())
+            .with_output_validation(),
+    )
+    .await?;
+
+    let result = client.call_tool(echo(json!({ "sum": "3" }))).await?;
+    assert_eq!(result.is_error, Some(true));
+    assert!(result.structured_content.is_none());
+    let text = result.content[0].as_text().unwrap().text.as_str();
+    assert!(text.contains("'echo'"), "{text}");
+    assert!(text.contains("/sum"), "{text}");
+
+    let result = client.call_tool(echo(json!({ "sum": 3 }))).await?;
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(result.structured_content, Some(json!({ "sum": 3 })));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_rejects_mismatched_output() -> anyhow::Result<()> {
+    let client = connect(ToolRouter::new().with_route(echo_tool())).await?;
+    let tools = client.list_all_tools().await?;
+    let tool = &tools[0];
+
+    let err = client
+        .call_tool_validated(tool, echo(json!({ "total": 3 })))
+        .await
+        .expect_err("mismatched output should be rejected");
+    let ServiceError::OutputSchemaViolation { tool, violations } = err else {
+        panic!("expected output schema violation, got {err:?}");
+    };
+    assert_eq!(tool, "echo");
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].pointer, "");
+    assert_eq!(violations[0].keyword, "required");
+
+    let result = client
+        .call_tool_validated(&tools[0], echo(json!({ "sum": 3 })))
+        .await?;
+    assert_eq!(result.structured_content, Some(json!({ "sum": 3 })));
+
+    let err = client
+        .call_tool_validated(&tools[0], CallToolRequestParams::new("other"))
+        .await
+        .expect_err("the schema of another tool");
+    let ServiceError::McpError(err) = err else {
+        panic!("expected mcp error, got {err:?}");
+    };
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+
+    client.cancel().await?;
+    Ok(())
+}
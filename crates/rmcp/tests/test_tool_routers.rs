@@ -7,7 +7,11 @@ use std::{
 use futures::future::BoxFuture;
 use rmcp::{
     ServerHandler,
-    handler::server::{router::tool::ToolRouter, tool::CallToolHandler, wrapper::Parameters},
+    handler::server::{
+        router::{MergeError, tool::ToolRouter},
+        tool::CallToolHandler,
+        wrapper::Parameters,
+    },
 };
 
 #[derive(Debug, Default)]
@@ -29,7 +33,7 @@ pub struct Sum {
 
 #[rmcp::tool_router(router = test_router_1)]
 impl<T> TestHandler<T> {
-    #[rmcp::tool]
+    #[rmcp::tool(description = "An async method")]
     async fn async_method(&self, Parameters(Request { fields }): Parameters<Request>) {
         drop(fields)
     }
@@ -37,18 +41,18 @@ impl<T> TestHandler<T> {
 
 #[rmcp::tool_router(router = test_router_2)]
 impl<T> TestHandler<T> {
-    #[rmcp::tool]
+    #[rmcp::tool(description = "A sync method")]
     fn sync_method(&self, Parameters(Request { fields }): Parameters<Request>) {
         drop(fields)
     }
 }
 
-#[rmcp::tool]
+#[rmcp::tool(description = "An async function")]
 async fn async_function(Parameters(Request { fields }): Parameters<Request>) {
     drop(fields)
 }
 
-#[rmcp::tool]
+#[rmcp::tool(description = "An async function returning a future")]
 fn async_function2<T>(_callee: &TestHandler<T>) -> BoxFuture<'_, ()> {
     Box::pin(async move {})
 }
@@ -370,3 +374,59 @@ fn test_pre_init_disable_silent_but_correct() {
     assert!(router.enable_route("async_function"));
     assert_eq!(counter.load(Ordering::SeqCst), 1);
 }
+
+#[test]
+fn test_merge_with_prefix() {
+    let mut router = TestHandler::<()>::test_router_1();
+    router.merge_with_prefix("git_", build_router().with_disabled("sync_method"));
+
+    assert!(router.has_route("async_method"));
+    assert!(router.has_route("git_async_method"));
+    assert!(router.has_route("git_async_function"));
+    assert!(router.is_disabled("git_sync_method"));
+    assert_eq!(router.list_all().len(), 4);
+}
+
+#[test]
+fn test_merge_renamed() {
+    let mut router = ToolRouter::<TestHandler<()>>::new();
+    router.merge_renamed(TestHandler::<()>::test_router_2(), |name| {
+        name.replace('_', "-")
+    });
+    assert!(router.has_route("sync-method"));
+    assert!(!router.has_route("sync_method"));
+}
+
+#[test]
+fn test_try_merge_rejects_collisions() {
+    let mut router = build_router();
+    let err = router
+        .try_merge(TestHandler::<()>::test_router_1())
+        .expect_err("async_method is already registered");
+    assert_eq!(err, MergeError::Collision("async_method".to_string()));
+    assert_eq!(router.list_all().len(), 4);
+
+    router
+        .try_merge_with_prefix("other_", TestHandler::<()>::test_router_1())
+        .unwrap();
+    assert!(router.has_route("other_async_method"));
+
+    // two tools renamed onto the same name
+    let err = ToolRouter::<TestHandler<()>>::new()
+        .try_merge_renamed(build_router(), |_| "same".to_string())
+        .unwrap_err();
+    assert_eq!(err, MergeError::Collision("same".to_string()));
+}
+
+#[test]
+fn test_try_merge_validates_prefixed_names() {
+    let mut router = ToolRouter::<TestHandler<()>>::new();
+    let err = router
+        .try_merge_with_prefix("git tools/", TestHandler::<()>::test_router_1())
+        .unwrap_err();
+    assert_eq!(
+        err,
+        MergeError::InvalidName("git tools/async_method".to_string())
+    );
+    assert!(router.list_all().is_empty());
+}
@@ -0,0 +1,163 @@
+#![cfg(not(feature = "local"))]
+//cargo test --test test_tool_sets --features "client server macros"
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::router::tool::ToolRouter,
+    model::{CallToolRequestParams, ClientInfo},
+    service::{MaybeSend, RunningService},
+    tool_handler, tool_router,
+};
+use serde_json::json;
+
+/// Tools that need nothing from the server.
+#[tool_router]
+mod math {
+    use rmcp::{handler::server::wrapper::Parameters, schemars::JsonSchema};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, JsonSchema)]
+    pub struct NegateParams {
+        value: i64,
+    }
+
+    #[tool(description = "Add two numbers")]
+    pub async fn add(a: i64, b: i64) -> String {
+        (a + b).to_string()
+    }
+
+    #[tool(description = "Negate a number", timeout = "1s")]
+    pub fn negate(Parameters(NegateParams { value }): Parameters<NegateParams>) -> String {
+        (-value).to_string()
+    }
+}
+
+/// Tools shared by every server that can greet.
+#[tool_router(router = greeting_router)]
+trait Greeter {
+    fn greeting(&self) -> &'static str;
+
+    #[tool(description = "Greet someone")]
+    async fn greet(&self, name: String) -> String {
+        format!("{}, {name}", self.greeting())
+    }
+
+    #[tool(description = "Say goodbye")]
+    fn farewell(&self) -> String {
+        "bye".to_string()
+    }
+}
+
+/// Every greeter gets the greeting tools and the math tools.
+fn router<S: Greeter + MaybeSend + 'static>() -> ToolRouter<S> {
+    S::greeting_router() + math::tool_router()
+}
+
+#[derive(Debug, Clone)]
+struct English;
+
+impl Greeter for English {
+    fn greeting(&self) -> &'static str {
+        "Hello"
+    }
+}
+
+#[tool_handler(router = router::<Self>())]
+impl ServerHandler for English {}
+
+#[derive(Debug, Clone)]
+struct French;
+
+impl Greeter for French {
+    fn greeting(&self) -> &'static str {
+        "Bonjour"
+    }
+
+    fn farewell(&self) -> String {
+        "au revoir".to_string()
+    }
+}
+
+#[tool_handler(router = router::<Self>())]
+impl ServerHandler for French {}
+
+#[derive(Debug, Clone, Default)]
+struct Client;
+
+impl ClientHandler for Client {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+async fn connect<S: ServerHandler>(
+    server: S,
+) -> anyhow::Result<RunningService<RoleClient, Client>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(Client.serve(client_transport).await?)
+}
+
+async fn call(
+    client: &RunningService<RoleClient, Client>,
+    name: &'static str,
+    arguments: serde_json::Value,
+) -> anyhow::Result<String> {
+    let result = client
+        .call_tool(
+            CallToolRequestParams::new(name).with_arguments(arguments.as_object().unwrap().clone()),
+        )
+        .await?;
+    Ok(result.content[0].as_text().unwrap().text.clone())
+}
+
+#[test]
+fn test_module_router() {
+    let router = math::tool_router::<English>();
+    let mut names: Vec<_> = router
+        .list_all()
+        .into_iter()
+        .map(|tool| tool.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, ["add", "negate"]);
+    assert_eq!(
+        router.map.get("negate").unwrap().timeout(),
+        Some(std::time::Duration::from_secs(1))
+    );
+}
+
+#[tokio::test]
+async fn test_shared_tool_sets() -> anyhow::Result<()> {
+    let english = connect(English).await?;
+    let mut names: Vec<_> = english
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["add", "farewell", "greet", "negate"]);
+    assert_eq!(call(&english, "add", json!({ "a": 1, "b": 2 })).await?, "3");
+    assert_eq!(call(&english, "negate", json!({ "value": 4 })).await?, "-4");
+    assert_eq!(
+        call(&english, "greet", json!({ "name": "Ada" })).await?,
+        "Hello, Ada"
+    );
+    assert_eq!(call(&english, "farewell", json!({})).await?, "bye");
+
+    let french = connect(French).await?;
+    assert_eq!(
+        call(&french, "greet", json!({ "name": "Ada" })).await?,
+        "Bonjour, Ada"
+    );
+    // overriding a tool method replaces the tool's behaviour
+    assert_eq!(call(&french, "farewell", json!({})).await?, "au revoir");
+
+    english.cancel().await?;
+    french.cancel().await?;
+    Ok(())
+}
@@ -2,14 +2,14 @@ use std::{
     io,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use futures::Stream;
 use rmcp::{
-    ErrorData as McpError, RoleServer, ServerHandler, model::*, service::RequestContext, tool,
+    ErrorData as McpError, ServerHandler, handler::server::progress::Progress, model::*, tool,
     tool_handler, tool_router,
 };
-use serde_json::json;
 use tokio_stream::StreamExt;
 use tracing::debug;
 
@@ -65,45 +65,21 @@ impl ProgressDemo {
         }
     }
     #[tool(description = "Process data stream with progress updates")]
-    async fn stream_processor(
-        &self,
-        ctx: RequestContext<RoleServer>,
-    ) -> Result<CallToolResult, McpError> {
+    async fn stream_processor(&self, progress: Progress) -> Result<CallToolResult, McpError> {
         let mut counter = 0;
+        // report every chunk instead of at most one every 100ms
+        let progress = progress.with_min_interval(Duration::ZERO);
 
         let mut data_source = self.data_source.clone();
-        loop {
-            let chunk = data_source.next().await;
-            if chunk.is_none() {
-                break;
-            }
-
-            let chunk = chunk.unwrap().unwrap();
+        while let Some(chunk) = data_source.next().await {
+            let chunk = chunk.map_err(|e| McpError::internal_error(e.to_string(), None))?;
             let chunk_str = String::from_utf8_lossy(&chunk);
             counter += 1;
-            // create progress notification param
-            let progress_param = ProgressNotificationParam {
-                progress_token: ProgressToken(NumberOrString::Number(counter)),
-                progress: counter as f64,
-                total: None,
-                message: Some(chunk_str.to_string()),
-            };
-
-            match ctx.peer.notify_progress(progress_param).await {
-                Ok(_) => {
-                    debug!("Processed record: {}", chunk_str);
-                }
-                Err(e) => {
-                    return Err(McpError::internal_error(
-                        format!("Failed to notify progress: {}", e),
-                        Some(json!({
-                            "record": chunk_str,
-                            "progress": counter,
-                            "error": e.to_string()
-                        })),
-                    ));
-                }
-            }
+            // does nothing unless the client asked for progress
+            progress
+                .report(counter as f64, None, Some(chunk_str.to_string()))
+                .await;
+            debug!("Processed record: {}", chunk_str);
         }
 
         Ok(CallToolResult::success(vec![Content::text(format!(